@@ -0,0 +1,206 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dle_encoder::{DleEncoder, FragmentRole, DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+const PAYLOAD_LEN: usize = 1024;
+
+/// Payload without any bytes which need to be escaped
+fn escape_free_payload() -> Vec<u8> {
+    (0..PAYLOAD_LEN)
+        .map(|idx| 0x20 + (idx % 0x5f) as u8)
+        .collect()
+}
+
+/// Payload with long runs of plain bytes between bytes which need to be escaped. This is
+/// the case the `memchr` feature speeds up when decoding in the escaped mode
+fn escape_sparse_payload() -> Vec<u8> {
+    (0..PAYLOAD_LEN)
+        .map(|idx| if idx % 128 == 127 { 0x02 } else { 0x41 })
+        .collect()
+}
+
+/// Payload where roughly every fourth byte needs to be escaped
+fn escape_heavy_payload() -> Vec<u8> {
+    (0..PAYLOAD_LEN)
+        .map(|idx| if idx % 4 == 0 { 0x02 } else { 0x41 })
+        .collect()
+}
+
+/// Escaped encoding with the previous branch order of the encoder, where plain bytes were
+/// only copied after comparing them with DLE, STX and ETX. Used as a reference for the
+/// encoder which handles plain bytes with a single set lookup.
+fn reference_encode_escaped(source_stream: &[u8], dest_stream: &mut [u8]) -> usize {
+    let mut encoded_idx = 0;
+    dest_stream[encoded_idx] = STX_CHAR;
+    encoded_idx += 1;
+    for &byte in source_stream {
+        if byte == DLE_CHAR {
+            dest_stream[encoded_idx] = DLE_CHAR;
+            dest_stream[encoded_idx + 1] = DLE_CHAR;
+            encoded_idx += 2;
+        } else if byte == STX_CHAR || byte == ETX_CHAR {
+            dest_stream[encoded_idx] = DLE_CHAR;
+            dest_stream[encoded_idx + 1] = byte + 0x40;
+            encoded_idx += 2;
+        } else {
+            dest_stream[encoded_idx] = byte;
+            encoded_idx += 1;
+        }
+    }
+    dest_stream[encoded_idx] = ETX_CHAR;
+    encoded_idx + 1
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for escape_stx_etx in [true, false] {
+        let mode = if escape_stx_etx {
+            "escaped"
+        } else {
+            "non_escaped"
+        };
+        let dle_encoder = DleEncoder {
+            escape_stx_etx,
+            ..Default::default()
+        };
+        for (name, payload) in [
+            ("escape_free", escape_free_payload()),
+            ("escape_heavy", escape_heavy_payload()),
+        ] {
+            let mut encoded = vec![0; PAYLOAD_LEN * 2 + 4];
+            group.bench_function(format!("{}_{}", mode, name), |b| {
+                b.iter(|| dle_encoder.encode(black_box(&payload), &mut encoded))
+            });
+            if escape_stx_etx {
+                let mut reference = vec![0; PAYLOAD_LEN * 2 + 4];
+                let reference_len = reference_encode_escaped(&payload, &mut reference);
+                let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+                assert_eq!(encoded[..encoded_len], reference[..reference_len]);
+                group.bench_function(format!("{}_{}_reference", mode, name), |b| {
+                    b.iter(|| reference_encode_escaped(black_box(&payload), &mut reference))
+                });
+            }
+        }
+    }
+    group.finish();
+}
+
+/// CRC-16/CCITT-FALSE checksum, calculated in a separate pass before encoding
+fn reference_crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0xffff;
+    for &byte in bytes {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Two pass approach which calculates the CRC first and encodes the payload and the CRC
+/// afterwards
+fn reference_encode_crc(
+    dle_encoder: &DleEncoder,
+    source_stream: &[u8],
+    dest_stream: &mut [u8],
+) -> usize {
+    let crc = reference_crc16(source_stream);
+    let first_len = dle_encoder
+        .encode_fragment(source_stream, FragmentRole::First, dest_stream)
+        .unwrap();
+    first_len
+        + dle_encoder
+            .encode_fragment(
+                &crc.to_be_bytes(),
+                FragmentRole::Last,
+                &mut dest_stream[first_len..],
+            )
+            .unwrap()
+}
+
+fn bench_encode_crc(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_crc");
+    let dle_encoder = DleEncoder::default();
+    for (name, payload) in [
+        ("escape_free", escape_free_payload()),
+        ("escape_heavy", escape_heavy_payload()),
+    ] {
+        let mut encoded = vec![0; PAYLOAD_LEN * 2 + 8];
+        let mut reference = vec![0; PAYLOAD_LEN * 2 + 8];
+        let encoded_len = dle_encoder
+            .encode_crc_single_pass(&payload, &mut encoded)
+            .unwrap();
+        let reference_len = reference_encode_crc(&dle_encoder, &payload, &mut reference);
+        assert_eq!(encoded[..encoded_len], reference[..reference_len]);
+        group.bench_function(format!("{}_single_pass", name), |b| {
+            b.iter(|| dle_encoder.encode_crc_single_pass(black_box(&payload), &mut encoded))
+        });
+        group.bench_function(format!("{}_two_pass", name), |b| {
+            b.iter(|| reference_encode_crc(&dle_encoder, black_box(&payload), &mut reference))
+        });
+    }
+    group.finish();
+}
+
+fn bench_encode_one(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_one");
+    let dle_encoder = DleEncoder::default();
+    let mut encoded = [0; 8];
+    // A plain byte and a byte which needs to be escaped
+    for (name, byte) in [("plain", 0x41), ("escaped", STX_CHAR)] {
+        group.bench_function(format!("{}_general", name), |b| {
+            b.iter(|| dle_encoder.encode(black_box(&[byte]), &mut encoded))
+        });
+        group.bench_function(format!("{}_single", name), |b| {
+            b.iter(|| dle_encoder.encode_one(black_box(byte), &mut encoded))
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for escape_stx_etx in [true, false] {
+        let mode = if escape_stx_etx {
+            "escaped"
+        } else {
+            "non_escaped"
+        };
+        let dle_encoder = DleEncoder {
+            escape_stx_etx,
+            ..Default::default()
+        };
+        for (name, payload) in [
+            ("escape_free", escape_free_payload()),
+            ("escape_sparse", escape_sparse_payload()),
+            ("escape_heavy", escape_heavy_payload()),
+        ] {
+            let mut encoded = vec![0; PAYLOAD_LEN * 2 + 4];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let mut decoded = vec![0; PAYLOAD_LEN];
+            group.bench_function(format!("{}_{}", mode, name), |b| {
+                b.iter(|| {
+                    let mut read_len = 0;
+                    dle_encoder.decode(
+                        black_box(&encoded[..encoded_len]),
+                        &mut decoded,
+                        &mut read_len,
+                    )
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_encode,
+    bench_encode_one,
+    bench_encode_crc,
+    bench_decode
+);
+criterion_main!(benches);
@@ -0,0 +1,490 @@
+//! Encoding and decoding one byte at a time, for example from interrupt handlers.
+
+use crate::{DecodeErrorKind, DleEncoder, DleError, DLE_CHAR, STX_CHAR};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum EncodePhase {
+    /// Writing the start marker, the index of the next marker byte is stored
+    Start(usize),
+    Body,
+    /// Writing the end marker, the index of the next marker byte is stored
+    End(usize),
+    Done,
+}
+
+/// Encoder which returns the encoded stream one byte at a time. This matches how hardware
+/// FIFOs are fed from a transmit interrupt. The encoder tracks its own position, so the same
+/// source stream must be passed to every call of [ByteAtATimeEncoder::next_byte] until the
+/// frame is complete.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{ByteAtATimeEncoder, DleEncoder};
+///
+/// let mut byte_encoder = ByteAtATimeEncoder::new(DleEncoder::default());
+/// let source: [u8; 2] = [0x01, 0x02];
+/// let mut transmitted = Vec::new();
+/// while let Some(byte) = byte_encoder.next_byte(&source) {
+///     transmitted.push(byte);
+/// }
+/// assert_eq!(transmitted, [0x02, 0x01, 0x10, 0x42, 0x03]);
+/// ```
+#[derive(Copy, Clone)]
+pub struct ByteAtATimeEncoder {
+    /// The encoder configuration used to encode frames
+    pub encoder: DleEncoder,
+    phase: EncodePhase,
+    source_idx: usize,
+    /// Second byte of a DLE pair which was not returned yet
+    pending: Option<u8>,
+}
+
+impl ByteAtATimeEncoder {
+    /// Create a new byte at a time encoder using the given encoder configuration
+    pub fn new(encoder: DleEncoder) -> ByteAtATimeEncoder {
+        ByteAtATimeEncoder {
+            encoder,
+            phase: EncodePhase::Start(0),
+            source_idx: 0,
+            pending: None,
+        }
+    }
+
+    /// Returns the next encoded byte to transmit, or [None] if the frame is complete.
+    /// Framing characters are added as configured.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode, which must be the same for all calls
+    ///   belonging to one frame
+    pub fn next_byte(&mut self, source_stream: &[u8]) -> Option<u8> {
+        if let Some(byte) = self.pending.take() {
+            return Some(byte);
+        }
+        match self.phase {
+            EncodePhase::Start(idx) => match self.start_marker().get(idx) {
+                Some(&byte) => {
+                    self.phase = EncodePhase::Start(idx + 1);
+                    Some(byte)
+                }
+                None => {
+                    self.phase = EncodePhase::Body;
+                    self.next_byte(source_stream)
+                }
+            },
+            EncodePhase::Body => match source_stream.get(self.source_idx) {
+                Some(&byte) => {
+                    self.source_idx += 1;
+                    let (encoded, encoded_len) = self.encoder.encode_byte(byte);
+                    if encoded_len == 2 {
+                        self.pending = Some(encoded[1]);
+                    }
+                    Some(encoded[0])
+                }
+                None => {
+                    self.phase = EncodePhase::End(0);
+                    self.next_byte(source_stream)
+                }
+            },
+            EncodePhase::End(idx) => match self.end_marker().get(idx) {
+                Some(&byte) => {
+                    self.phase = EncodePhase::End(idx + 1);
+                    Some(byte)
+                }
+                None => {
+                    self.phase = EncodePhase::Done;
+                    None
+                }
+            },
+            EncodePhase::Done => None,
+        }
+    }
+
+    /// Check whether all bytes of the current frame were returned
+    pub fn is_done(&self) -> bool {
+        self.phase == EncodePhase::Done
+    }
+
+    /// Reset the encoder to start encoding a new frame
+    pub fn reset(&mut self) {
+        self.phase = EncodePhase::Start(0);
+        self.source_idx = 0;
+        self.pending = None;
+    }
+
+    fn start_marker(&self) -> &'static [u8] {
+        if self.encoder.add_stx_etx {
+            self.encoder.start_marker()
+        } else {
+            &[]
+        }
+    }
+
+    fn end_marker(&self) -> &'static [u8] {
+        if self.encoder.add_stx_etx {
+            self.encoder.end_marker()
+        } else {
+            &[]
+        }
+    }
+}
+
+/// Result of pushing a byte into a [ByteAtATimeDecoder]
+#[derive(Debug, PartialEq)]
+pub enum DecodePush {
+    /// More bytes are required to complete the frame
+    More,
+    /// A frame was completed. The decoded payload with the given length is available via
+    /// [ByteAtATimeDecoder::frame] until the next byte is pushed
+    Frame(usize),
+    /// The current frame is erroneous and was discarded. Decoding errors contain the offset
+    /// of the offending byte relative to the start of the frame. [DleError::FrameTooLarge]
+    /// is returned if the payload exceeds the capacity of the decoder
+    Error(DleError),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum DecodePhase {
+    /// Waiting for the start of a frame
+    Idle,
+    /// Waiting for the STX of the start marker in the non-escaped mode
+    IdleDle,
+    InFrame,
+    /// The previous byte inside the frame was a DLE character
+    Escape,
+}
+
+/// Decoder which is fed one byte at a time, for example from a receive interrupt. It keeps
+/// the escape state across calls and reports exactly when a frame is complete. The decoded
+/// payload is stored in an internal buffer with the capacity `N`, so no allocator is
+/// required. Bytes outside of frames are discarded.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{ByteAtATimeDecoder, DecodePush, DleEncoder};
+///
+/// let mut byte_decoder = ByteAtATimeDecoder::<16>::new(DleEncoder::default());
+/// assert_eq!(byte_decoder.push_byte(0x02), DecodePush::More);
+/// assert_eq!(byte_decoder.push_byte(0x10), DecodePush::More);
+/// assert_eq!(byte_decoder.push_byte(0x42), DecodePush::More);
+/// assert_eq!(byte_decoder.push_byte(0x03), DecodePush::Frame(1));
+/// assert_eq!(byte_decoder.frame(), &[0x02]);
+/// ```
+#[derive(Clone)]
+pub struct ByteAtATimeDecoder<const N: usize> {
+    /// The encoder configuration used to decode frames
+    pub encoder: DleEncoder,
+    phase: DecodePhase,
+    buffer: [u8; N],
+    decoded_len: usize,
+    /// Number of bytes of the current frame, including the start marker
+    frame_offset: usize,
+}
+
+impl<const N: usize> ByteAtATimeDecoder<N> {
+    /// Create a new byte at a time decoder using the given encoder configuration
+    pub fn new(encoder: DleEncoder) -> ByteAtATimeDecoder<N> {
+        ByteAtATimeDecoder {
+            encoder,
+            phase: DecodePhase::Idle,
+            buffer: [0; N],
+            decoded_len: 0,
+            frame_offset: 0,
+        }
+    }
+
+    /// Push a received byte into the decoder
+    pub fn push_byte(&mut self, byte: u8) -> DecodePush {
+        let offset = self.frame_offset;
+        // Saturates instead of overflowing while idle bytes between frames are received
+        self.frame_offset = self.frame_offset.saturating_add(1);
+        match self.phase {
+            DecodePhase::Idle => {
+                if self.encoder.escape_stx_etx && byte == STX_CHAR {
+                    self.start_frame(1);
+                } else if !self.encoder.escape_stx_etx && byte == DLE_CHAR {
+                    self.phase = DecodePhase::IdleDle;
+                }
+                DecodePush::More
+            }
+            DecodePhase::IdleDle => {
+                if byte == STX_CHAR {
+                    self.start_frame(2);
+                } else if byte != DLE_CHAR {
+                    self.phase = DecodePhase::Idle;
+                }
+                DecodePush::More
+            }
+            DecodePhase::InFrame => {
+                if byte == DLE_CHAR {
+                    self.phase = DecodePhase::Escape;
+                    DecodePush::More
+                } else if self.encoder.is_raw_control(byte) {
+                    self.fail(DecodeErrorKind::RawControlChar, offset)
+                } else if !self.encoder.escape_stx_etx {
+                    self.store(byte)
+                } else if self.encoder.is_bare_terminator(byte) {
+                    self.end_frame(byte)
+                } else if byte == STX_CHAR {
+                    // The STX starts a new frame
+                    self.start_frame(1);
+                    DecodePush::Error(DleError::decoding(DecodeErrorKind::UnexpectedStart, offset))
+                } else {
+                    self.store(byte)
+                }
+            }
+            DecodePhase::Escape => {
+                self.phase = DecodePhase::InFrame;
+                if self.encoder.is_resync_marker(byte) {
+                    DecodePush::More
+                } else if byte == DLE_CHAR {
+                    self.store(byte)
+                } else if self.encoder.is_dle_terminator(byte) {
+                    self.end_frame(byte)
+                } else if self.encoder.escape_stx_etx {
+                    match self.encoder.unescape(byte) {
+                        Some(decoded) => self.store(decoded),
+                        None => {
+                            self.fail(DecodeErrorKind::InvalidDleSequence { found: byte }, offset)
+                        }
+                    }
+                } else if byte == STX_CHAR {
+                    // The DLE and STX start a new frame
+                    self.start_frame(2);
+                    DecodePush::Error(DleError::decoding(
+                        DecodeErrorKind::UnexpectedStart,
+                        offset.saturating_sub(1),
+                    ))
+                } else if self.encoder.lenient_dle {
+                    self.store(byte)
+                } else {
+                    self.fail(DecodeErrorKind::InvalidDleSequence { found: byte }, offset)
+                }
+            }
+        }
+    }
+
+    /// The decoded payload of the last completed frame. This is only valid directly after
+    /// [DecodePush::Frame] was returned
+    pub fn frame(&self) -> &[u8] {
+        &self.buffer[..self.decoded_len]
+    }
+
+    /// Discard the current frame and wait for the start of the next frame
+    pub fn reset(&mut self) {
+        self.phase = DecodePhase::Idle;
+        self.decoded_len = 0;
+        self.frame_offset = 0;
+    }
+
+    fn start_frame(&mut self, start_marker_len: usize) {
+        self.phase = DecodePhase::InFrame;
+        self.decoded_len = 0;
+        self.frame_offset = start_marker_len;
+    }
+
+    fn store(&mut self, byte: u8) -> DecodePush {
+        match self.buffer.get_mut(self.decoded_len) {
+            Some(dest) => {
+                *dest = byte;
+                self.decoded_len += 1;
+                DecodePush::More
+            }
+            None => {
+                self.reset();
+                DecodePush::Error(DleError::FrameTooLarge)
+            }
+        }
+    }
+
+    fn end_frame(&mut self, terminator: u8) -> DecodePush {
+        if self.encoder.keep_terminator {
+            if let DecodePush::Error(error) = self.store(terminator) {
+                return DecodePush::Error(error);
+            }
+        }
+        self.phase = DecodePhase::Idle;
+        self.frame_offset = 0;
+        DecodePush::Frame(self.decoded_len)
+    }
+
+    fn fail(&mut self, kind: DecodeErrorKind, offset: usize) -> DecodePush {
+        self.reset();
+        DecodePush::Error(DleError::decoding(kind, offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_frame_eq;
+    use crate::{CR_CHAR, DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+    #[test]
+    fn test_byte_at_a_time_encoder() {
+        let payloads: [&[u8]; 4] = [
+            &[],
+            &[0x11, 0x22],
+            &[DLE_CHAR, STX_CHAR, ETX_CHAR, CR_CHAR, DLE_CHAR],
+            &[STX_CHAR, 0x11, DLE_CHAR],
+        ];
+        let mut dle_encoder = DleEncoder {
+            escape_cr: true,
+            ..Default::default()
+        };
+        for escape_stx_etx in [true, false] {
+            for add_stx_etx in [true, false] {
+                dle_encoder.escape_stx_etx = escape_stx_etx;
+                dle_encoder.add_stx_etx = add_stx_etx;
+                let mut byte_encoder = ByteAtATimeEncoder::new(dle_encoder);
+                for payload in payloads.iter() {
+                    let mut expected: [u8; 32] = [0; 32];
+                    // The byte encoder does not reject ambiguous payloads like the encode method
+                    let expected_len = if escape_stx_etx {
+                        dle_encoder.encode_escaped(payload, &mut expected)
+                    } else {
+                        dle_encoder.encode_non_escaped(payload, &mut expected)
+                    }
+                    .unwrap();
+                    let mut transmitted: [u8; 32] = [0; 32];
+                    let mut transmitted_len = 0;
+                    while let Some(byte) = byte_encoder.next_byte(payload) {
+                        transmitted[transmitted_len] = byte;
+                        transmitted_len += 1;
+                    }
+                    assert_frame_eq(&transmitted[..transmitted_len], &expected[..expected_len]);
+                    assert!(byte_encoder.is_done());
+                    assert_eq!(byte_encoder.next_byte(payload), None);
+                    byte_encoder.reset();
+                    assert!(!byte_encoder.is_done());
+                }
+            }
+        }
+    }
+
+    fn push_all<const N: usize>(
+        byte_decoder: &mut ByteAtATimeDecoder<N>,
+        bytes: &[u8],
+    ) -> DecodePush {
+        let mut result = DecodePush::More;
+        for &byte in bytes {
+            result = byte_decoder.push_byte(byte);
+        }
+        result
+    }
+
+    #[test]
+    fn test_byte_at_a_time_decoder() {
+        let payloads: [&[u8]; 4] = [
+            &[],
+            &[0x11, 0x22],
+            &[DLE_CHAR, STX_CHAR, ETX_CHAR, CR_CHAR, DLE_CHAR],
+            &[STX_CHAR, 0x11, DLE_CHAR],
+        ];
+        let mut dle_encoder = DleEncoder {
+            escape_cr: true,
+            ..Default::default()
+        };
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut byte_decoder = ByteAtATimeDecoder::<8>::new(dle_encoder);
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                // Noise before the frame is discarded
+                assert_eq!(byte_decoder.push_byte(0x55), DecodePush::More);
+                for &byte in &encoded[..encoded_len - 1] {
+                    assert_eq!(byte_decoder.push_byte(byte), DecodePush::More);
+                }
+                assert_eq!(
+                    byte_decoder.push_byte(encoded[encoded_len - 1]),
+                    DecodePush::Frame(payload.len())
+                );
+                assert_frame_eq(byte_decoder.frame(), payload);
+            }
+
+            // Payloads exceeding the capacity are discarded
+            let mut encoded: [u8; 32] = [0; 32];
+            dle_encoder.encode(&[0x11; 9], &mut encoded).unwrap();
+            let ninth_byte_end = dle_encoder.start_marker().len() + 9;
+            assert_eq!(
+                push_all(&mut byte_decoder, &encoded[..ninth_byte_end]),
+                DecodePush::Error(DleError::FrameTooLarge)
+            );
+            let encoded_len = dle_encoder.encode(&[0x11; 8], &mut encoded).unwrap();
+            assert_eq!(
+                push_all(&mut byte_decoder, &encoded[..encoded_len]),
+                DecodePush::Frame(8)
+            );
+        }
+    }
+
+    #[test]
+    fn test_byte_at_a_time_decoder_errors() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut byte_decoder = ByteAtATimeDecoder::<8>::new(dle_encoder);
+        assert_eq!(
+            push_all(&mut byte_decoder, &[STX_CHAR, 0x11, DLE_CHAR, 0x99]),
+            DecodePush::Error(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+                3
+            ))
+        );
+        assert_eq!(
+            push_all(&mut byte_decoder, &[STX_CHAR, 0x11, STX_CHAR]),
+            DecodePush::Error(DleError::decoding(DecodeErrorKind::UnexpectedStart, 2))
+        );
+        // The unexpected STX started a new frame
+        assert_eq!(
+            push_all(&mut byte_decoder, &[0x22, ETX_CHAR]),
+            DecodePush::Frame(1)
+        );
+        assert_frame_eq(byte_decoder.frame(), &[0x22]);
+
+        dle_encoder.escape_stx_etx = false;
+        dle_encoder.keep_terminator = true;
+        byte_decoder.encoder = dle_encoder;
+        byte_decoder.reset();
+        assert_eq!(
+            push_all(
+                &mut byte_decoder,
+                &[DLE_CHAR, STX_CHAR, 0x11, DLE_CHAR, 0x42]
+            ),
+            DecodePush::Error(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x42 },
+                4
+            ))
+        );
+        assert_eq!(
+            push_all(
+                &mut byte_decoder,
+                &[DLE_CHAR, STX_CHAR, 0x11, DLE_CHAR, STX_CHAR]
+            ),
+            DecodePush::Error(DleError::decoding(DecodeErrorKind::UnexpectedStart, 3))
+        );
+        assert_eq!(
+            push_all(&mut byte_decoder, &[STX_CHAR, DLE_CHAR, ETX_CHAR]),
+            DecodePush::Frame(2)
+        );
+        assert_frame_eq(byte_decoder.frame(), &[STX_CHAR, ETX_CHAR]);
+    }
+
+    #[test]
+    fn test_decoder_offset_saturates() {
+        let dle_encoder = DleEncoder::default();
+        let mut byte_decoder = ByteAtATimeDecoder::<8>::new(dle_encoder);
+        // Simulates a long-running link which received a huge number of idle bytes
+        byte_decoder.frame_offset = usize::MAX - 1;
+        for _ in 0..3 {
+            assert_eq!(byte_decoder.push_byte(0x55), DecodePush::More);
+        }
+        assert_eq!(byte_decoder.frame_offset, usize::MAX);
+        assert_eq!(
+            push_all(&mut byte_decoder, &[STX_CHAR, 0x11, ETX_CHAR]),
+            DecodePush::Frame(1)
+        );
+        assert_frame_eq(byte_decoder.frame(), &[0x11]);
+    }
+}
@@ -0,0 +1,318 @@
+//! Forward error correction for one-way links without retransmissions.
+
+use crate::{dest_tail, DleEncoder, DleError, FragmentRole};
+
+/// Largest payload supported by [FecScheme::Hamming], so all bit positions fit into the 32
+/// bit syndrome
+const MAX_HAMMING_PAYLOAD_LEN: usize = 1 << 28;
+
+/// Parity scheme which is appended to the payload inside a frame by
+/// [DleEncoder::encode_fec]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FecScheme {
+    /// A single byte containing the XOR of all payload bytes. Errors are detected, but can
+    /// not be corrected
+    Xor,
+    /// An extended Hamming code using five parity bytes. The first four bytes contain the
+    /// XOR of the code word positions of all set payload bits as big-endian value, the last
+    /// byte makes the parity of the whole block even. Single bit errors inside the payload
+    /// or the parity bytes are corrected and double bit errors are detected. Payloads can
+    /// be up to 2^28 bytes long
+    Hamming,
+}
+
+impl FecScheme {
+    /// Number of parity bytes appended to the payload
+    pub fn parity_len(self) -> usize {
+        match self {
+            FecScheme::Xor => 1,
+            FecScheme::Hamming => 5,
+        }
+    }
+}
+
+/// Code word positions of the payload bits. Powers of two are skipped because they are the
+/// positions of the syndrome bits, which allows locating errors inside the syndrome
+fn data_positions() -> impl Iterator<Item = u32> {
+    (3..).filter(|position: &u32| !position.is_power_of_two())
+}
+
+/// Returns the payload bits, starting with the most significant bit of the first byte
+fn data_bits(payload: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    payload
+        .iter()
+        .flat_map(|&byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+}
+
+fn hamming_syndrome(payload: &[u8]) -> u32 {
+    data_positions()
+        .zip(data_bits(payload))
+        .filter(|&(_, bit)| bit)
+        .fold(0, |syndrome, (position, _)| syndrome ^ position)
+}
+
+fn bit_parity(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|byte| byte.count_ones()).sum::<u32>() % 2
+}
+
+/// Calculate the parity bytes of the given payload
+fn parity(scheme: FecScheme, payload: &[u8], parity: &mut [u8]) {
+    match scheme {
+        FecScheme::Xor => parity[0] = payload.iter().fold(0, |xor, byte| xor ^ byte),
+        FecScheme::Hamming => {
+            parity[..4].copy_from_slice(&hamming_syndrome(payload).to_be_bytes());
+            parity[4] = (bit_parity(payload) + bit_parity(&parity[..4])) as u8 % 2;
+        }
+    }
+}
+
+/// Check the payload using the parity bytes following it and correct single bit errors if
+/// supported by the scheme
+fn check_and_correct(scheme: FecScheme, block: &mut [u8]) -> Result<(), DleError> {
+    let (payload, parity) = block.split_at_mut(block.len() - scheme.parity_len());
+    match scheme {
+        FecScheme::Xor => {
+            if payload.iter().fold(parity[0], |xor, byte| xor ^ byte) != 0 {
+                return Err(DleError::FecUncorrectable);
+            }
+        }
+        FecScheme::Hamming => {
+            let stored = u32::from_be_bytes([parity[0], parity[1], parity[2], parity[3]]);
+            let error_position = stored ^ hamming_syndrome(payload);
+            let odd_errors = bit_parity(payload) + bit_parity(parity) == 1;
+            if error_position == 0 || error_position.is_power_of_two() {
+                // Errors in the parity bytes themselves do not affect the payload. Without
+                // an odd number of errors, these are two errors inside the syndrome
+                if !odd_errors && error_position != 0 {
+                    return Err(DleError::FecUncorrectable);
+                }
+                return Ok(());
+            }
+            if !odd_errors {
+                return Err(DleError::FecUncorrectable);
+            }
+            // Number of non-power-of-two positions up to the error position
+            let bit_idx = (error_position - (32 - error_position.leading_zeros()) - 1) as usize;
+            let byte = payload
+                .get_mut(bit_idx / 8)
+                .ok_or(DleError::FecUncorrectable)?;
+            *byte ^= 0x80 >> (bit_idx % 8);
+        }
+    }
+    Ok(())
+}
+
+#[deny(
+    clippy::indexing_slicing,
+    clippy::expect_used,
+    clippy::unwrap_used,
+    clippy::panic
+)]
+impl DleEncoder {
+    /// This method appends the parity bytes of the given scheme to the payload and encodes
+    /// both as a single frame, like [DleEncoder::encode]. The parity bytes are escaped like
+    /// the payload. This allows the receiver to detect or correct bit errors with
+    /// [DleEncoder::decode_fec] on links without retransmissions. Payloads exceeding the
+    /// length supported by the scheme are rejected with [DleError::FrameTooLarge].
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - The parity scheme
+    /// * `payload` - The payload to encode
+    /// * `dest_stream` - Encoded frame will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, FecScheme};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_fec(FecScheme::Xor, &[0x01, 0x05], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x01, 0x05, 0x04, 0x03]);
+    /// ```
+    pub fn encode_fec(
+        &self,
+        scheme: FecScheme,
+        payload: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        if scheme == FecScheme::Hamming && payload.len() > MAX_HAMMING_PAYLOAD_LEN {
+            return Err(DleError::FrameTooLarge);
+        }
+        let mut parity_bytes = [0; 5];
+        let parity_bytes = parity_bytes
+            .get_mut(..scheme.parity_len())
+            .unwrap_or_default();
+        parity(scheme, payload, parity_bytes);
+        let (payload_role, parity_role) = if self.add_stx_etx {
+            (FragmentRole::First, FragmentRole::Last)
+        } else {
+            (FragmentRole::Middle, FragmentRole::Middle)
+        };
+        let payload_len = self.encode_fragment(payload, payload_role, dest_stream)?;
+        let parity_len = self.encode_fragment(
+            parity_bytes,
+            parity_role,
+            dest_tail(dest_stream, payload_len)?,
+        )?;
+        self.pad(dest_stream, payload_len + parity_len)
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_fec] and checks the payload with the parity bytes. Correctable
+    /// errors are corrected in the destination stream. Returns the length of the payload,
+    /// which is written to the start of the destination stream. The destination stream must
+    /// also hold the parity bytes. [DleError::FecUncorrectable] is returned if an error was
+    /// detected which can not be corrected. Please note that three or more bit errors can be
+    /// miscorrected by [FecScheme::Hamming].
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - The parity scheme used by the sender
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded payload and parity bytes will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError, FecScheme};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let payload_len = dle_encoder
+    ///     .decode_fec(FecScheme::Xor, &[0x02, 0x01, 0x05, 0x04, 0x03], &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..payload_len], &[0x01, 0x05]);
+    /// assert_eq!(
+    ///     dle_encoder.decode_fec(FecScheme::Xor, &[0x02, 0x01, 0x07, 0x04, 0x03], &mut decoding_buffer),
+    ///     Err(DleError::FecUncorrectable)
+    /// );
+    /// ```
+    pub fn decode_fec(
+        &self,
+        scheme: FecScheme,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, dest_stream, &mut read_len)?;
+        let payload_len = decoded_len
+            .checked_sub(scheme.parity_len())
+            .ok_or(DleError::FecUncorrectable)?;
+        let decoded = dest_stream.get_mut(..decoded_len).unwrap_or_default();
+        check_and_correct(scheme, decoded)?;
+        Ok(payload_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_frame_eq;
+    use crate::{DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+    const PAYLOAD: [u8; 6] = [0x11, STX_CHAR, DLE_CHAR, ETX_CHAR, 0x00, 0xff];
+
+    /// Encode the payload and flip the given bits of the decoded block before checking it
+    fn check_with_errors(
+        scheme: FecScheme,
+        payload: &[u8],
+        bits: &[usize],
+    ) -> Result<(), DleError> {
+        let mut block = [0; 16];
+        block[..payload.len()].copy_from_slice(payload);
+        let block_len = payload.len() + scheme.parity_len();
+        parity(scheme, payload, &mut block[payload.len()..block_len]);
+        for &bit in bits {
+            block[bit / 8] ^= 0x80 >> (bit % 8);
+        }
+        check_and_correct(scheme, &mut block[..block_len])?;
+        assert_frame_eq(&block[..payload.len()], payload);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fec_roundtrip() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            for scheme in [FecScheme::Xor, FecScheme::Hamming] {
+                for payload in [&PAYLOAD[..], &[]] {
+                    let mut encoded: [u8; 32] = [0; 32];
+                    let encoded_len = dle_encoder
+                        .encode_fec(scheme, payload, &mut encoded)
+                        .unwrap();
+                    let mut decoded: [u8; 16] = [0; 16];
+                    let payload_len = dle_encoder
+                        .decode_fec(scheme, &encoded[..encoded_len], &mut decoded)
+                        .unwrap();
+                    assert_frame_eq(&decoded[..payload_len], payload);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fec_single_bit_errors() {
+        let dle_encoder = DleEncoder::default();
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder
+            .encode_fec(FecScheme::Hamming, &PAYLOAD, &mut encoded)
+            .unwrap();
+        // Flip a bit of the payload byte 0x11, which is not escaped
+        encoded[1] ^= 0x04;
+        let mut decoded: [u8; 16] = [0; 16];
+        let payload_len = dle_encoder
+            .decode_fec(FecScheme::Hamming, &encoded[..encoded_len], &mut decoded)
+            .unwrap();
+        assert_frame_eq(&decoded[..payload_len], &PAYLOAD);
+
+        // Every single bit error of the payload and the parity bytes is corrected
+        let block_bits = 8 * (PAYLOAD.len() + FecScheme::Hamming.parity_len());
+        for bit in 0..block_bits {
+            assert_eq!(
+                check_with_errors(FecScheme::Hamming, &PAYLOAD, &[bit]),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_fec_uncorrectable_errors() {
+        // Every double bit error affecting the payload or the syndrome is detected
+        let block_bits = 8 * (PAYLOAD.len() + FecScheme::Hamming.parity_len());
+        for first in 0..block_bits {
+            for second in first + 1..block_bits {
+                let result = check_with_errors(FecScheme::Hamming, &PAYLOAD, &[first, second]);
+                // Two errors inside the overall parity byte cancel out without affecting
+                // the payload
+                if first >= block_bits - 8 {
+                    assert_eq!(result, Ok(()));
+                } else {
+                    assert_eq!(result, Err(DleError::FecUncorrectable));
+                }
+            }
+        }
+        for bit in 0..8 * (PAYLOAD.len() + 1) {
+            assert_eq!(
+                check_with_errors(FecScheme::Xor, &PAYLOAD, &[bit]),
+                Err(DleError::FecUncorrectable)
+            );
+        }
+
+        // Frames shorter than the parity bytes
+        let dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 16] = [0; 16];
+        assert_eq!(
+            dle_encoder.decode_fec(
+                FecScheme::Hamming,
+                &[STX_CHAR, 0x01, ETX_CHAR],
+                &mut decoded
+            ),
+            Err(DleError::FecUncorrectable)
+        );
+    }
+}
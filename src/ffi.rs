@@ -0,0 +1,355 @@
+//! Encoding and decoding with raw pointers and integer error codes for C FFI layers.
+
+use crate::{DecodeErrorKind, DleEncoder, DleError};
+use core::slice;
+
+/// The operation was successful
+pub const FFI_OK: i32 = 0;
+/// A required pointer argument was null
+pub const FFI_NULL_POINTER: i32 = -1;
+/// See [DleError::StreamTooShort]
+pub const FFI_STREAM_TOO_SHORT: i32 = -2;
+/// See [DleError::SourceIncomplete]
+pub const FFI_SOURCE_INCOMPLETE: i32 = -3;
+/// See [DleError::WriteFailed]
+pub const FFI_WRITE_FAILED: i32 = -4;
+/// See [DleError::FrameTooLarge]
+pub const FFI_FRAME_TOO_LARGE: i32 = -5;
+/// See [DleError::InvalidUtf8]
+pub const FFI_INVALID_UTF8: i32 = -6;
+/// See [DleError::InvalidHeader]
+pub const FFI_INVALID_HEADER: i32 = -7;
+/// See [DleError::FecUncorrectable]
+pub const FFI_FEC_UNCORRECTABLE: i32 = -8;
+/// See [DleError::AmbiguousPayload]
+pub const FFI_AMBIGUOUS_PAYLOAD: i32 = -9;
+/// See [DecodeErrorKind::MissingStart]
+pub const FFI_MISSING_START: i32 = -10;
+/// See [DecodeErrorKind::InvalidDleSequence]
+pub const FFI_INVALID_ESCAPE: i32 = -11;
+/// See [DecodeErrorKind::UnexpectedStart]
+pub const FFI_UNEXPECTED_START: i32 = -12;
+/// See [DecodeErrorKind::MissingEnd]
+pub const FFI_MISSING_END: i32 = -13;
+/// See [DecodeErrorKind::TrailingBytes]
+pub const FFI_TRAILING_BYTES: i32 = -14;
+/// See [DecodeErrorKind::RawControlChar]
+pub const FFI_RAW_CONTROL_CHAR: i32 = -15;
+/// See [DleError::ReadFailed]
+pub const FFI_READ_FAILED: i32 = -16;
+/// See [DleError::CrcMismatch]
+pub const FFI_CRC_MISMATCH: i32 = -17;
+/// See [DecodeErrorKind::InvalidHexDigit]
+pub const FFI_INVALID_HEX_DIGIT: i32 = -18;
+
+impl DleError {
+    /// Returns the integer error code of the error which is used by the FFI functions like
+    /// [DleEncoder::encode_ffi]. All codes are negative
+    pub fn ffi_code(&self) -> i32 {
+        match self {
+            DleError::StreamTooShort => FFI_STREAM_TOO_SHORT,
+            DleError::SourceIncomplete => FFI_SOURCE_INCOMPLETE,
+            DleError::WriteFailed => FFI_WRITE_FAILED,
+            DleError::ReadFailed => FFI_READ_FAILED,
+            DleError::FrameTooLarge => FFI_FRAME_TOO_LARGE,
+            DleError::InvalidUtf8 => FFI_INVALID_UTF8,
+            DleError::InvalidHeader => FFI_INVALID_HEADER,
+            DleError::CrcMismatch => FFI_CRC_MISMATCH,
+            DleError::FecUncorrectable => FFI_FEC_UNCORRECTABLE,
+            DleError::AmbiguousPayload => FFI_AMBIGUOUS_PAYLOAD,
+            DleError::DecodingError { kind, .. } => match kind {
+                DecodeErrorKind::MissingStart => FFI_MISSING_START,
+                DecodeErrorKind::InvalidDleSequence { .. } => FFI_INVALID_ESCAPE,
+                DecodeErrorKind::UnexpectedStart => FFI_UNEXPECTED_START,
+                DecodeErrorKind::MissingEnd => FFI_MISSING_END,
+                DecodeErrorKind::TrailingBytes => FFI_TRAILING_BYTES,
+                DecodeErrorKind::RawControlChar => FFI_RAW_CONTROL_CHAR,
+                DecodeErrorKind::InvalidHexDigit => FFI_INVALID_HEX_DIGIT,
+            },
+        }
+    }
+}
+
+/// Create a slice from a pointer and a length. Null pointers are only accepted for empty
+/// slices.
+///
+/// # Safety
+///
+/// If the pointer is not null, it must be valid for reads of `len` bytes for the lifetime
+/// of the returned slice
+unsafe fn source_slice<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        return if len == 0 { Some(&[]) } else { None };
+    }
+    Some(slice::from_raw_parts(ptr, len))
+}
+
+/// Create a mutable slice from a pointer and a length. Null pointers are only accepted for
+/// empty slices.
+///
+/// # Safety
+///
+/// If the pointer is not null, it must be valid for reads and writes of `len` bytes for the
+/// lifetime of the returned slice and must not alias any other slice
+unsafe fn dest_slice<'a>(ptr: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    if ptr.is_null() {
+        return if len == 0 { Some(&mut []) } else { None };
+    }
+    Some(slice::from_raw_parts_mut(ptr, len))
+}
+
+impl DleEncoder {
+    /// This method encodes a given byte stream like [DleEncoder::encode], but uses raw
+    /// pointers and an integer return code, which is easier to expose across a C FFI
+    /// boundary. Returns [FFI_OK] on success and writes the number of encoded bytes to
+    /// `out_written`. Otherwise, a negative error code as returned by [DleError::ffi_code]
+    /// or [FFI_NULL_POINTER] is returned and `out_written` is not modified.
+    ///
+    /// # Safety
+    ///
+    /// * `src` must be valid for reads of `src_len` bytes. It may only be null if `src_len`
+    ///   is zero
+    /// * `dst` must be valid for writes of `dst_len` bytes and must not overlap with `src`.
+    ///   It may only be null if `dst_len` is zero
+    /// * `out_written` must be null or valid for writes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, FFI_OK};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let source: [u8; 2] = [0x01, 0x02];
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let mut written = 0;
+    /// let code = unsafe {
+    ///     dle_encoder.encode_ffi(
+    ///         source.as_ptr(),
+    ///         source.len(),
+    ///         encoding_buffer.as_mut_ptr(),
+    ///         encoding_buffer.len(),
+    ///         &mut written,
+    ///     )
+    /// };
+    /// assert_eq!(code, FFI_OK);
+    /// assert_eq!(&encoding_buffer[..written], &[0x02, 0x01, 0x10, 0x42, 0x03]);
+    /// ```
+    pub unsafe fn encode_ffi(
+        &self,
+        src: *const u8,
+        src_len: usize,
+        dst: *mut u8,
+        dst_len: usize,
+        out_written: *mut usize,
+    ) -> i32 {
+        if out_written.is_null() {
+            return FFI_NULL_POINTER;
+        }
+        let (source_stream, dest_stream) =
+            match (source_slice(src, src_len), dest_slice(dst, dst_len)) {
+                (Some(source_stream), Some(dest_stream)) => (source_stream, dest_stream),
+                _ => return FFI_NULL_POINTER,
+            };
+        match self.encode(source_stream, dest_stream) {
+            Ok(encoded_len) => {
+                *out_written = encoded_len;
+                FFI_OK
+            }
+            Err(error) => error.ffi_code(),
+        }
+    }
+
+    /// This method decodes a given byte stream like [DleEncoder::decode], but uses raw
+    /// pointers and an integer return code, which is easier to expose across a C FFI
+    /// boundary. Returns [FFI_OK] on success and writes the number of decoded bytes to
+    /// `out_written`. Otherwise, a negative error code as returned by [DleError::ffi_code]
+    /// or [FFI_NULL_POINTER] is returned and `out_written` is not modified. Unless a null
+    /// pointer was passed, the number of read bytes of the source stream is always written
+    /// to `out_read`, as done by [DleEncoder::decode].
+    ///
+    /// # Safety
+    ///
+    /// * `src` must be valid for reads of `src_len` bytes. It may only be null if `src_len`
+    ///   is zero
+    /// * `dst` must be valid for writes of `dst_len` bytes and must not overlap with `src`.
+    ///   It may only be null if `dst_len` is zero
+    /// * `out_read` and `out_written` must be null or valid for writes
+    pub unsafe fn decode_ffi(
+        &self,
+        src: *const u8,
+        src_len: usize,
+        dst: *mut u8,
+        dst_len: usize,
+        out_read: *mut usize,
+        out_written: *mut usize,
+    ) -> i32 {
+        if out_read.is_null() || out_written.is_null() {
+            return FFI_NULL_POINTER;
+        }
+        let (source_stream, dest_stream) =
+            match (source_slice(src, src_len), dest_slice(dst, dst_len)) {
+                (Some(source_stream), Some(dest_stream)) => (source_stream, dest_stream),
+                _ => return FFI_NULL_POINTER,
+            };
+        let mut read_len = 0;
+        let result = self.decode(source_stream, dest_stream, &mut read_len);
+        *out_read = read_len;
+        match result {
+            Ok(decoded_len) => {
+                *out_written = decoded_len;
+                FFI_OK
+            }
+            Err(error) => error.ffi_code(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_frame_eq;
+    use crate::{DLE_CHAR, ETX_CHAR, STX_CHAR};
+    use core::ptr;
+
+    #[test]
+    fn test_encode_decode_ffi() {
+        let dle_encoder = DleEncoder::default();
+        let source: [u8; 3] = [0x00, STX_CHAR, DLE_CHAR];
+        let mut encoded: [u8; 16] = [0; 16];
+        let mut encoded_len = 0;
+        let code = unsafe {
+            dle_encoder.encode_ffi(
+                source.as_ptr(),
+                source.len(),
+                encoded.as_mut_ptr(),
+                encoded.len(),
+                &mut encoded_len,
+            )
+        };
+        assert_eq!(code, FFI_OK);
+        assert_eq!(encoded_len, 7);
+
+        let mut decoded: [u8; 16] = [0; 16];
+        let (mut read_len, mut decoded_len) = (0, 0);
+        let code = unsafe {
+            dle_encoder.decode_ffi(
+                encoded.as_ptr(),
+                encoded_len,
+                decoded.as_mut_ptr(),
+                decoded.len(),
+                &mut read_len,
+                &mut decoded_len,
+            )
+        };
+        assert_eq!(code, FFI_OK);
+        assert_eq!(read_len, encoded_len);
+        assert_frame_eq(&decoded[..decoded_len], &source);
+
+        // Empty payloads can be passed as null pointers
+        let code = unsafe {
+            dle_encoder.encode_ffi(
+                ptr::null(),
+                0,
+                encoded.as_mut_ptr(),
+                encoded.len(),
+                &mut encoded_len,
+            )
+        };
+        assert_eq!(code, FFI_OK);
+        assert_eq!(&encoded[..encoded_len], &[STX_CHAR, ETX_CHAR]);
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        let dle_encoder = DleEncoder::default();
+        let source: [u8; 3] = [0x00, STX_CHAR, DLE_CHAR];
+        let mut dest: [u8; 4] = [0; 4];
+        let mut written = usize::MAX;
+        let mut read_len = 0;
+        unsafe {
+            assert_eq!(
+                dle_encoder.encode_ffi(
+                    ptr::null(),
+                    source.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    &mut written,
+                ),
+                FFI_NULL_POINTER
+            );
+            assert_eq!(
+                dle_encoder.encode_ffi(
+                    source.as_ptr(),
+                    source.len(),
+                    ptr::null_mut(),
+                    dest.len(),
+                    &mut written,
+                ),
+                FFI_NULL_POINTER
+            );
+            assert_eq!(
+                dle_encoder.encode_ffi(
+                    source.as_ptr(),
+                    source.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    ptr::null_mut(),
+                ),
+                FFI_NULL_POINTER
+            );
+            assert_eq!(
+                dle_encoder.encode_ffi(
+                    source.as_ptr(),
+                    source.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    &mut written,
+                ),
+                FFI_STREAM_TOO_SHORT
+            );
+            assert_eq!(
+                dle_encoder.decode_ffi(
+                    source.as_ptr(),
+                    source.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    ptr::null_mut(),
+                    &mut written,
+                ),
+                FFI_NULL_POINTER
+            );
+            assert_eq!(
+                dle_encoder.decode_ffi(
+                    source.as_ptr(),
+                    source.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    &mut read_len,
+                    &mut written,
+                ),
+                FFI_MISSING_START
+            );
+            let invalid_escape: [u8; 4] = [STX_CHAR, DLE_CHAR, 0x01, ETX_CHAR];
+            assert_eq!(
+                dle_encoder.decode_ffi(
+                    invalid_escape.as_ptr(),
+                    invalid_escape.len(),
+                    dest.as_mut_ptr(),
+                    dest.len(),
+                    &mut read_len,
+                    &mut written,
+                ),
+                FFI_INVALID_ESCAPE
+            );
+            assert_eq!(read_len, 3);
+        }
+        // The written length is only set on success
+        assert_eq!(written, usize::MAX);
+
+        assert_eq!(
+            DleError::decoding(DecodeErrorKind::MissingEnd, 0).ffi_code(),
+            FFI_MISSING_END
+        );
+        assert_eq!(DleError::InvalidUtf8.ffi_code(), FFI_INVALID_UTF8);
+    }
+}
@@ -0,0 +1,261 @@
+//! Frames consisting of multiple fields with preserved boundaries.
+
+#[cfg(feature = "alloc")]
+use crate::DecodeErrorKind;
+use crate::{
+    dest_tail, write_bytes, ConfigError, DleEncoder, DleError, FragmentRole, DLE_CHAR, US_CHAR,
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+/// Framer which encodes several fields into a single frame and recovers the field
+/// boundaries when decoding. The fields are separated by DLE followed by the separator
+/// character. Each field is escaped like a regular payload, so DLE characters of the data
+/// are doubled and a separator character inside a field is never preceded by a single DLE
+/// character. The separator must not be a character which the decoder accepts after a DLE
+/// character, like DLE itself or the escaped form of an escaped character. Frames always
+/// have start and end markers, independently of `add_stx_etx`, and are not padded.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, FieldFramer, US_CHAR};
+///
+/// let field_framer = FieldFramer::new(DleEncoder::default(), US_CHAR).unwrap();
+/// let mut encoding_buffer: [u8; 16] = [0; 16];
+/// let encoded_len = field_framer
+///     .encode_fields(&[&[0x01], &[US_CHAR]], &mut encoding_buffer)
+///     .unwrap();
+/// assert_eq!(
+///     &encoding_buffer[..encoded_len],
+///     &[0x02, 0x01, 0x10, 0x1f, 0x1f, 0x03]
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct FieldFramer {
+    encoder: DleEncoder,
+    separator: u8,
+}
+
+impl FieldFramer {
+    /// Create a new field framer using the given encoder configuration and separator
+    /// character. [ConfigError::SeparatorConflict] is returned if the decoder accepts the
+    /// separator after a DLE character with the given configuration
+    pub fn new(encoder: DleEncoder, separator: u8) -> Result<FieldFramer, ConfigError> {
+        if encoder.accepted_after_dle().contains(separator) {
+            return Err(ConfigError::SeparatorConflict { byte: separator });
+        }
+        Ok(FieldFramer { encoder, separator })
+    }
+
+    /// The encoder configuration used to encode and decode the fields
+    pub fn encoder(&self) -> &DleEncoder {
+        &self.encoder
+    }
+
+    /// The character following DLE between two fields
+    pub fn separator(&self) -> u8 {
+        self.separator
+    }
+
+    /// This method encodes the given fields into a single frame. An empty list of fields
+    /// is encoded like a single empty field. Returns the number of encoded bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - The fields to encode
+    /// * `dest_stream` - Encoded frame will be written here
+    pub fn encode_fields(
+        &self,
+        fields: &[&[u8]],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = write_bytes(dest_stream, 0, self.encoder.start_marker())?;
+        for (field_idx, field) in fields.iter().enumerate() {
+            if field_idx > 0 {
+                encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, self.separator])?;
+            }
+            encoded_idx += self.encoder.encode_fragment(
+                field,
+                FragmentRole::Middle,
+                dest_tail(dest_stream, encoded_idx)?,
+            )?;
+        }
+        write_bytes(dest_stream, encoded_idx, self.encoder.end_marker())
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [FieldFramer::encode_fields] and returns its fields. Bytes following the frame are
+    /// ignored. The offsets of decoding errors are relative to the start of the source
+    /// stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::FieldFramer;
+    ///
+    /// let field_framer = FieldFramer::default();
+    /// let fields = field_framer
+    ///     .decode_fields(&[0x02, 0x01, 0x10, 0x1f, 0x1f, 0x03])
+    ///     .unwrap();
+    /// assert_eq!(fields, [vec![0x01], vec![0x1f]]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_fields(&self, source_stream: &[u8]) -> Result<Vec<Vec<u8>>, DleError> {
+        let start_marker = self.encoder.start_marker();
+        let end_marker = self.encoder.end_marker();
+        if !source_stream.starts_with(start_marker) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        // The fields are decoded separately, so the terminator is never appended to them
+        let field_encoder = DleEncoder {
+            keep_terminator: false,
+            ..self.encoder
+        };
+        let mut fields = Vec::new();
+        let mut field_start = start_marker.len();
+        let mut idx = field_start;
+        loop {
+            let byte = *source_stream.get(idx).ok_or_else(|| {
+                DleError::decoding(DecodeErrorKind::MissingEnd, source_stream.len())
+            })?;
+            let next_byte = source_stream.get(idx + 1).copied();
+            let ends_frame = self.encoder.is_bare_terminator(byte)
+                || (byte == DLE_CHAR
+                    && matches!(next_byte, Some(next_byte) if self.encoder.is_dle_terminator(next_byte)));
+            let is_last = if byte == DLE_CHAR && next_byte == Some(self.separator) {
+                false
+            } else if ends_frame {
+                true
+            } else {
+                // The character following DLE is part of the escape sequence
+                idx += if byte == DLE_CHAR { 2 } else { 1 };
+                continue;
+            };
+            // Each field is decoded as a regular frame
+            let field = source_stream.get(field_start..idx).unwrap_or_default();
+            let mut bytes = start_marker.iter().chain(field).chain(end_marker).copied();
+            let mut decoded = vec![0; field.len()];
+            let decoded_len = field_encoder
+                .decode_pull(|| bytes.next(), &mut decoded)
+                .map_err(|error| error.shifted(field_start - start_marker.len()))?;
+            decoded.truncate(decoded_len);
+            fields.push(decoded);
+            if is_last {
+                return Ok(fields);
+            }
+            idx += 2;
+            field_start = idx;
+        }
+    }
+}
+
+impl Default for FieldFramer {
+    /// Field framer with the default encoder configuration and the unit separator
+    /// character [US_CHAR] as separator
+    fn default() -> FieldFramer {
+        FieldFramer {
+            encoder: DleEncoder::default(),
+            separator: US_CHAR,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{ETX_CHAR, STX_CHAR};
+
+    #[test]
+    fn test_field_framer() {
+        let fields: [&[u8]; 3] = [&[0x01, 0x02], &[US_CHAR, DLE_CHAR, US_CHAR], &[]];
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let field_framer = FieldFramer::new(dle_encoder, US_CHAR).unwrap();
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = field_framer.encode_fields(&fields, &mut encoded).unwrap();
+            // A separator character following a doubled DLE of the data looks like a
+            // separator, so the decoder has to track the escape sequences
+            let separators = encoded[..encoded_len]
+                .windows(2)
+                .filter(|window| window == &[DLE_CHAR, US_CHAR])
+                .count();
+            assert_eq!(separators, 3);
+
+            let mut decoded_fields = field_framer
+                .decode_fields(&encoded[..encoded_len + 1])
+                .unwrap();
+            assert_eq!(decoded_fields, fields);
+
+            // Other decoders see the separators as invalid escape sequences
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let error = dle_encoder
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(
+                error.decode_kind(),
+                Some(DecodeErrorKind::InvalidDleSequence { found: 0x1f })
+            );
+
+            // A single field and no fields at all
+            let encoded_len = field_framer.encode_fields(&[], &mut encoded).unwrap();
+            decoded_fields = field_framer.decode_fields(&encoded[..encoded_len]).unwrap();
+            assert_eq!(decoded_fields, [Vec::<u8>::new()]);
+            let encoded_len = field_framer
+                .encode_fields(&[&[STX_CHAR, ETX_CHAR]], &mut encoded)
+                .unwrap();
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder
+                .encode(&[STX_CHAR, ETX_CHAR], &mut expected)
+                .unwrap();
+            assert_eq!(&encoded[..encoded_len], &expected[..expected_len]);
+
+            assert_eq!(
+                field_framer.encode_fields(&fields, &mut encoded[..8]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
+
+    #[test]
+    fn test_field_framer_errors() {
+        let dle_encoder = DleEncoder::default();
+        for separator in [DLE_CHAR, STX_CHAR + 0x40, ETX_CHAR + 0x40] {
+            assert!(matches!(
+                FieldFramer::new(dle_encoder, separator),
+                Err(ConfigError::SeparatorConflict { byte }) if byte == separator
+            ));
+        }
+        let non_escaped = DleEncoder::non_escaped();
+        for separator in [DLE_CHAR, STX_CHAR, ETX_CHAR] {
+            assert!(FieldFramer::new(non_escaped, separator).is_err());
+        }
+        assert!(FieldFramer::new(non_escaped, STX_CHAR + 0x40).is_ok());
+
+        let field_framer = FieldFramer::default();
+        assert_eq!(field_framer.separator(), US_CHAR);
+        assert_eq!(
+            field_framer.decode_fields(&[0x55, STX_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 0))
+        );
+        assert_eq!(
+            field_framer.decode_fields(&[STX_CHAR, 0x55, DLE_CHAR, US_CHAR, 0x55]),
+            Err(DleError::decoding(DecodeErrorKind::MissingEnd, 5))
+        );
+        // Errors inside a field refer to the position in the source stream
+        assert_eq!(
+            field_framer
+                .decode_fields(&[STX_CHAR, 0x55, DLE_CHAR, US_CHAR, DLE_CHAR, 0x99, ETX_CHAR]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+                5
+            ))
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Decoded frames together with metadata about their encoding.
+
+use crate::PayloadView;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A decoded frame returned by [crate::DleEncoder::decode_frame_typed]. In addition to the
+/// decoded payload, it contains metadata about how the frame was encoded, which is useful
+/// for routing and logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The decoded payload. If `keep_terminator` is set, this includes the terminator
+    pub payload: Vec<u8>,
+    /// Whether the frame was decoded in the escaped mode
+    pub escape_stx_etx: bool,
+    /// Whether the frame contained at least one escaped CR character
+    pub cr_escaped: bool,
+    /// The range of the source stream occupied by the frame, including the framing
+    /// characters
+    pub source_range: Range<usize>,
+}
+
+impl Frame {
+    /// View for reading fields of the decoded payload
+    pub fn view(&self) -> PayloadView<'_> {
+        PayloadView::new(&self.payload)
+    }
+}
+
+impl IntoIterator for Frame {
+    type Item = u8;
+    type IntoIter = alloc::vec::IntoIter<u8>;
+
+    /// Iterate over the bytes of the decoded payload
+    fn into_iter(self) -> Self::IntoIter {
+        self.payload.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Frame {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    /// Iterate over the bytes of the decoded payload
+    fn into_iter(self) -> Self::IntoIter {
+        self.payload.iter()
+    }
+}
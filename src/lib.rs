@@ -1,27 +1,459 @@
-// use std::io::Read;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
+use core::convert::TryFrom;
+use core::ops::Range;
+
+mod bytewise;
+#[cfg(feature = "fec")]
+mod fec;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod fields;
+#[cfg(feature = "alloc")]
+mod frame;
+#[cfg(test)]
+mod panic_free;
+#[cfg(feature = "std")]
+mod reader;
+mod ring;
+mod seq;
+#[cfg(feature = "alloc")]
+mod stream;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(test)]
+mod vectors;
+mod view;
+mod wide;
+mod writer;
+
+pub use bytewise::{ByteAtATimeDecoder, ByteAtATimeEncoder, DecodePush};
+#[cfg(feature = "fec")]
+pub use fec::FecScheme;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
+pub use fields::FieldFramer;
+#[cfg(feature = "alloc")]
+pub use frame::Frame;
+#[cfg(feature = "std")]
+pub use reader::DleReader;
+pub use ring::RingDecoder;
+pub use seq::SeqEncoder;
+#[cfg(feature = "alloc")]
+pub use stream::StreamDecoder;
+pub use view::PayloadView;
+pub use wide::DleEncoderU16;
+pub use writer::DleFrameWriter;
 
 pub const STX_CHAR: u8 = 0x02;
 pub const ETX_CHAR: u8 = 0x03;
 pub const DLE_CHAR: u8 = 0x10;
 pub const CR_CHAR: u8 = 0x0d;
+pub const LF_CHAR: u8 = 0x0a;
+pub const SYN_CHAR: u8 = 0x16;
+pub const US_CHAR: u8 = 0x1f;
+
+/// A compact set of byte values. It is used to configure additional bytes which
+/// should be escaped by the encoder in the escaped mode.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{ByteSet, LF_CHAR};
+///
+/// let set = ByteSet::from_bytes(&[LF_CHAR, b';']);
+/// assert!(set.contains(LF_CHAR));
+/// assert!(!set.contains(b'a'));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ByteSet([u64; 4]);
+
+impl ByteSet {
+    /// Create an empty set
+    pub const fn new() -> ByteSet {
+        ByteSet([0; 4])
+    }
+
+    /// Create a set containing all bytes of the given slice
+    pub const fn from_bytes(bytes: &[u8]) -> ByteSet {
+        let mut set = ByteSet::new();
+        let mut idx = 0;
+        while idx < bytes.len() {
+            set = set.with(bytes[idx]);
+            idx += 1;
+        }
+        set
+    }
+
+    /// Return a copy of the set with the given byte added
+    #[must_use]
+    pub const fn with(mut self, byte: u8) -> ByteSet {
+        self.0[(byte >> 6) as usize] |= 1 << (byte & 0x3f);
+        self
+    }
+
+    /// Add a byte to the set
+    pub fn insert(&mut self, byte: u8) {
+        *self = self.with(byte);
+    }
+
+    /// Return a copy of the set with the given byte removed
+    #[must_use]
+    const fn without(mut self, byte: u8) -> ByteSet {
+        self.0[(byte >> 6) as usize] &= !(1 << (byte & 0x3f));
+        self
+    }
+
+    /// Check whether the given byte is part of the set
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.0[(byte >> 6) as usize] & (1 << (byte & 0x3f)) != 0
+    }
+
+    /// Check whether the set is empty
+    pub const fn is_empty(&self) -> bool {
+        self.0[0] == 0 && self.0[1] == 0 && self.0[2] == 0 && self.0[3] == 0
+    }
+}
+
+/// The scheme used to calculate the character following the DLE character for escaped
+/// characters in the escaped mode.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, EscapeScheme};
+///
+/// let dle_encoder = DleEncoder {
+///     escape_scheme: EscapeScheme::Xor(0x80),
+///     ..Default::default()
+/// };
+/// let mut encoding_buffer: [u8; 16] = [0; 16];
+/// let encoded_len = dle_encoder.encode(&[0x02], &mut encoding_buffer).unwrap();
+/// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x82, 0x03]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EscapeScheme {
+    /// The given offset is added to the escaped character, wrapping around on overflow
+    Additive(u8),
+    /// The escaped character is XORed with the given mask
+    Xor(u8),
+    /// The escaped form of a character is looked up in the table, using the character as
+    /// index. Only the entries of escaped characters are relevant
+    Table(&'static [u8; 256]),
+}
+
+impl EscapeScheme {
+    /// Returns the escaped form of the given character
+    pub fn escape(&self, byte: u8) -> u8 {
+        match self {
+            EscapeScheme::Additive(offset) => byte.wrapping_add(*offset),
+            EscapeScheme::Xor(mask) => byte ^ mask,
+            EscapeScheme::Table(table) => table[byte as usize],
+        }
+    }
+
+    /// Returns the character of the given set whose escaped form is the given escaped
+    /// character, or [None] if there is no such character
+    pub fn unescape(&self, escaped: u8, candidates: &ByteSet) -> Option<u8> {
+        let byte = match self {
+            EscapeScheme::Additive(offset) => escaped.wrapping_sub(*offset),
+            EscapeScheme::Xor(mask) => escaped ^ mask,
+            EscapeScheme::Table(table) => {
+                return (0..=u8::MAX)
+                    .find(|&byte| candidates.contains(byte) && table[byte as usize] == escaped)
+            }
+        };
+        if candidates.contains(byte) {
+            Some(byte)
+        } else {
+            None
+        }
+    }
+}
 
 /// This struct is used to create a DleEncoder instance. It can also
 /// be used to configure the encoder
+///
+/// The encoding and decoding methods never panic. Malformed or truncated frames and
+/// destination streams which are too short are reported with a [DleError] instead.
 #[derive(Copy, Clone)]
 pub struct DleEncoder {
     /// Configure whether the encoder uses the escaped or non-escaped mode
     pub escape_stx_etx: bool,
-    /// It is possible to escape CR characters as well in the escaped mode
+    /// It is possible to escape CR characters as well in the escaped mode. This includes a
+    /// CR character which is the last byte of the payload, directly before ETX. This setting
+    /// is ignored in the non-escaped mode, where CR characters, including a trailing one, are
+    /// transmitted unchanged because only DLE starts a control sequence
     pub escape_cr: bool,
+    /// Additional bytes which are escaped in the escaped mode. Each occurrence is encoded
+    /// as DLE followed by the escaped form of the byte, in the same way STX, ETX and CR are
+    /// encoded. STX, ETX and DLE are always handled by the encoder, so adding them here has
+    /// no effect. The escaped form of a byte must not be a control character itself, so
+    /// with the default escape scheme, bytes like 0xC2 (which would wrap to STX) must not be
+    /// used. This setting is ignored in the non-escaped mode
+    pub escape_extra: ByteSet,
+    /// The scheme used to calculate the escaped form of escaped characters in the escaped
+    /// mode. The default is adding an offset of 0x40
+    pub escape_scheme: EscapeScheme,
     /// Configure the encoder to not add STX and ETX characters at the start
     /// and end when encoding
     pub add_stx_etx: bool,
+    /// Configure the decoder to append the terminator character which ended the frame to
+    /// the decoded stream. This is useful for downstream parsers which expect an end marker
+    pub keep_terminator: bool,
+    /// Configure whether the decoder only accepts escape sequences which the encoder
+    /// produces with the same configuration. If this is disabled, an escaped CR character
+    /// is accepted even if `escape_cr` is not set, which allows decoding streams of
+    /// encoders configured differently. Enabled by default
+    pub strict_escapes: bool,
+    /// Configure the decoder to reject raw STX and ETX characters inside the body of a frame
+    /// in the non-escaped mode with a [DecodeErrorKind::RawControlChar] error. Frame
+    /// boundaries in this mode are only DLE STX and DLE ETX, so raw STX and ETX characters
+    /// are regular data and are passed through by the encoder. Enabling this adds safety
+    /// for links which should never carry these characters in the payload, but payloads
+    /// containing them can not be decoded anymore. Ignored in the escaped mode. Disabled
+    /// by default
+    pub strict_non_escaped: bool,
+    /// An alternate terminator character which ends a frame in addition to ETX when
+    /// decoding. In the escaped mode, an unescaped occurrence of the character ends the
+    /// frame while escape sequences are still decoded as data. In the non-escaped mode, the
+    /// character ends the frame if it follows a DLE character. The encoder never writes
+    /// this character as terminator, so it should be added to `escape_extra` if it can
+    /// occur in payloads in the escaped mode. DLE, STX and ETX can not be used and are
+    /// rejected by [DleEncoder::validate_config]
+    pub alt_terminator: Option<u8>,
+    /// The format of the encoded bytes written by [DleEncoder::encode_fmt]. The default is
+    /// writing each byte as a character
+    pub text_format: TextFormat,
+    /// Configure the encoder to insert a resync marker after every given number of payload
+    /// bytes. This allows receivers of long frames over lossy links to resynchronize without
+    /// waiting for the whole frame. The marker is a DLE character followed by a SYN
+    /// character in both modes. If this is set, the decoder skips resync markers, otherwise
+    /// they are rejected as invalid escape sequences. An interval of zero inserts no markers.
+    /// The markers are only written by [DleEncoder::encode] and the methods it is based on,
+    /// including [DleEncoder::encode_fragment], where the payload bytes are counted per
+    /// fragment, and by [DleEncoder::encode_scatter] and [DleEncoder::encode_ranged]
+    pub resync_interval: Option<usize>,
+    /// Configure how the decoder handles bytes following the end marker of a decoded frame.
+    /// By default, they are ignored and left to the caller
+    pub trailing_policy: TrailingPolicy,
+    /// Configure the encoder to pad encoded frames with the given fill byte up to the given
+    /// length, which is required for links with fixed-length packets. Frames which are
+    /// longer than the padded length are rejected with [DleError::FrameTooLarge]. The
+    /// decoder skips the fill bytes following a frame up to the padded length and includes
+    /// them in the read length. The fill byte must not be confused with the start of a
+    /// frame or an escape sequence, so STX and DLE are rejected by
+    /// [DleEncoder::validate_config]. The padding is written by all methods which encode
+    /// complete frames, but not by [DleEncoder::encode_fragment] and
+    /// [DleEncoder::encode_chunk] for fragments which are only a part of a frame
+    pub pad_to: Option<(usize, u8)>,
+    /// Configure the encoder to write the given idle sequence instead of an empty frame if
+    /// the source stream is empty. This keeps links active which require continuous line
+    /// activity. The decoder skips idle sequences preceding a frame and includes them in the
+    /// read length. The idle sequence must not be confused with the start of a frame or an
+    /// escape sequence, so sequences containing STX or DLE are rejected by
+    /// [DleEncoder::validate_config]. It is written by all methods which encode complete
+    /// frames from a source stream, including [DleEncoder::encode_fragment] and
+    /// [DleEncoder::encode_chunk] for the role [FragmentRole::Whole]
+    pub idle_on_empty: Option<&'static [u8]>,
+    /// Configure the decoder to accept a byte which differs from STX in a single bit as the
+    /// STX of the start marker. This recovers frames on noisy links where the STX was
+    /// corrupted by a bit flip. The terminator characters are never accepted. This is a
+    /// heuristic with a risk of false positives: in the escaped mode, a source stream
+    /// starting with 0x00, 0x06, LF, 0x12, 0x22, 0x42 or 0x82 is decoded as a frame
+    /// instead of being rejected with [DecodeErrorKind::MissingStart]. The start marker
+    /// must still be at the start of the source stream. Searching for frames inside a
+    /// stream, for example with [DleEncoder::frame_ranges], still requires an exact STX.
+    /// Only applies to [DleEncoder::decode], [DleEncoder::decode_pull] and the methods based
+    /// on them. Disabled by default
+    pub lenient_stx: bool,
+    /// Configure the decoder to accept a DLE followed by any character other than DLE, STX
+    /// or ETX in the non-escaped mode and decode it as the following character. This allows
+    /// decoding streams of encoders which escape additional characters. Without this option,
+    /// such sequences are rejected with [DecodeErrorKind::InvalidDleSequence]. Has no effect in
+    /// the escaped mode. Disabled by default
+    pub lenient_dle: bool,
+    /// The escape sequence written for DLE characters of the payload in the escaped mode.
+    /// By default, DLE characters are doubled. Some peers escape them like STX and ETX
+    /// instead, which is selected with [DleStyle::Offset]. The decoder accepts doubled DLE
+    /// characters with both styles. Ignored in the non-escaped mode, where DLE characters
+    /// are always doubled
+    pub dle_escape_style: DleStyle,
+    /// Configure the escaped mode to end frames only with DLE followed by a terminator
+    /// character. A bare terminator character is regular data, so the encoder writes ETX
+    /// characters of the payload unescaped and ends frames with DLE ETX. The escaped form
+    /// of ETX is still decoded. This is used by peers which treat ETX as a data byte. All
+    /// decoders, including the byte-wise, ring and stream decoders, end frames only at the
+    /// DLE terminator sequence with this setting. Ignored in the non-escaped mode. Disabled
+    /// by default
+    pub etx_terminates_only_after_dle: bool,
 }
 
-#[derive(Debug, PartialEq)]
+/// Errors returned by the encoding and decoding methods.
+///
+/// Errors are ordered by their variant in the order of declaration, so
+/// [DleError::StreamTooShort] is the smallest error and [DleError::DecodingError] the
+/// greatest one. Decoding errors are ordered by their kind and then by their offset. This
+/// allows sorting and deduplicating errors collected from many frames.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DleError {
     StreamTooShort,
-    DecodingError,
+    /// The byte source ran out of bytes before a complete frame was decoded
+    SourceIncomplete,
+    /// Writing the encoded stream into a [core::fmt::Write] implementation failed
+    WriteFailed,
+    /// Reading the encoded stream from a `std::io::Read` implementation failed, see
+    /// `DleReader`
+    ReadFailed,
+    /// A frame exceeded the configured maximum frame length
+    FrameTooLarge,
+    /// The decoded payload is not valid UTF-8, see [DleEncoder::decode_to_string]
+    InvalidUtf8,
+    /// The decoded payload does not have the layout expected by a method decoding structured
+    /// frames, like [DleEncoder::decode_typed], [DleEncoder::decode_sparse] or
+    /// [SeqEncoder::decode_seq]
+    InvalidHeader,
+    /// The CRC following a frame does not match the frame, see [DleEncoder::decode_with_crc]
+    CrcMismatch,
+    /// The parity bytes of a frame revealed errors which can not be corrected, see
+    /// `DleEncoder::decode_fec`. The variant also exists without the `fec` feature, so
+    /// enabling the feature does not change this enum
+    FecUncorrectable,
+    /// The payload contains a raw STX or ETX character which can not be distinguished from
+    /// the framing characters, because neither framing characters are added nor STX and ETX
+    /// are escaped, see [DleEncoder::encode]
+    AmbiguousPayload,
+    /// The source stream is not a valid DLE encoded frame. The kind of the error and
+    /// the offset of the offending byte in the source stream are provided
+    DecodingError {
+        kind: DecodeErrorKind,
+        offset: usize,
+    },
+}
+
+/// The different reasons for a [DleError::DecodingError], ordered in the order of
+/// declaration
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DecodeErrorKind {
+    /// The source stream does not start with the start marker of a frame
+    MissingStart,
+    /// A DLE character was followed by a character which can not be escaped. The offset
+    /// points to the character following the DLE, which is provided as the found byte and
+    /// explained by [DleEncoder::explain_invalid_escape]. The 16-bit decoder of
+    /// [DleEncoderU16] provides the low byte of the found symbol.
+    /// A DLE character at the end of the source stream is not an invalid escape sequence,
+    /// because the following character was not received yet, so it is reported as
+    /// [DecodeErrorKind::MissingEnd]
+    InvalidDleSequence { found: u8 },
+    /// The start marker of another frame was found before the end of the current frame.
+    /// This usually means that the end of the current frame was lost. In the non-escaped
+    /// mode, the read length points to the DLE of the new start marker, so decoding can be
+    /// resumed at the new frame. Corrupted escape sequences are reported as
+    /// [DecodeErrorKind::InvalidDleSequence] instead
+    UnexpectedStart,
+    /// The source stream ended before the end marker of the frame was found
+    MissingEnd,
+    /// The frame was followed by bytes which are not allowed by the configured
+    /// [TrailingPolicy]. The frame itself was decoded successfully
+    TrailingBytes,
+    /// A raw STX or ETX character was found inside the body of a frame in the non-escaped
+    /// mode while `strict_non_escaped` is enabled, or a raw STX or DLE character was found
+    /// by [DleEncoder::decode_framed_no_escape]
+    RawControlChar,
+    /// A character which is not a hexadecimal digit was found by [DleEncoder::decode_hex]
+    InvalidHexDigit,
+}
+
+/// Configuration problems of a [DleEncoder] which lead to data being lost or altered
+/// when a frame is encoded and decoded again, see [DleEncoder::assert_lossless]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// `add_stx_etx` is disabled, so the encoded stream can not be decoded without
+    /// external framing. Please note that the non-escaped mode still doubles DLE
+    /// characters in this case, so the encoded stream is not a pass-through of the data
+    MissingFraming,
+    /// `keep_terminator` is enabled, so the decoder appends the terminator character to the
+    /// decoded data
+    KeepTerminator,
+    /// `strict_non_escaped` is enabled in the non-escaped mode, so payloads containing STX
+    /// or ETX characters are rejected by the decoder
+    StrictNonEscaped,
+    /// The escaped form of the given byte is a control character and can not be
+    /// distinguished from it by the decoder
+    ControlCharEscape { byte: u8 },
+    /// The two given bytes share the same escaped form, so the decoder can not restore
+    /// the original byte
+    AmbiguousEscape { first: u8, second: u8 },
+    /// The given symbol is used for more than one control symbol of a [DleEncoderU16]
+    DuplicateControlSymbol { symbol: u16 },
+    /// The escaped form of the given symbol of a [DleEncoderU16] is a control symbol
+    ControlSymbolEscape { symbol: u16 },
+    /// The given field separator of a [FieldFramer] is accepted by the decoder after a DLE
+    /// character, so it can not be distinguished from the encoded data
+    SeparatorConflict { byte: u8 },
+    /// The given alternative terminator is a control character, so it can not be
+    /// distinguished from the start marker, an escape sequence or the regular terminator
+    AltTerminatorConflict { byte: u8 },
+    /// The given fill byte of `pad_to` is a control character, so it can be confused with
+    /// the start of a frame or an escape sequence
+    FillConflict { byte: u8 },
+    /// The given byte of the idle sequence is a control character, so it can be confused with
+    /// the start of a frame or an escape sequence
+    IdleConflict { byte: u8 },
+}
+
+impl DleError {
+    pub(crate) fn decoding(kind: DecodeErrorKind, offset: usize) -> DleError {
+        DleError::DecodingError { kind, offset }
+    }
+
+    /// Move the offset of a decoding error by the given number of bytes, for errors of
+    /// frames which were decoded from a subslice of the source stream
+    pub(crate) fn shifted(self, delta: usize) -> DleError {
+        match self {
+            DleError::DecodingError { kind, offset } => DleError::decoding(kind, offset + delta),
+            error => error,
+        }
+    }
+
+    /// Returns the offset of the offending byte in the source stream for decoding errors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DecodeErrorKind, DleEncoder};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let error = dle_encoder
+    ///     .decode(&[0x02, 0x00, 0x10, 0x99, 0x03], &mut decoding_buffer, &mut read_len)
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     error.decode_kind(),
+    ///     Some(DecodeErrorKind::InvalidDleSequence { found: 0x99 })
+    /// );
+    /// assert_eq!(error.offset(), Some(3));
+    /// ```
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            DleError::DecodingError { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of a decoding error
+    pub fn decode_kind(&self) -> Option<DecodeErrorKind> {
+        match self {
+            DleError::DecodingError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
 }
 
 impl Default for DleEncoder {
@@ -29,15 +461,450 @@ impl Default for DleEncoder {
         DleEncoder {
             escape_stx_etx: true,
             escape_cr: false,
+            escape_extra: ByteSet::new(),
+            escape_scheme: EscapeScheme::Additive(0x40),
             add_stx_etx: true,
+            keep_terminator: false,
+            strict_escapes: true,
+            strict_non_escaped: false,
+            alt_terminator: None,
+            text_format: TextFormat::Char,
+            resync_interval: None,
+            trailing_policy: TrailingPolicy::Ignore,
+            pad_to: None,
+            idle_on_empty: None,
+            lenient_stx: false,
+            lenient_dle: false,
+            dle_escape_style: DleStyle::Double,
+            etx_terminates_only_after_dle: false,
+        }
+    }
+}
+
+/// Format of the encoded bytes written by [DleEncoder::encode_fmt]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Each byte is written as the character with the same code point, bytes above 0x7f take
+    /// two bytes of UTF-8
+    Char,
+    /// Each byte is written as two uppercase hexadecimal digits without separators, like by
+    /// [DleEncoder::encode_hex]
+    Hex,
+}
+
+/// Escape sequence of DLE characters in the escaped mode, see [DleEncoder::dle_escape_style]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DleStyle {
+    /// DLE characters are doubled
+    Double,
+    /// DLE characters are written as DLE followed by the escaped form of DLE according to
+    /// the escape scheme, like STX and ETX. This is DLE followed by 0x50 for the default
+    /// escape scheme
+    Offset,
+}
+
+/// The meaningful combinations of the `escape_stx_etx`, `escape_cr` and `add_stx_etx`
+/// settings of a [DleEncoder]. Other combinations either contain settings which are ignored,
+/// like `escape_cr` in the non-escaped mode, or produce streams which can not be framed
+/// downstream, like the non-escaped mode without framing characters. An encoder is created
+/// from a mode with [From], which uses the defaults of [DleEncoder::escaped] for all other
+/// settings, and [DleEncoder::mode] returns the mode of an encoder.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, DleMode};
+///
+/// let dle_encoder = DleEncoder::from(DleMode::NonEscapedFramed);
+/// assert!(!dle_encoder.escape_stx_etx);
+/// assert_eq!(dle_encoder.mode(), Some(DleMode::NonEscapedFramed));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DleMode {
+    /// Escaped mode with STX and ETX framing characters, which is the default
+    EscapedFramed,
+    /// Escaped mode with STX and ETX framing characters, which escapes CR characters as well
+    EscapedFramedCr,
+    /// Escaped mode without framing characters, for frames delimited by an outer protocol
+    EscapedBare,
+    /// Escaped mode without framing characters, which escapes CR characters as well
+    EscapedBareCr,
+    /// Non-escaped mode with DLE STX and DLE ETX framing sequences
+    NonEscapedFramed,
+}
+
+impl From<DleMode> for DleEncoder {
+    fn from(mode: DleMode) -> DleEncoder {
+        let (escape_stx_etx, escape_cr, add_stx_etx) = match mode {
+            DleMode::EscapedFramed => (true, false, true),
+            DleMode::EscapedFramedCr => (true, true, true),
+            DleMode::EscapedBare => (true, false, false),
+            DleMode::EscapedBareCr => (true, true, false),
+            DleMode::NonEscapedFramed => (false, false, true),
+        };
+        DleEncoder {
+            escape_stx_etx,
+            escape_cr,
+            add_stx_etx,
+            ..DleEncoder::escaped()
+        }
+    }
+}
+
+/// Handling of bytes following a decoded frame in the source stream, see
+/// [DleEncoder::decode]
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DecodeErrorKind, DleEncoder, TrailingPolicy};
+///
+/// let dle_encoder = DleEncoder {
+///     trailing_policy: TrailingPolicy::Error,
+///     ..Default::default()
+/// };
+/// let mut decoding_buffer: [u8; 16] = [0; 16];
+/// let mut read_len = 0;
+/// // Another frame may follow
+/// let encoded_frames: [u8; 6] = [0x02, 0x01, 0x03, 0x02, 0x01, 0x03];
+/// assert!(dle_encoder
+///     .decode(&encoded_frames, &mut decoding_buffer, &mut read_len)
+///     .is_ok());
+/// let error = dle_encoder
+///     .decode(&[0x02, 0x01, 0x03, 0x55], &mut decoding_buffer, &mut read_len)
+///     .unwrap_err();
+/// assert_eq!(error.decode_kind(), Some(DecodeErrorKind::TrailingBytes));
+/// assert_eq!(error.offset(), Some(3));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrailingPolicy {
+    /// Bytes following the frame are ignored. The caller can use the read length to process
+    /// them, for example to decode the next frame
+    Ignore,
+    /// Bytes following the frame must start with the start marker of another frame, which
+    /// may be preceded by idle sequences, otherwise a [DecodeErrorKind::TrailingBytes] error
+    /// is returned. This still allows decoding concatenated frames
+    Error,
+    /// The frame must end the source stream, otherwise a [DecodeErrorKind::TrailingBytes]
+    /// error is returned
+    RequireEmpty,
+}
+
+/// Comparison of the encoded forms of a payload for two encoder configurations, see
+/// [DleEncoder::diff_against]
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeDiff {
+    /// The payload encoded with the first configuration
+    pub encoded: Vec<u8>,
+    /// The payload encoded with the other configuration
+    pub other_encoded: Vec<u8>,
+    /// Offset of the first byte which differs between both encoded forms, or [None] if they
+    /// are equal. If one encoded form is a prefix of the other, this is the length of the
+    /// shorter one
+    pub first_difference: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl EncodeDiff {
+    /// Returns whether the encoded forms differ
+    pub fn differs(&self) -> bool {
+        self.first_difference.is_some()
+    }
+}
+
+/// Position of a fragment inside a frame, used by [DleEncoder::encode_fragment]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FragmentRole {
+    /// First fragment of a frame, only the start marker is written
+    First,
+    /// Fragment inside a frame, no framing characters are written
+    Middle,
+    /// Last fragment of a frame, only the end marker is written
+    Last,
+    /// The fragment is a complete frame, both start and end markers are written
+    Whole,
+}
+
+impl FragmentRole {
+    fn has_start(self) -> bool {
+        matches!(self, FragmentRole::First | FragmentRole::Whole)
+    }
+
+    fn has_end(self) -> bool {
+        matches!(self, FragmentRole::Last | FragmentRole::Whole)
+    }
+}
+
+/// Escape sequences of a decoded frame, returned by [DleEncoder::decode_with_stats].
+/// Resync markers are not counted
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DecodeStats {
+    /// Number of DLE characters which were doubled
+    pub doubled_dle: usize,
+    /// Number of characters which were written as DLE followed by their escaped form. This
+    /// includes DLE characters escaped with [DleStyle::Offset]
+    pub escaped_chars: usize,
+}
+
+impl DecodeStats {
+    /// Number of bytes added by the escape sequences, which is one byte per sequence
+    pub fn overhead(&self) -> usize {
+        self.doubled_dle + self.escaped_chars
+    }
+}
+
+/// Details of a rejected escape sequence, returned by [DleEncoder::explain_invalid_escape]
+///
+/// The [core::fmt::Display] implementation describes the mismatch, for example
+/// `found 0x99, expected one of DLE, 0x42, 0x43`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EscapeMismatch {
+    /// The character following the DLE character which was rejected
+    pub found: u8,
+    /// All characters which are accepted after a DLE character with the configuration
+    pub expected: ByteSet,
+}
+
+impl core::fmt::Display for EscapeMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "found {:#04x}, expected one of ", self.found)?;
+        let expected = (0..=u8::MAX).filter(|&byte| self.expected.contains(byte));
+        for (idx, byte) in expected.enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            if byte == DLE_CHAR {
+                f.write_str("DLE")?;
+            } else {
+                write!(f, "{:#04x}", byte)?;
+            }
         }
+        Ok(())
+    }
+}
+
+/// Payload decoded into a stack array by [DleEncoder::decode_bounded]. Payloads which do
+/// not fit into the array are truncated
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecodeResult<const N: usize> {
+    /// The decoded payload, bytes after the used length are zero
+    pub buffer: [u8; N],
+    /// Number of used bytes of the buffer
+    pub len: usize,
+    /// Whether the payload exceeded the capacity `N` and was truncated
+    pub spilled: bool,
+}
+
+impl<const N: usize> DecodeResult<N> {
+    /// The decoded payload, which is truncated to `N` bytes if it spilled
+    pub fn payload(&self) -> &[u8] {
+        self.buffer.get(..self.len).unwrap_or_default()
+    }
+
+    /// View for reading fields of the decoded payload
+    pub fn view(&self) -> PayloadView<'_> {
+        PayloadView::new(self.payload())
     }
 }
 
+/// Progress of decoding a frame into several destination streams, returned by
+/// [DleEncoder::decode_partial] and [DleEncoder::resume_partial]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DecodePartial {
+    /// Number of bytes written to the destination stream
+    pub decoded_len: usize,
+    /// Offset in the source stream up to which the frame was decoded. Escape sequences are
+    /// never split. Once the frame is complete, this is the read length returned by
+    /// [DleEncoder::decode]
+    pub read_len: usize,
+    /// Whether the complete frame was decoded
+    pub complete: bool,
+}
+
+/// Progress of a chunked encoding operation, returned by [DleEncoder::encode_chunk]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EncodeProgress {
+    /// Number of bytes written to the destination stream
+    pub written: usize,
+    /// Number of bytes of the source stream which were encoded
+    pub src_consumed: usize,
+    /// Whether the whole source stream and all requested framing characters were written
+    pub complete: bool,
+}
+
+// All boundaries are checked, so malformed input or short destination streams can never panic
+#[deny(
+    clippy::indexing_slicing,
+    clippy::expect_used,
+    clippy::unwrap_used,
+    clippy::panic
+)]
 impl DleEncoder {
+    /// Create an encoder using the escaped mode with STX and ETX framing characters. Only
+    /// STX, ETX and DLE are escaped. Unlike [Default::default], this preset is guaranteed to
+    /// keep its configuration in future versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::escaped();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder.encode(&[0x02, 0x10], &mut encoding_buffer).unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x42, 0x10, 0x10, 0x03]);
+    /// ```
+    pub fn escaped() -> DleEncoder {
+        DleEncoder {
+            escape_stx_etx: true,
+            escape_cr: false,
+            escape_extra: ByteSet::new(),
+            escape_scheme: EscapeScheme::Additive(0x40),
+            add_stx_etx: true,
+            keep_terminator: false,
+            strict_escapes: true,
+            strict_non_escaped: false,
+            alt_terminator: None,
+            text_format: TextFormat::Char,
+            resync_interval: None,
+            trailing_policy: TrailingPolicy::Ignore,
+            pad_to: None,
+            idle_on_empty: None,
+            lenient_stx: false,
+            lenient_dle: false,
+            dle_escape_style: DleStyle::Double,
+            etx_terminates_only_after_dle: false,
+        }
+    }
+
+    /// Create an encoder using the non-escaped mode with DLE STX and DLE ETX framing
+    /// sequences. Only DLE characters are doubled. All other settings are the same as for
+    /// [DleEncoder::escaped].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::non_escaped();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder.encode(&[0x02, 0x10], &mut encoding_buffer).unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x10, 0x02, 0x02, 0x10, 0x10, 0x10, 0x03]
+    /// );
+    /// ```
+    pub fn non_escaped() -> DleEncoder {
+        DleEncoder {
+            escape_stx_etx: false,
+            ..DleEncoder::escaped()
+        }
+    }
+
+    /// Create an encoder using the escaped mode without framing characters. This is useful
+    /// if frames are delimited by an outer protocol, for example a length field. All other
+    /// settings are the same as for [DleEncoder::escaped].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::raw_escaped_no_framing();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder.encode(&[0x02, 0x10], &mut encoding_buffer).unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x10, 0x42, 0x10, 0x10]);
+    /// ```
+    pub fn raw_escaped_no_framing() -> DleEncoder {
+        DleEncoder {
+            add_stx_etx: false,
+            ..DleEncoder::escaped()
+        }
+    }
+
+    /// Create an encoder whose encoded frames survive text-mode channels, for example
+    /// terminals which translate LF to CRLF. Both CR and LF are escaped in the escaped mode,
+    /// so the encoded frames contain neither character. All other settings use their
+    /// default values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, CR_CHAR, LF_CHAR};
+    ///
+    /// let dle_encoder = DleEncoder::text_safe();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode(&[CR_CHAR, LF_CHAR], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x4d, 0x10, 0x4a, 0x03]);
+    /// ```
+    pub fn text_safe() -> DleEncoder {
+        DleEncoder {
+            escape_cr: true,
+            escape_extra: ByteSet::from_bytes(&[LF_CHAR]),
+            ..Default::default()
+        }
+    }
+
+    /// The start of text character used by the encoder. The control characters are not
+    /// configurable, so this is always [STX_CHAR]. The accessors allow generic code to query
+    /// the control characters from the encoder instead of the module constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::non_escaped();
+    /// let start_marker = [dle_encoder.dle(), dle_encoder.stx()];
+    /// assert_eq!(start_marker, [0x10, 0x02]);
+    /// ```
+    pub const fn stx(&self) -> u8 {
+        STX_CHAR
+    }
+
+    /// The end of text character used by the encoder, which is always [ETX_CHAR]. An
+    /// additional terminator can be configured with `alt_terminator`
+    pub const fn etx(&self) -> u8 {
+        ETX_CHAR
+    }
+
+    /// The data link escape character used by the encoder, which is always [DLE_CHAR]
+    pub const fn dle(&self) -> u8 {
+        DLE_CHAR
+    }
+
+    /// The carriage return character used by the encoder, which is always [CR_CHAR]. It is
+    /// only escaped if `escape_cr` is set
+    pub const fn cr(&self) -> u8 {
+        CR_CHAR
+    }
+
+    /// Returns the mode described by the `escape_stx_etx`, `escape_cr` and `add_stx_etx`
+    /// settings, or [None] if the combination is not one of the meaningful ones listed by
+    /// [DleMode]. This is the case for the non-escaped mode with `escape_cr` set, which is
+    /// ignored in that mode, or without framing characters.
+    pub fn mode(&self) -> Option<DleMode> {
+        match (self.escape_stx_etx, self.escape_cr, self.add_stx_etx) {
+            (true, false, true) => Some(DleMode::EscapedFramed),
+            (true, true, true) => Some(DleMode::EscapedFramedCr),
+            (true, false, false) => Some(DleMode::EscapedBare),
+            (true, true, false) => Some(DleMode::EscapedBareCr),
+            (false, false, true) => Some(DleMode::NonEscapedFramed),
+            (false, _, _) => None,
+        }
+    }
+
     /// This method encodes a given byte stream with ASCII based DLE encoding.
     /// It returns the number of encoded bytes or a DLE error code.
     ///
+    /// If `add_stx_etx` and `escape_stx_etx` are both disabled, raw STX and ETX characters
+    /// of the payload are written unchanged and can not be told apart from the framing
+    /// characters added downstream. Such payloads are rejected with
+    /// [DleError::AmbiguousPayload] by all methods which encode complete frames.
+    ///
     /// # Arguments
     ///
     /// * `source_stream` - The stream to encode
@@ -62,7 +929,20 @@ impl DleEncoder {
     /// println!("Source buffer: {:?}", example_array);
     /// println!("Encoded stream: {:?}", &encoding_buffer[ .. encoded_len])
     /// ```
+    ///
+    /// The result must be used, otherwise the number of encoded bytes is lost:
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// dle_encoder.encode(&[0, 0x02, 0x10], &mut encoding_buffer);
+    /// ```
+    #[must_use = "the encoded length is required to use the destination stream"]
     pub fn encode(&self, source_stream: &[u8], dest_stream: &mut [u8]) -> Result<usize, DleError> {
+        self.check_unambiguous(source_stream)?;
         if self.escape_stx_etx {
             self.encode_escaped(source_stream, dest_stream)
         } else {
@@ -70,6 +950,44 @@ impl DleEncoder {
         }
     }
 
+    /// This method encodes a payload which consists of a single byte. The output is
+    /// identical to calling [DleEncoder::encode] with a one byte source stream, but the
+    /// framing and the single possible escape sequence are written directly instead of
+    /// looping over the source stream. This is useful for protocols which send many single
+    /// byte commands or acknowledgements.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The payload byte to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 8] = [0; 8];
+    /// let encoded_len = dle_encoder.encode_one(0x02, &mut encoding_buffer).unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x42, 0x03]);
+    /// ```
+    #[must_use = "the encoded length is required to use the destination stream"]
+    pub fn encode_one(&self, byte: u8, dest_stream: &mut [u8]) -> Result<usize, DleError> {
+        self.check_unambiguous(&[byte])?;
+        // A resync marker is never due before the first byte, so the frame only consists of
+        // the markers and the encoded byte
+        let (encoded, encoded_len) = self.encode_byte(byte);
+        let encoded = encoded.get(..encoded_len).unwrap_or_default();
+        let encoded_len = if self.add_stx_etx {
+            let encoded_idx = write_bytes(dest_stream, 0, self.start_marker())?;
+            let encoded_idx = write_bytes(dest_stream, encoded_idx, encoded)?;
+            write_bytes(dest_stream, encoded_idx, self.end_marker())?
+        } else {
+            write_bytes(dest_stream, 0, encoded)?
+        };
+        self.pad(dest_stream, encoded_len)
+    }
+
     /// This method encodes a given byte stream with ASCII based DLE encoding.
     /// It explicitely does so in the escaped mode, which is the default
     /// mode.
@@ -78,71 +996,76 @@ impl DleEncoder {
     ///
     /// * `source_stream` - The stream to encode
     /// * `dest_stream` - Encoded stream will be written here
+    #[must_use = "the encoded length is required to use the destination stream"]
     pub fn encode_escaped(
         &self,
         source_stream: &[u8],
         dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        if let Some(idle_len) = self.encode_idle(source_stream, dest_stream)? {
+            return Ok(idle_len);
+        }
+        let encoded_len = self.encode_escaped_framed(
+            source_stream,
+            dest_stream,
+            self.add_stx_etx,
+            self.add_stx_etx,
+        )?;
+        self.pad(dest_stream, encoded_len)
+    }
+
+    fn encode_escaped_framed(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        add_stx: bool,
+        add_etx: bool,
     ) -> Result<usize, DleError> {
         let mut encoded_idx = 0;
-        let mut source_idx = 0;
-        let max_dest_len = dest_stream.len();
-        if self.add_stx_etx {
-            if max_dest_len < 1 {
-                return Err(DleError::StreamTooShort);
-            }
-            dest_stream[encoded_idx] = STX_CHAR;
+        if add_stx {
+            write_byte(dest_stream, encoded_idx, STX_CHAR)?;
             encoded_idx += 1;
         }
-        while encoded_idx < max_dest_len && source_idx < source_stream.len() {
-            let next_byte = source_stream[source_idx];
-            if next_byte == STX_CHAR
-                || next_byte == ETX_CHAR
-                || (self.escape_cr && next_byte == CR_CHAR)
-            {
-                if encoded_idx + 1 >= max_dest_len {
-                    return Err(DleError::StreamTooShort);
+        // All characters which are written as DLE followed by a second character. Plain
+        // characters are the common case, so they are handled with a single set lookup.
+        // Compared to checking for DLE and the escaped characters first, this reduced the
+        // time to encode an escape-free payload by about 27 % in the encode benchmark
+        let special_bytes = self.escaped_bytes().with(DLE_CHAR);
+        for (source_idx, &next_byte) in source_stream.iter().enumerate() {
+            if self.resync_due(source_idx) {
+                encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, SYN_CHAR])?;
+            }
+            if !special_bytes.contains(next_byte) {
+                write_byte(dest_stream, encoded_idx, next_byte)?;
+                encoded_idx += 1;
+            } else {
+                let escaped = if next_byte == DLE_CHAR {
+                    self.escaped_dle()
                 } else {
-                    dest_stream[encoded_idx] = DLE_CHAR;
-                    encoded_idx += 1;
-                    // Next byte will be the actual byte + 0x40. This prevents STX and ETX from
-                    // appearin in the encoded data stream at all, so when polling an encoded
-                    // stream, the transmission can be stopped at ETX. 0x40 was chose at random
-                    // with special requirements:
+                    // Next byte will be the escaped form of the actual byte, which is the
+                    // actual byte + 0x40 for the default escape scheme. This prevents STX and
+                    // ETX from appearin in the encoded data stream at all, so when polling an
+                    // encoded stream, the transmission can be stopped at ETX. 0x40 was chose
+                    // at random with special requirements:
                     // - Prevent going from one control char to another
                     // - Prevent overflow for common characters
-                    dest_stream[encoded_idx] = next_byte + 0x40;
-                }
-            } else if next_byte == DLE_CHAR {
-                if encoded_idx + 1 >= max_dest_len {
-                    return Err(DleError::StreamTooShort);
-                } else {
-                    dest_stream[encoded_idx] = DLE_CHAR;
-                    encoded_idx += 1;
-                    dest_stream[encoded_idx] = DLE_CHAR;
-                }
-            } else {
-                dest_stream[encoded_idx] = next_byte;
+                    self.escape_scheme.escape(next_byte)
+                };
+                encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, escaped])?;
             }
-            encoded_idx += 1;
-            source_idx += 1;
         }
 
-        if source_idx == source_stream.len() {
-            if self.add_stx_etx {
-                if encoded_idx + 1 >= max_dest_len {
-                    return Err(DleError::StreamTooShort);
-                }
-                dest_stream[encoded_idx] = ETX_CHAR;
-                encoded_idx += 1
-            }
-            Ok(encoded_idx)
-        } else {
-            Err(DleError::StreamTooShort)
+        if add_etx {
+            encoded_idx = write_bytes(dest_stream, encoded_idx, self.end_marker())?;
         }
+        Ok(encoded_idx)
     }
 
     /// This method encodes a given byte stream with ASCII based DLE encoding.
-    /// It explicitely does so in the non-escaped mode.
+    /// It explicitely does so in the non-escaped mode. Only DLE characters are doubled,
+    /// raw STX and ETX characters of the payload are written unchanged because the frame
+    /// boundaries are the DLE STX and DLE ETX sequences. Unlike [DleEncoder::encode], this
+    /// method does not reject such payloads if `add_stx_etx` is disabled.
     ///
     /// # Arguments
     ///
@@ -168,186 +1091,330 @@ impl DleEncoder {
     /// println!("Source buffer: {:?}", example_array);
     /// println!("Encoded stream: {:?}", &encoding_buffer[ .. encoded_len])
     /// ```
+    #[must_use = "the encoded length is required to use the destination stream"]
     pub fn encode_non_escaped(
         &self,
         source_stream: &[u8],
         dest_stream: &mut [u8],
     ) -> Result<usize, DleError> {
-        let mut encoded_idx = 0;
-        let mut source_idx = 0;
-        let source_stream_len = source_stream.len();
-        let max_dest_len = dest_stream.len();
-        if self.add_stx_etx {
-            if max_dest_len < 2 {
-                return Err(DleError::StreamTooShort);
-            }
-            dest_stream[encoded_idx] = DLE_CHAR;
-            encoded_idx += 1;
-            dest_stream[encoded_idx] = STX_CHAR;
-            encoded_idx += 1;
+        if let Some(idle_len) = self.encode_idle(source_stream, dest_stream)? {
+            return Ok(idle_len);
         }
+        let encoded_len = self.encode_non_escaped_framed(
+            source_stream,
+            dest_stream,
+            self.add_stx_etx,
+            self.add_stx_etx,
+        )?;
+        self.pad(dest_stream, encoded_len)
+    }
 
-        while encoded_idx < max_dest_len && source_idx < source_stream_len {
-            let next_byte = source_stream[source_idx];
-            if next_byte == DLE_CHAR {
-                if encoded_idx + 1 >= max_dest_len {
-                    return Err(DleError::StreamTooShort);
-                } else {
-                    dest_stream[encoded_idx] = DLE_CHAR;
-                    encoded_idx += 1;
-                    dest_stream[encoded_idx] = DLE_CHAR;
-                }
-            } else {
-                dest_stream[encoded_idx] = next_byte;
-            }
-            encoded_idx += 1;
-            source_idx += 1;
+    /// Reject payloads with raw STX or ETX characters which are written unchanged if
+    /// framing and escaping are disabled, see [DleError::AmbiguousPayload]
+    fn check_unambiguous(&self, source_stream: &[u8]) -> Result<(), DleError> {
+        let ambiguous = !self.escape_stx_etx
+            && !self.add_stx_etx
+            && source_stream
+                .iter()
+                .any(|&byte| byte == STX_CHAR || byte == ETX_CHAR);
+        if ambiguous {
+            return Err(DleError::AmbiguousPayload);
         }
+        Ok(())
+    }
 
-        if source_idx == source_stream_len {
-            if self.add_stx_etx {
-                if encoded_idx + 2 >= max_dest_len {
-                    return Err(DleError::StreamTooShort);
-                }
-                dest_stream[encoded_idx] = DLE_CHAR;
-                encoded_idx += 1;
-                dest_stream[encoded_idx] = ETX_CHAR;
-                encoded_idx += 1;
-            }
-            Ok(encoded_idx)
-        } else {
-            Err(DleError::StreamTooShort)
+    /// Pad an encoded frame with the given length to the configured padded length
+    fn pad(&self, dest_stream: &mut [u8], encoded_len: usize) -> Result<usize, DleError> {
+        let (padded_len, fill) = match self.pad_to {
+            Some(pad_to) => pad_to,
+            None => return Ok(encoded_len),
+        };
+        if encoded_len > padded_len {
+            return Err(DleError::FrameTooLarge);
         }
+        dest_stream
+            .get_mut(encoded_len..padded_len)
+            .ok_or(DleError::StreamTooShort)?
+            .fill(fill);
+        Ok(padded_len)
     }
 
-    /// This method decodes a given byte stream which was encoded with a ASCII
-    /// DLE encoder. It explicitely does so in the escaped mode, which is the default
-    /// mode. It returns the length of the decoded buffer or an error code if
-    /// there is a decoder failure or the destination stream is too short.
+    /// Write the configured idle sequence if the source stream is empty. Returns the length
+    /// of the idle sequence, or [None] if a frame should be encoded instead
+    fn encode_idle(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<Option<usize>, DleError> {
+        let idle = match self.idle_on_empty {
+            Some(idle) if source_stream.is_empty() => idle,
+            _ => return Ok(None),
+        };
+        dest_stream
+            .get_mut(..idle.len())
+            .ok_or(DleError::StreamTooShort)?
+            .copy_from_slice(idle);
+        Ok(Some(idle.len()))
+    }
+
+    fn encode_non_escaped_framed(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        add_stx: bool,
+        add_etx: bool,
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = 0;
+        if add_stx {
+            encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, STX_CHAR])?;
+        }
+
+        for (source_idx, &next_byte) in source_stream.iter().enumerate() {
+            if self.resync_due(source_idx) {
+                encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, SYN_CHAR])?;
+            }
+            if next_byte == DLE_CHAR {
+                encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, DLE_CHAR])?;
+            } else {
+                write_byte(dest_stream, encoded_idx, next_byte)?;
+                encoded_idx += 1;
+            }
+        }
+
+        if add_etx {
+            encoded_idx = write_bytes(dest_stream, encoded_idx, &[DLE_CHAR, ETX_CHAR])?;
+        }
+        Ok(encoded_idx)
+    }
+
+    /// This method encodes a given byte stream with ASCII based DLE encoding and returns
+    /// the encoded stream. If the encoded stream is identical to the source stream, which is
+    /// the case if no framing characters are added and no byte needs to be escaped, the
+    /// source stream is returned without copying it. Errors are returned like
+    /// [DleEncoder::encode] returns them, for example [DleError::AmbiguousPayload] for raw
+    /// STX or ETX characters if framing and escaping are disabled or
+    /// [DleError::FrameTooLarge] if the frame is longer than the padded length.
     ///
     /// # Arguments
     ///
-    /// * `source_stream` - The stream to decode
-    /// * `dest_stream` - Decoded stream will be written here
-    /// * `read_len` - The number of read bytes in the source stream will be
-    ///   assigned to this variable
+    /// * `source_stream` - The stream to encode
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::borrow::Cow;
     /// use dle_encoder::DleEncoder;
     ///
-    /// let dle_encoder = DleEncoder::default();
-    /// let mut decoding_buffer: [u8; 16] = [0; 16];
-    /// let encoded_array: [u8; 4] = [0x02, 0x10, 0x02 + 0x40, 0x03];
-    /// let mut read_len = 0;
-    /// let decode_result = dle_encoder.decode(
-    ///     &encoded_array, &mut decoding_buffer, &mut read_len
+    /// let dle_encoder = DleEncoder {
+    ///     add_stx_etx: false,
+    ///     ..Default::default()
+    /// };
+    /// assert!(matches!(
+    ///     dle_encoder.encode_cow(&[0x01, 0x42]),
+    ///     Ok(Cow::Borrowed(_))
+    /// ));
+    /// assert_eq!(
+    ///     dle_encoder.encode_cow(&[0x01, 0x02]).unwrap().as_ref(),
+    ///     &[0x01, 0x10, 0x42]
     /// );
-    /// assert!(decode_result.is_ok());
-    /// let decoded_len = decode_result.unwrap();
-    /// assert_eq!(decoded_len, 1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn encode_cow<'a>(&self, source_stream: &'a [u8]) -> Result<Cow<'a, [u8]>, DleError> {
+        self.check_unambiguous(source_stream)?;
+        let pass_through = !self.add_stx_etx
+            && self.pad_to.is_none()
+            && (self.idle_on_empty.is_none() || !source_stream.is_empty())
+            && self.resync_marker_count(source_stream.len()) == 0
+            && source_stream
+                .iter()
+                .all(|&byte| byte != DLE_CHAR && !(self.escape_stx_etx && self.needs_escape(byte)));
+        if pass_through {
+            return Ok(Cow::Borrowed(source_stream));
+        }
+        let mut encoded = vec![0; self.max_encoded_len(source_stream.len())];
+        let encoded_len = if self.escape_stx_etx {
+            self.encode_escaped(source_stream, &mut encoded)?
+        } else {
+            self.encode_non_escaped(source_stream, &mut encoded)?
+        };
+        encoded.truncate(encoded_len);
+        Ok(Cow::Owned(encoded))
+    }
+
+    /// This method re-encodes the first frame of a given encoded stream, which is equivalent
+    /// to decoding the frame and encoding the decoded payload again. This is useful when
+    /// forwarding frames. If the body of the frame contains no DLE characters and no other
+    /// bytes which need to be escaped, the payload is identical to the body and the frame
+    /// is returned without copying it, only dropping the bytes after its end marker. Frames
+    /// ending with the alternative terminator, padding, resync markers, idle sequences and
+    /// kept terminators always require a full decoding and encoding pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw_frame` - The encoded stream starting with the frame to re-encode
+    ///
+    /// # Examples
     ///
-    /// println!("Source buffer: {:?}", encoded_array);
-    /// println!("Encoded stream: {:?}", &decoding_buffer[ .. decoded_len])
     /// ```
-    pub fn decode(
-        &self,
-        source_stream: &[u8],
-        dest_stream: &mut [u8],
-        read_len: &mut usize,
-    ) -> Result<usize, DleError> {
+    /// use std::borrow::Cow;
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let reframed = dle_encoder.reframe(&[0x02, 0x01, 0x42, 0x03, 0x55]).unwrap();
+    /// assert!(matches!(reframed, Cow::Borrowed(&[0x02, 0x01, 0x42, 0x03])));
+    /// let reframed = dle_encoder.reframe(&[0x02, 0x10, 0x10, 0x03]).unwrap();
+    /// assert_eq!(reframed.as_ref(), &[0x02, 0x10, 0x10, 0x03]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn reframe<'a>(&self, raw_frame: &'a [u8]) -> Result<Cow<'a, [u8]>, DleError> {
+        if let Some(frame_len) = self.escape_free_frame_len(raw_frame) {
+            return Ok(Cow::Borrowed(
+                raw_frame.get(..frame_len).unwrap_or(raw_frame),
+            ));
+        }
+        let mut decoded = vec![0; raw_frame.len()];
+        let mut read_len = 0;
+        let decoded_len = self.decode(raw_frame, &mut decoded, &mut read_len)?;
+        let decoded = decoded.get(..decoded_len).unwrap_or_default();
+        Ok(Cow::Owned(self.encode_cow(decoded)?.into_owned()))
+    }
+
+    /// Returns the length of the frame at the start of the given stream if its body is
+    /// copied verbatim when decoding and encoding it with this configuration
+    #[cfg(feature = "alloc")]
+    fn escape_free_frame_len(&self, raw_frame: &[u8]) -> Option<usize> {
+        if !self.add_stx_etx
+            || self.keep_terminator
+            || self.alt_terminator.is_some()
+            || self.pad_to.is_some()
+            || self.resync_interval.is_some()
+            || self.idle_on_empty.is_some()
+            || (self.escape_stx_etx && self.etx_terminates_only_after_dle)
+        {
+            return None;
+        }
         if self.escape_stx_etx {
-            self.decode_escaped(source_stream, dest_stream, read_len)
+            let special_bytes = self.escaped_bytes().with(DLE_CHAR);
+            let body = raw_frame.strip_prefix(&[STX_CHAR])?;
+            let body_len = body.iter().position(|&byte| special_bytes.contains(byte))?;
+            (body.get(body_len) == Some(&ETX_CHAR)).then(|| body_len + 2)
         } else {
-            self.decode_non_escaped(source_stream, dest_stream, read_len)
+            let body = raw_frame.strip_prefix(&[DLE_CHAR, STX_CHAR])?;
+            let body_len = body.iter().position(|&byte| byte == DLE_CHAR)?;
+            let escape_free = body
+                .get(..body_len)?
+                .iter()
+                .all(|&byte| !self.is_raw_control(byte));
+            (escape_free && body.get(body_len + 1) == Some(&ETX_CHAR)).then(|| body_len + 4)
         }
     }
 
-    /// This method decodes a given byte stream which was encoded with a ASCII
-    /// DLE encoder. It explicitely does so in the escaped mode, which is the default
-    /// mode. It returns the length of the decoded buffer or an error code if
-    /// there is a decoder failure or the destination stream is too short.
+    /// This method encodes a given byte stream with ASCII based DLE encoding into a
+    /// fixed-capacity [heapless::Vec]. This allows returning owned encoded streams without
+    /// a global allocator. [DleError::StreamTooShort] is returned if the capacity `N` is
+    /// exhausted.
     ///
     /// # Arguments
     ///
-    /// * `source_stream` - The stream to decode
-    /// * `dest_stream` - Decoded stream will be written here
-    /// * `read_len` - The number of read bytes in the source stream will be
-    ///   assigned to this variable
-    pub fn decode_escaped(
+    /// * `source_stream` - The stream to encode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded = dle_encoder.encode_heapless::<16>(&[0, 0x02, 0x10]).unwrap();
+    /// assert_eq!(encoded.as_slice(), &[0x02, 0, 0x10, 0x42, 0x10, 0x10, 0x03]);
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn encode_heapless<const N: usize>(
+        &self,
+        source_stream: &[u8],
+    ) -> Result<heapless::Vec<u8, N>, DleError> {
+        let mut encoded = heapless::Vec::new();
+        // Can not fail because the new length is the capacity
+        let _ = encoded.resize(N, 0);
+        let encoded_len = self.encode(source_stream, &mut encoded)?;
+        encoded.truncate(encoded_len);
+        Ok(encoded)
+    }
+
+    /// This method encodes a fragment of a larger payload which is transmitted as a single
+    /// frame. The fragment is always escaped according to the encoder configuration, but
+    /// the start and end markers are only written as specified by the fragment role. The
+    /// concatenation of all encoded fragments is a regular frame. The `add_stx_etx` setting
+    /// is ignored by this method. A fragment with the role [FragmentRole::Whole] is encoded
+    /// like a framed payload by [DleEncoder::encode], including the padding and the idle
+    /// sequence for empty payloads. The other fragments are never padded, because the length
+    /// of the complete frame is not known.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The fragment to encode
+    /// * `role` - Position of the fragment inside the frame
+    /// * `dest_stream` - Encoded fragment will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, FragmentRole};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let mut encoded_len = dle_encoder
+    ///     .encode_fragment(&[0x01, 0x02], FragmentRole::First, &mut encoding_buffer)
+    ///     .unwrap();
+    /// encoded_len += dle_encoder
+    ///     .encode_fragment(&[0x03], FragmentRole::Last, &mut encoding_buffer[encoded_len..])
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x02, 0x01, 0x10, 0x42, 0x10, 0x43, 0x03]
+    /// );
+    /// ```
+    #[must_use = "the encoded length is required to use the destination stream"]
+    pub fn encode_fragment(
         &self,
         source_stream: &[u8],
+        role: FragmentRole,
         dest_stream: &mut [u8],
-        read_len: &mut usize,
     ) -> Result<usize, DleError> {
-        let mut encoded_idx = 0;
-        let mut decoded_idx = 0;
-        let source_stream_len = source_stream.len();
-        let dest_stream_len = dest_stream.len();
-        *read_len = 0;
-        if dest_stream_len < 1 {
-            return Err(DleError::StreamTooShort);
-        }
-        if source_stream[encoded_idx] != STX_CHAR {
-            return Err(DleError::DecodingError);
-        }
-        encoded_idx += 1;
-        while encoded_idx < source_stream_len - 1
-            && decoded_idx < dest_stream_len
-            && source_stream[encoded_idx] != ETX_CHAR
-            && source_stream[encoded_idx] != STX_CHAR
-        {
-            if source_stream[encoded_idx] == DLE_CHAR {
-                if encoded_idx + 1 >= source_stream_len {
-                    *read_len = source_stream_len;
-                    return Err(DleError::DecodingError);
-                }
-                let next_byte = source_stream[encoded_idx + 1];
-                if next_byte == DLE_CHAR {
-                    dest_stream[decoded_idx] = next_byte;
-                } else if next_byte == STX_CHAR + 0x40
-                    || next_byte == ETX_CHAR + 0x40
-                    || (self.escape_cr && next_byte == CR_CHAR + 0x40)
-                {
-                    dest_stream[decoded_idx] = next_byte - 0x40;
-                } else {
-                    *read_len = encoded_idx + 2;
-                    return Err(DleError::DecodingError);
-                }
-                encoded_idx += 1
+        if role == FragmentRole::Whole {
+            let dle_encoder = DleEncoder {
+                add_stx_etx: true,
+                ..*self
+            };
+            return if self.escape_stx_etx {
+                dle_encoder.encode_escaped(source_stream, dest_stream)
             } else {
-                dest_stream[decoded_idx] = source_stream[encoded_idx];
-            }
-            encoded_idx += 1;
-            decoded_idx += 1
+                dle_encoder.encode_non_escaped(source_stream, dest_stream)
+            };
         }
-
-        if source_stream[encoded_idx] != ETX_CHAR {
-            if decoded_idx == dest_stream_len {
-                *read_len = 0;
-                Err(DleError::StreamTooShort)
-            } else {
-                *read_len = encoded_idx + 1;
-                Err(DleError::DecodingError)
-            }
+        if self.escape_stx_etx {
+            self.encode_escaped_framed(source_stream, dest_stream, role.has_start(), role.has_end())
         } else {
-            *read_len = encoded_idx + 1;
-            Ok(decoded_idx)
+            self.encode_non_escaped_framed(
+                source_stream,
+                dest_stream,
+                role.has_start(),
+                role.has_end(),
+            )
         }
     }
 
-    /// This method decodes a given byte stream which was encoded with a ASCII
-    /// DLE encoder. It explicitely does so in the non-escaped mode.
-    /// It returns the length of the decoded buffer or an error code if
-    /// there is a decoder failure or the destination stream is too short.
+    /// This method encodes a message with a header consisting of a message type and the
+    /// payload length as big-endian 16 bit value. The header and the payload are escaped
+    /// together and framed as configured, so the frame payload is
+    /// `[msg_type, len_hi, len_lo, payload...]`. Payloads longer than [u16::MAX] bytes are
+    /// rejected with [DleError::FrameTooLarge]. Use [DleEncoder::decode_typed] to decode the
+    /// message.
     ///
     /// # Arguments
     ///
-    /// * `source_stream` - The stream to decode
-    /// * `dest_stream` - Decoded stream will be written here
-    /// * `read_len` - The number of read bytes in the source stream will be
-    ///   assigned to this variable
+    /// * `msg_type` - The message type written as first byte of the header
+    /// * `payload` - The message payload
+    /// * `dest_stream` - Encoded message will be written here
     ///
     /// # Examples
     ///
@@ -355,423 +1422,8664 @@ impl DleEncoder {
     /// use dle_encoder::DleEncoder;
     ///
     /// let dle_encoder = DleEncoder::default();
-    /// let mut decoding_buffer: [u8; 16] = [0; 16];
-    /// let encoded_array: [u8; 6] = [0x10, 0x02, 0x02, 0x03, 0x10, 0x03];
-    /// let mut read_len = 0;
-    /// let decode_result = dle_encoder.decode_non_escaped(
-    ///     &encoded_array, &mut decoding_buffer, &mut read_len
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_typed(0x07, &[0x02], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x02, 0x07, 0x00, 0x01, 0x10, 0x42, 0x03]
     /// );
-    /// assert!(decode_result.is_ok());
-    /// let decoded_len = decode_result.unwrap();
-    /// assert_eq!(decoded_len, 2);
+    /// ```
+    pub fn encode_typed(
+        &self,
+        msg_type: u8,
+        payload: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let payload_len = u16::try_from(payload.len()).map_err(|_| DleError::FrameTooLarge)?;
+        let [len_hi, len_lo] = payload_len.to_be_bytes();
+        self.check_unambiguous(&[msg_type, len_hi, len_lo])?;
+        self.check_unambiguous(payload)?;
+        let (header_role, payload_role) = if self.add_stx_etx {
+            (FragmentRole::First, FragmentRole::Last)
+        } else {
+            (FragmentRole::Middle, FragmentRole::Middle)
+        };
+        let header_len =
+            self.encode_fragment(&[msg_type, len_hi, len_lo], header_role, dest_stream)?;
+        let payload_len =
+            self.encode_fragment(payload, payload_role, dest_tail(dest_stream, header_len)?)?;
+        self.pad(dest_stream, header_len + payload_len)
+    }
+
+    /// This method encodes a given byte stream like [DleEncoder::encode], but leaves a slot
+    /// with the given number of bytes before the end marker untouched. This allows filling
+    /// in a checksum which is computed after encoding without copying the frame again.
+    /// Returns the offset of the reserved slot. The frame ends after the slot and the end
+    /// marker, which is one byte in the escaped mode and two bytes in the non-escaped mode,
+    /// or directly after the slot if `add_stx_etx` is disabled. The bytes written into the
+    /// slot are not escaped, so they must not contain DLE characters, and in the escaped
+    /// mode no other characters which need to be escaped.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `reserve_tail` - The number of bytes reserved before the end marker
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
     ///
-    /// println!("Source buffer: {:?}", encoded_array);
-    /// println!("Encoded stream: {:?}", &decoding_buffer[ .. decoded_len])
     /// ```
-    pub fn decode_non_escaped(
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let slot_offset = dle_encoder
+    ///     .encode_reserve(&[0x01, 0x02], 1, &mut encoding_buffer)
+    ///     .unwrap();
+    /// encoding_buffer[slot_offset] = 0x55;
+    /// assert_eq!(&encoding_buffer[..slot_offset + 2], &[0x02, 0x01, 0x10, 0x42, 0x55, 0x03]);
+    /// ```
+    pub fn encode_reserve(
         &self,
         source_stream: &[u8],
+        reserve_tail: usize,
         dest_stream: &mut [u8],
-        read_len: &mut usize,
     ) -> Result<usize, DleError> {
-        let mut encoded_idx = 0;
-        let mut decoded_idx = 0;
-        let source_stream_len = source_stream.len();
-        let dest_stream_len = dest_stream.len();
-        *read_len = 0;
-
-        if dest_stream_len < 2 {
-            return Err(DleError::StreamTooShort);
-        }
-        if source_stream[encoded_idx] != DLE_CHAR {
-            return Err(DleError::DecodingError);
-        }
-        encoded_idx += 1;
-        if source_stream[encoded_idx] != STX_CHAR {
-            *read_len = 1;
-            return Err(DleError::DecodingError);
-        }
-        encoded_idx += 1;
-        while encoded_idx < source_stream_len && decoded_idx < dest_stream_len {
-            if source_stream[encoded_idx] == DLE_CHAR {
-                if encoded_idx + 1 >= source_stream_len {
-                    *read_len = encoded_idx;
-                    return Err(DleError::DecodingError);
-                }
-                let next_byte = source_stream[encoded_idx + 1];
-                if next_byte == STX_CHAR {
-                    // Set read_len so the DLE/STX char combination is preserved
-                    // It could be the start of another frame
-                    *read_len = encoded_idx;
-                    return Err(DleError::DecodingError);
-                } else if next_byte == DLE_CHAR {
-                    dest_stream[decoded_idx] = next_byte;
-                    encoded_idx += 1;
-                } else if next_byte == ETX_CHAR {
-                    // End of stream reached
-                    *read_len = encoded_idx + 2;
-                    return Ok(decoded_idx);
-                } else {
-                    *read_len = encoded_idx;
-                    return Err(DleError::DecodingError);
-                }
-            } else {
-                dest_stream[decoded_idx] = source_stream[encoded_idx];
-            }
-            encoded_idx += 1;
-            decoded_idx += 1;
-        }
+        self.check_unambiguous(source_stream)?;
+        let (body_role, end_role) = if self.add_stx_etx {
+            (FragmentRole::First, FragmentRole::Last)
+        } else {
+            (FragmentRole::Middle, FragmentRole::Middle)
+        };
+        let slot_offset = self.encode_fragment(source_stream, body_role, dest_stream)?;
+        let slot_end = slot_offset
+            .checked_add(reserve_tail)
+            .ok_or(DleError::StreamTooShort)?;
+        let end_len = self.encode_fragment(&[], end_role, dest_tail(dest_stream, slot_end)?)?;
+        self.pad(dest_stream, slot_end + end_len)?;
+        Ok(slot_offset)
+    }
 
-        if decoded_idx == dest_stream_len {
-            // So far we did not find anything wrong here, let the user try
-            // again
-            *read_len = 0;
-            Err(DleError::StreamTooShort)
+    /// This method encodes a sparse set of updates as a single frame. Each update consists
+    /// of an offset and the value at this offset, which is useful to transmit only the
+    /// changed fields of a larger record. The updates are serialized as the big endian
+    /// offset followed by the value, so the frame payload is
+    /// `[offset_hi, offset_lo, value, ...]`, and escaped like any other payload. Use
+    /// [DleEncoder::decode_sparse] to decode the updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - Pairs of offsets and values
+    /// * `dest_stream` - Encoded frame will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_sparse(&[(0x0004, 0x02), (0x0100, 0x55)], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x02, 0x00, 0x04, 0x10, 0x42, 0x01, 0x00, 0x55, 0x03]
+    /// );
+    /// ```
+    pub fn encode_sparse(
+        &self,
+        updates: &[(u16, u8)],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let (start_role, end_role) = if self.add_stx_etx {
+            (FragmentRole::First, FragmentRole::Last)
         } else {
-            *read_len = encoded_idx;
-            Err(DleError::DecodingError)
+            (FragmentRole::Middle, FragmentRole::Middle)
+        };
+        let mut encoded_len = self.encode_fragment(&[], start_role, dest_stream)?;
+        for &(offset, value) in updates {
+            let [offset_hi, offset_lo] = offset.to_be_bytes();
+            self.check_unambiguous(&[offset_hi, offset_lo, value])?;
+            encoded_len += self.encode_fragment(
+                &[offset_hi, offset_lo, value],
+                FragmentRole::Middle,
+                dest_tail(dest_stream, encoded_len)?,
+            )?;
         }
+        encoded_len += self.encode_fragment(&[], end_role, dest_tail(dest_stream, encoded_len)?)?;
+        self.pad(dest_stream, encoded_len)
     }
 
-    // TODO: Implement for the generic Read Trait
-    //pub fn decode_from_reader(source: &impl std::io::Read) {}
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// This method encodes a given byte stream as a frame with a length header for
+    /// receivers which read a fixed header and then exactly the announced number of bytes.
+    /// The start marker is followed by the length of the encoded body as big endian [u16],
+    /// then the encoded body and the end marker follow. The length bytes are not escaped, so
+    /// they can be equal to control characters and the frame can only be decoded with
+    /// [DleEncoder::decode_framed_len]. Encoded bodies longer than [u16::MAX] bytes are
+    /// rejected with [DleError::FrameTooLarge]. The framing characters are always written,
+    /// independently of `add_stx_etx`, and no padding or idle sequence is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded frame will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_framed_len(&[0x01, 0x10], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x02, 0x00, 0x03, 0x01, 0x10, 0x10, 0x03]
+    /// );
+    /// ```
+    pub fn encode_framed_len(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let len_offset = write_bytes(dest_stream, 0, self.start_marker())?;
+        // The length is patched after the body was encoded
+        let body_offset = write_bytes(dest_stream, len_offset, &[0, 0])?;
+        let body_len = self.encode_fragment(
+            source_stream,
+            FragmentRole::Middle,
+            dest_tail(dest_stream, body_offset)?,
+        )?;
+        let encoded_len = write_bytes(dest_stream, body_offset + body_len, self.end_marker())?;
+        let body_len = u16::try_from(body_len).map_err(|_| DleError::FrameTooLarge)?;
+        write_bytes(dest_stream, len_offset, &body_len.to_be_bytes())?;
+        Ok(encoded_len)
+    }
 
-    const TEST_ARRAY_0: [u8; 5] = [0, 0, 0, 0, 0];
-    const TEST_ARRAY_1: [u8; 3] = [0, DLE_CHAR, 5];
-    const TEST_ARRAY_2: [u8; 3] = [0, STX_CHAR, 5];
-    const TEST_ARRAY_3: [u8; 3] = [0, CR_CHAR, ETX_CHAR];
-    const TEST_ARRAY_4: [u8; 3] = [DLE_CHAR, ETX_CHAR, STX_CHAR];
+    /// This method encodes a given byte stream and appends a CRC after the end marker of the
+    /// frame. The CRC is computed over the complete encoded frame, including the start and
+    /// end markers and the padding configured with `pad_to`, and is appended in big endian
+    /// byte order without escaping it. The CRC-16/CCITT-FALSE algorithm with the polynomial
+    /// 0x1021 and the initial value 0xFFFF is used. The frame is always framed with start
+    /// and end markers, independently of `add_stx_etx`, and no idle sequence is written for
+    /// empty payloads.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded frame followed by the CRC will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_with_crc(&[0x01, 0x02], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(encoded_len, 7);
+    /// assert_eq!(&encoding_buffer[..5], &[0x02, 0x01, 0x10, 0x42, 0x03]);
+    /// ```
+    pub fn encode_with_crc(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let dle_encoder = DleEncoder {
+            add_stx_etx: true,
+            idle_on_empty: None,
+            ..*self
+        };
+        let frame_len = dle_encoder.encode(source_stream, dest_stream)?;
+        let crc = crc16(dest_stream.get(..frame_len).unwrap_or_default());
+        write_bytes(dest_stream, frame_len, &crc.to_be_bytes())
+    }
+
+    /// This method encodes a given byte stream followed by its CRC into a single frame.
+    /// The CRC is updated while each source byte is escaped, so the source stream is only
+    /// traversed once, which reduces the latency compared to calculating the CRC first.
+    /// Unlike [DleEncoder::encode_with_crc], the CRC is computed over the payload and is
+    /// appended in big endian byte order inside the frame, escaped like the payload, before
+    /// the end marker. The same CRC-16/CCITT-FALSE algorithm is used. The decoded payload
+    /// of the frame is the source stream followed by the CRC, and the CRC of the complete
+    /// decoded payload is zero for an intact frame. The frame is always framed with start
+    /// and end markers, independently of `add_stx_etx`. No resync markers, padding or idle
+    /// sequence are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded frame including the CRC will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_crc_single_pass(b"123456789", &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], b"\x02123456789\x29\xb1\x03");
+    /// ```
+    pub fn encode_crc_single_pass(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = write_bytes(dest_stream, 0, self.start_marker())?;
+        let mut crc = CRC16_INIT;
+        for &byte in source_stream {
+            crc = crc16_update(crc, byte);
+            let (encoded, encoded_len) = self.encode_byte(byte);
+            encoded_idx = write_bytes(
+                dest_stream,
+                encoded_idx,
+                encoded.get(..encoded_len).unwrap_or_default(),
+            )?;
+        }
+        for byte in crc.to_be_bytes() {
+            let (encoded, encoded_len) = self.encode_byte(byte);
+            encoded_idx = write_bytes(
+                dest_stream,
+                encoded_idx,
+                encoded.get(..encoded_len).unwrap_or_default(),
+            )?;
+        }
+        write_bytes(dest_stream, encoded_idx, self.end_marker())
+    }
+
+    /// This method decodes a given byte stream which was encoded with a ASCII
+    /// DLE encoder. It explicitely does so in the escaped mode, which is the default
+    /// mode. It returns the length of the decoded buffer or an error code if
+    /// there is a decoder failure or the destination stream is too short.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// If decoding fails, `read_len` indicates how many source bytes the caller should
+    /// discard before retrying:
+    ///
+    /// * [DleError::StreamTooShort]: 0, so the frame can be decoded again with a larger
+    ///   destination stream
+    /// * [DecodeErrorKind::MissingStart]: 0 if the first byte is not a start marker and 1
+    ///   if the second byte of the non-escaped start marker is wrong. No frame starts at the
+    ///   beginning of the source stream, so the caller should search for the next start
+    ///   marker, for example by discarding at least one byte
+    /// * Other decoding errors: the number of bytes of the erroneous frame which were read.
+    ///   If the source stream ended before the frame was complete, this is the length of the
+    ///   source stream
+    ///
+    /// Skipped idle sequences configured with `idle_on_empty` are added to the read length,
+    /// except for [DleError::StreamTooShort].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_array: [u8; 4] = [0x02, 0x10, 0x02 + 0x40, 0x03];
+    /// let mut read_len = 0;
+    /// let decode_result = dle_encoder.decode(
+    ///     &encoded_array, &mut decoding_buffer, &mut read_len
+    /// );
+    /// assert!(decode_result.is_ok());
+    /// let decoded_len = decode_result.unwrap();
+    /// assert_eq!(decoded_len, 1);
+    ///
+    /// println!("Source buffer: {:?}", encoded_array);
+    /// println!("Encoded stream: {:?}", &decoding_buffer[ .. decoded_len])
+    /// ```
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        let idle_len = self.idle_prefix_len(source_stream);
+        let source_stream = source_tail(source_stream, idle_len);
+        let result = if self.escape_stx_etx {
+            self.decode_escaped(source_stream, dest_stream, read_len)
+        } else {
+            self.decode_non_escaped(source_stream, dest_stream, read_len)
+        };
+        if idle_len > 0 && result != Err(DleError::StreamTooShort) {
+            *read_len += idle_len;
+        }
+        result.map_err(|error| error.shifted(idle_len))
+    }
+
+    /// This method encodes as much of the given byte stream as fits into the destination
+    /// stream. Instead of discarding all progress if the destination is too short, the number
+    /// of written bytes and consumed source bytes is returned. The caller can flush the
+    /// written bytes and call this method again with the remaining source stream. Escape
+    /// sequences are never split between two calls.
+    ///
+    /// The fragment role specifies which framing characters are written, which allows
+    /// suppressing the end marker until the final call. If the start marker was written,
+    /// which is the case if any bytes were written, subsequent calls should use
+    /// [FragmentRole::Middle] or [FragmentRole::Last]. The `add_stx_etx` setting is ignored
+    /// by this method. If padding or an idle sequence is configured, a frame with the role
+    /// [FragmentRole::Whole] is only written if it fits into the destination stream
+    /// completely, because the padded length depends on the complete frame. Fragments with
+    /// other roles are never padded or replaced by the idle sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    /// * `role` - Specifies which framing characters are written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, FragmentRole};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let payload: [u8; 4] = [0x01, 0x02, 0x03, 0x04];
+    /// let mut encoded = Vec::new();
+    /// let mut chunk: [u8; 3] = [0; 3];
+    /// let mut consumed = 0;
+    /// let mut role = FragmentRole::Whole;
+    /// loop {
+    ///     let progress = dle_encoder.encode_chunk(&payload[consumed..], &mut chunk, role);
+    ///     encoded.extend_from_slice(&chunk[..progress.written]);
+    ///     consumed += progress.src_consumed;
+    ///     if progress.complete {
+    ///         break;
+    ///     }
+    ///     if progress.written > 0 {
+    ///         role = FragmentRole::Last;
+    ///     }
+    /// }
+    /// assert_eq!(encoded, [0x02, 0x01, 0x10, 0x42, 0x10, 0x43, 0x04, 0x03]);
+    /// ```
+    pub fn encode_chunk(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        role: FragmentRole,
+    ) -> EncodeProgress {
+        let mut progress = EncodeProgress {
+            written: 0,
+            src_consumed: 0,
+            complete: false,
+        };
+        if role == FragmentRole::Whole && (self.pad_to.is_some() || self.idle_on_empty.is_some()) {
+            if let Ok(written) = self.encode_fragment(source_stream, role, dest_stream) {
+                progress.written = written;
+                progress.src_consumed = source_stream.len();
+                progress.complete = true;
+            }
+            return progress;
+        }
+        if role.has_start() {
+            match write_bytes(dest_stream, 0, self.start_marker()) {
+                Ok(end) => progress.written = end,
+                Err(_) => return progress,
+            }
+        }
+        for &byte in source_stream {
+            let (encoded, encoded_len) = self.encode_byte(byte);
+            let encoded = encoded.get(..encoded_len).unwrap_or_default();
+            match write_bytes(dest_stream, progress.written, encoded) {
+                Ok(end) => progress.written = end,
+                Err(_) => return progress,
+            }
+            progress.src_consumed += 1;
+        }
+        if role.has_end() {
+            match write_bytes(dest_stream, progress.written, self.end_marker()) {
+                Ok(end) => progress.written = end,
+                Err(_) => return progress,
+            }
+        }
+        progress.complete = true;
+        progress
+    }
+
+    /// This method encodes a given byte stream with ASCII based DLE encoding directly into
+    /// a [core::fmt::Write] implementation, which allows embedding encoded frames in textual
+    /// output without a byte buffer. The frame is encoded like [DleEncoder::encode], and the
+    /// format of its bytes is configured with the `text_format` setting. Returns the length
+    /// of the encoded frame in bytes, which is not the number of written characters: the
+    /// hexadecimal format writes two characters per byte, and the character format writes
+    /// bytes above 0x7f as characters with a two byte UTF-8 encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `writer` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, TextFormat};
+    ///
+    /// let dle_encoder = DleEncoder {
+    ///     text_format: TextFormat::Hex,
+    ///     ..Default::default()
+    /// };
+    /// let mut text = String::from("frame: ");
+    /// let encoded_len = dle_encoder.encode_fmt(&[0x02, 0xab], &mut text).unwrap();
+    /// assert_eq!(encoded_len, 5);
+    /// assert_eq!(text, "frame: 021042AB03");
+    /// ```
+    pub fn encode_fmt(
+        &self,
+        source_stream: &[u8],
+        writer: &mut impl core::fmt::Write,
+    ) -> Result<usize, DleError> {
+        let escape_range = 0..source_stream.len();
+        // Frames which exceed the padded length are rejected before anything is written
+        if self.pad_to.is_some() {
+            self.encode_frame_with(source_stream, escape_range.clone(), |_| Ok(()))?;
+        }
+        self.encode_frame_with(source_stream, escape_range, |bytes| {
+            for &byte in bytes {
+                match self.text_format {
+                    TextFormat::Char => writer.write_char(char::from(byte)),
+                    TextFormat::Hex => write!(writer, "{:02X}", byte),
+                }
+                .map_err(|_| DleError::WriteFailed)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// This method encodes a given byte stream and writes the encoded frame as uppercase
+    /// hexadecimal ASCII characters, two per byte, for transports which only accept
+    /// printable characters. Returns the number of written characters, which is twice the
+    /// length of the frame returned by [DleEncoder::encode]. The destination stream must be
+    /// large enough for the hexadecimal form. Frames are decoded with
+    /// [DleEncoder::decode_hex].
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Hexadecimal form of the encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_hex(&[0x02, 0xab], &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], b"021042AB03");
+    /// ```
+    pub fn encode_hex(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let encoded_len = self.encode(source_stream, dest_stream)?;
+        // The frame is expanded in place starting with its last byte, so each byte is read
+        // before it is overwritten. The first write fails if the destination is too short
+        for idx in (0..encoded_len).rev() {
+            let byte = dest_stream.get(idx).copied().unwrap_or_default();
+            let digit = |nibble: u8| HEX_DIGITS.get(usize::from(nibble)).copied();
+            let digits = [digit(byte >> 4), digit(byte & 0x0f)];
+            write_bytes(dest_stream, 2 * idx, &digits.map(Option::unwrap_or_default))?;
+        }
+        Ok(2 * encoded_len)
+    }
+
+    /// This method writes a given byte stream between STX and ETX without escaping it, which
+    /// keeps frames readable for debugging. Unlike the non-escaped mode, DLE characters
+    /// are not doubled, so [DleError::AmbiguousPayload] is returned if the payload contains
+    /// STX, ETX or DLE characters. The frame is the same one [DleEncoder::encode] writes in
+    /// the escaped mode for such payloads. The other settings of the configuration are
+    /// ignored. Frames are decoded with [DleEncoder::decode_framed_no_escape].
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::non_escaped();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_framed_no_escape(b"ok", &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, b'o', b'k', 0x03]);
+    /// assert_eq!(
+    ///     dle_encoder.encode_framed_no_escape(&[0x10], &mut encoding_buffer),
+    ///     Err(DleError::AmbiguousPayload)
+    /// );
+    /// ```
+    pub fn encode_framed_no_escape(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        if source_stream
+            .iter()
+            .any(|&byte| byte == STX_CHAR || byte == ETX_CHAR || byte == DLE_CHAR)
+        {
+            return Err(DleError::AmbiguousPayload);
+        }
+        let encoded_idx = write_bytes(dest_stream, 0, &[STX_CHAR])?;
+        let encoded_idx = write_bytes(dest_stream, encoded_idx, source_stream)?;
+        write_bytes(dest_stream, encoded_idx, &[ETX_CHAR])
+    }
+
+    /// This method encodes a given byte stream, but only escapes the bytes inside the given
+    /// range of the source stream. All other bytes are copied verbatim, which allows
+    /// transmitting a fixed header without escaping it. The receiver has to know the
+    /// layout of the frame to decode it. Framing characters are added as configured.
+    /// Ranges exceeding the source stream are limited to its length.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `escape_range` - Range of the source stream which is escaped
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_ranged(&[0x02, 0x10, 0x02, 0x10], 2..4, &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x02, 0x02, 0x10, 0x10, 0x42, 0x10, 0x10, 0x03]
+    /// );
+    /// ```
+    pub fn encode_ranged(
+        &self,
+        source_stream: &[u8],
+        escape_range: Range<usize>,
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = 0;
+        self.encode_frame_with(source_stream, escape_range, |bytes| {
+            encoded_idx = write_bytes(dest_stream, encoded_idx, bytes)?;
+            Ok(())
+        })
+    }
+
+    /// This method encodes a given byte stream in the escaped mode, with the set of escaped
+    /// characters chosen by a predicate instead of `escape_extra`. Each byte for which the
+    /// predicate returns true is encoded as DLE followed by its escaped form. STX, ETX and
+    /// DLE are always escaped, independent of the predicate, and CR is escaped if
+    /// `escape_cr` is set. The other settings of the encoder are used unchanged. The frame
+    /// can be decoded with [DleEncoder::decode_with_predicate] and the same predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `needs_escape` - Returns true for each byte which should be escaped
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder
+    ///     .encode_with_predicate(&[0x01, 0x20], |byte| byte < 0x20, &mut encoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x41, 0x20, 0x03]);
+    /// ```
+    pub fn encode_with_predicate(
+        &self,
+        source_stream: &[u8],
+        needs_escape: impl Fn(u8) -> bool,
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        self.with_escape_predicate(needs_escape)
+            .encode_escaped(source_stream, dest_stream)
+    }
+
+    /// This method encodes a given byte stream into two destination regions. The first
+    /// region is filled completely before the encoding continues in the second one, for
+    /// example the end and the start of a ring buffer used for DMA transfers. Escape
+    /// sequences can be split between the two regions. The concatenation of the written
+    /// parts of both regions is identical to the output of [DleEncoder::encode]. Returns the
+    /// total number of bytes written to both regions.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream_0` - First region the encoded stream is written to
+    /// * `dest_stream_1` - Second region the encoded stream is written to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut ring: [u8; 8] = [0; 8];
+    /// let (start, end) = ring.split_at_mut(5);
+    /// let encoded_len = dle_encoder.encode_scatter(&[0x01, 0x02, 0x03], end, start).unwrap();
+    /// assert_eq!(encoded_len, 7);
+    /// assert_eq!(ring, [0x42, 0x10, 0x43, 0x03, 0, 0x02, 0x01, 0x10]);
+    /// ```
+    pub fn encode_scatter(
+        &self,
+        source_stream: &[u8],
+        dest_stream_0: &mut [u8],
+        dest_stream_1: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let dest_stream_0_len = dest_stream_0.len();
+        let mut encoded_idx = 0;
+        self.encode_frame_with(source_stream, 0..source_stream.len(), |bytes| {
+            for &byte in bytes {
+                let dest = match dest_stream_0.get_mut(encoded_idx) {
+                    Some(dest) => dest,
+                    None => dest_stream_1
+                        .get_mut(encoded_idx - dest_stream_0_len)
+                        .ok_or(DleError::StreamTooShort)?,
+                };
+                *dest = byte;
+                encoded_idx += 1;
+            }
+            Ok(())
+        })
+    }
+
+    /// Encode a complete frame like [DleEncoder::encode] and pass the encoded bytes to the
+    /// given write function, for encoders which do not write into a single destination
+    /// slice. Only the bytes of the source stream inside the escape range are escaped, the
+    /// other bytes are written verbatim. Returns the number of written bytes
+    fn encode_frame_with(
+        &self,
+        source_stream: &[u8],
+        escape_range: Range<usize>,
+        mut write: impl FnMut(&[u8]) -> Result<(), DleError>,
+    ) -> Result<usize, DleError> {
+        self.check_unambiguous(source_stream)?;
+        if let Some(idle) = self.idle_on_empty {
+            if source_stream.is_empty() {
+                write(idle)?;
+                return Ok(idle.len());
+            }
+        }
+        let mut encoded_len = 0;
+        let mut write_counted = |bytes: &[u8]| {
+            encoded_len += bytes.len();
+            write(bytes)
+        };
+        if self.add_stx_etx {
+            write_counted(self.start_marker())?;
+        }
+        for (source_idx, &byte) in source_stream.iter().enumerate() {
+            if self.resync_due(source_idx) {
+                write_counted(&[DLE_CHAR, SYN_CHAR])?;
+            }
+            if escape_range.contains(&source_idx) {
+                let (encoded, byte_len) = self.encode_byte(byte);
+                write_counted(encoded.get(..byte_len).unwrap_or_default())?;
+            } else {
+                write_counted(&[byte])?;
+            }
+        }
+        if self.add_stx_etx {
+            write_counted(self.end_marker())?;
+        }
+        let (padded_len, fill) = match self.pad_to {
+            Some(pad_to) => pad_to,
+            None => return Ok(encoded_len),
+        };
+        if encoded_len > padded_len {
+            return Err(DleError::FrameTooLarge);
+        }
+        for _ in encoded_len..padded_len {
+            write(&[fill])?;
+        }
+        Ok(padded_len)
+    }
+
+    /// This method decodes the first frame of a given byte stream and returns the decoded
+    /// length together with the remaining bytes of the source stream which follow the frame.
+    /// This is useful to decode multiple concatenated frames in sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_frames: [u8; 7] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x03];
+    /// let (decoded_len, remainder) = dle_encoder
+    ///     .decode_and_remainder(&encoded_frames, &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01]);
+    /// assert_eq!(remainder, &[0x02, 0x10, 0x42, 0x03]);
+    /// ```
+    pub fn decode_and_remainder<'a>(
+        &self,
+        source_stream: &'a [u8],
+        dest_stream: &mut [u8],
+    ) -> Result<(usize, &'a [u8]), DleError> {
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, dest_stream, &mut read_len)?;
+        Ok((decoded_len, source_tail(source_stream, read_len)))
+    }
+
+    /// This method decodes the first frame of a given byte stream and returns the decoded
+    /// length together with the raw encoded bytes of the frame, from the start marker up to
+    /// and including the end marker. This allows logging the on-wire form of a frame next to
+    /// its decoded payload. Padding following the frame is not part of the raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_frames: [u8; 6] = [0x02, 0x10, 0x42, 0x03, 0x02, 0x03];
+    /// let (decoded_len, raw) = dle_encoder
+    ///     .decode_with_raw(&encoded_frames, &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x02]);
+    /// assert_eq!(raw, &[0x02, 0x10, 0x42, 0x03]);
+    /// ```
+    pub fn decode_with_raw<'a>(
+        &self,
+        source_stream: &'a [u8],
+        dest_stream: &mut [u8],
+    ) -> Result<(usize, &'a [u8]), DleError> {
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, dest_stream, &mut read_len)?;
+        if self.pad_to.is_some() {
+            // The read length includes the padding, so the end of the frame is searched again
+            read_len = self.scan_frame(source_stream, usize::MAX)?.1;
+        }
+        let frame_start = self.idle_prefix_len(source_stream);
+        let raw_frame = source_stream.get(frame_start..read_len).unwrap_or_default();
+        Ok((decoded_len, raw_frame))
+    }
+
+    /// This method decodes the first frame of a given byte stream without copying it. The
+    /// returned iterator yields the decoded payload as consecutive chunks, which are
+    /// concatenated by the caller, for example into a scatter-gather structure. Runs of
+    /// bytes which are not escaped are yielded as borrowed subslices of the source stream.
+    /// Each escape sequence breaks the run and is yielded as a separate chunk with a single
+    /// byte. The chunks are not a contiguous payload, use [DleEncoder::decode] for that. The
+    /// frame is validated before the iterator is returned, so decoding errors are returned
+    /// like [DleEncoder::decode] returns them. The upper bound of the size hint of the
+    /// iterator is the number of remaining escaped bytes of the frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded: [u8; 7] = [0x02, 0x01, 0x05, 0x10, 0x42, 0x07, 0x03];
+    /// let chunks: Vec<&[u8]> = dle_encoder.decode_chunks(&encoded).unwrap().collect();
+    /// assert_eq!(chunks, [&[0x01, 0x05][..], &[0x02], &[0x07]]);
+    /// ```
+    pub fn decode_chunks<'a>(
+        &self,
+        source_stream: &'a [u8],
+    ) -> Result<impl Iterator<Item = &'a [u8]> + 'a, DleError> {
+        let (body, terminator) = self.frame_body(source_stream)?;
+        let chunks = DecodeChunks {
+            encoder: *self,
+            body,
+        };
+        Ok(chunks.chain(self.keep_terminator.then_some(terminator)))
+    }
+
+    /// This method returns the body of the first frame of a given byte stream between the
+    /// start and end markers without unescaping it. This is useful to forward escaped bodies,
+    /// for example by a proxy, without decoding and re-encoding them. Idle sequences before
+    /// the frame and padding after it are skipped. The frame is validated like
+    /// [DleEncoder::decode] validates it, so the body only contains valid escape sequences
+    /// and decoding errors are returned like [DleEncoder::decode] returns them. The
+    /// terminator of the frame is not part of the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream containing the frame
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let body = dle_encoder
+    ///     .strip_framing(&[0x02, 0x01, 0x10, 0x42, 0x03])
+    ///     .unwrap();
+    /// assert_eq!(body, &[0x01, 0x10, 0x42]);
+    /// ```
+    pub fn strip_framing<'a>(&self, source_stream: &'a [u8]) -> Result<&'a [u8], DleError> {
+        self.frame_body(source_stream).map(|(body, _)| body)
+    }
+
+    /// This method splits a given byte stream after its first frame without decoding it.
+    /// Returns the raw bytes of the frame and the remaining bytes, which are both subslices
+    /// of the source stream. The frame includes a preceding idle sequence and the padding
+    /// after it, so it spans the bytes [DleEncoder::decode] reports as read length. The frame
+    /// is validated like [DleEncoder::decode] validates it, but the remaining bytes are not
+    /// checked against the trailing policy. [DleError::SourceIncomplete] is returned if the
+    /// stream does not contain a complete frame because it is empty, only contains an idle
+    /// sequence or ends inside the first frame. Other decoding errors are returned like
+    /// [DleEncoder::decode] returns them.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to split
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded_frames: [u8; 7] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x03];
+    /// let (frame, rest) = dle_encoder.split_first_frame(&encoded_frames).unwrap();
+    /// assert_eq!(frame, &[0x02, 0x01, 0x03]);
+    /// assert_eq!(rest, &[0x02, 0x10, 0x42, 0x03]);
+    /// assert_eq!(
+    ///     dle_encoder.split_first_frame(&rest[..3]),
+    ///     Err(DleError::SourceIncomplete)
+    /// );
+    /// ```
+    pub fn split_first_frame<'a>(
+        &self,
+        source_stream: &'a [u8],
+    ) -> Result<(&'a [u8], &'a [u8]), DleError> {
+        let idle_len = self.idle_prefix_len(source_stream);
+        let frame = source_tail(source_stream, idle_len);
+        if frame.is_empty() {
+            return Err(DleError::SourceIncomplete);
+        }
+        let frame_end = match self.scan_frame_body(frame, usize::MAX) {
+            Ok((_, read_len)) => idle_len + self.skip_padding(frame, read_len),
+            Err(error) if error.decode_kind() == Some(DecodeErrorKind::MissingEnd) => {
+                return Err(DleError::SourceIncomplete)
+            }
+            Err(error) => return Err(error.shifted(idle_len)),
+        };
+        Ok(source_stream.split_at(frame_end.min(source_stream.len())))
+    }
+
+    /// This method decodes the first frame of a given byte stream into two destinations.
+    /// The first `header.len()` bytes of the decoded payload are written to the header
+    /// destination and the remaining bytes to the payload destination. Returns the number
+    /// of bytes written to each destination. If the frame is shorter than the header, the
+    /// header is only partially written and no bytes are written to the payload, so the
+    /// caller has to check the returned header length. Decoding errors are returned and the
+    /// read length is assigned like by [DleEncoder::decode], also if decoding fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `header` - The first decoded bytes will be written here
+    /// * `payload` - The decoded bytes following the header will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut header: [u8; 2] = [0; 2];
+    /// let mut payload: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let (header_len, payload_len) = dle_encoder
+    ///     .decode_split(&[0x02, 0x07, 0x01, 0x10, 0x42, 0x03], &mut header, &mut payload, &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(&header[..header_len], &[0x07, 0x01]);
+    /// assert_eq!(&payload[..payload_len], &[0x02]);
+    /// assert_eq!(read_len, 6);
+    /// ```
+    pub fn decode_split(
+        &self,
+        source_stream: &[u8],
+        header: &mut [u8],
+        payload: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<(usize, usize), DleError> {
+        let (body, terminator, frame_end) = match self.frame_parts(source_stream) {
+            Ok(parts) => parts,
+            Err(error) => {
+                *read_len = self.error_read_len(&error);
+                return Err(error);
+            }
+        };
+        let chunks = DecodeChunks {
+            encoder: *self,
+            body,
+        };
+        let header_len = header.len();
+        let mut decoded_len = 0;
+        for chunk in chunks.chain(self.keep_terminator.then_some(terminator)) {
+            for &byte in chunk {
+                let dest = match header.get_mut(decoded_len) {
+                    Some(dest) => dest,
+                    None => match payload.get_mut(decoded_len - header_len) {
+                        Some(dest) => dest,
+                        None => {
+                            *read_len = 0;
+                            return Err(DleError::StreamTooShort);
+                        }
+                    },
+                };
+                *dest = byte;
+                decoded_len += 1;
+            }
+        }
+        *read_len = frame_end;
+        Ok((
+            decoded_len.min(header_len),
+            decoded_len.saturating_sub(header_len),
+        ))
+    }
+
+    /// This method checks whether the first frames of two byte streams carry the same
+    /// payload, without copying the payloads. The other stream is decoded with the other
+    /// configuration, so frames encoded in different modes can be compared. Both frames are
+    /// validated first, so decoding errors of either frame are returned like
+    /// [DleEncoder::decode] returns them. The payloads are then compared byte by byte until
+    /// the first difference.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode with this configuration
+    /// * `other` - The configuration used to decode the other stream
+    /// * `other_stream` - The stream to decode with the other configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let same = dle_encoder
+    ///     .same_payload(
+    ///         &[0x02, 0x01, 0x10, 0x42, 0x03],
+    ///         &DleEncoder::non_escaped(),
+    ///         &[0x10, 0x02, 0x01, 0x02, 0x10, 0x03],
+    ///     )
+    ///     .unwrap();
+    /// assert!(same);
+    /// ```
+    pub fn same_payload(
+        &self,
+        source_stream: &[u8],
+        other: &DleEncoder,
+        other_stream: &[u8],
+    ) -> Result<bool, DleError> {
+        let payload = self.decode_chunks(source_stream)?.flatten();
+        let other_payload = other.decode_chunks(other_stream)?.flatten();
+        Ok(payload.eq(other_payload))
+    }
+
+    /// This method decodes the first frame of a given byte stream like [DleEncoder::decode]
+    /// and additionally counts the escape sequences of the frame. This allows measuring the
+    /// overhead of the escaping on the receiving side.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DecodeStats, DleEncoder};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let (decoded_len, stats) = dle_encoder
+    ///     .decode_with_stats(&[0x02, 0x10, 0x10, 0x10, 0x42, 0x03], &mut decoding_buffer, &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x10, 0x02]);
+    /// assert_eq!(stats, DecodeStats { doubled_dle: 1, escaped_chars: 1 });
+    /// assert_eq!(stats.overhead(), 2);
+    /// ```
+    pub fn decode_with_stats(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<(usize, DecodeStats), DleError> {
+        let decoded_len = self.decode(source_stream, dest_stream, read_len)?;
+        let (mut body, _) = self.frame_body(source_stream)?;
+        let mut stats = DecodeStats::default();
+        while let Some(dle_idx) = body.iter().position(|&byte| byte == DLE_CHAR) {
+            // Escape sequences were validated, so a DLE is never the last byte of the body
+            let escaped = body.get(dle_idx + 1).copied().unwrap_or_default();
+            if escaped == DLE_CHAR {
+                stats.doubled_dle += 1;
+            } else if !self.is_resync_marker(escaped) {
+                stats.escaped_chars += 1;
+            }
+            body = source_tail(body, dle_idx + 2);
+        }
+        Ok((decoded_len, stats))
+    }
+
+    /// This method explains a [DecodeErrorKind::InvalidDleSequence] error returned by
+    /// [DleEncoder::decode]. It returns the rejected character following the DLE character,
+    /// which is carried by the error, together with all characters which are accepted at
+    /// this position with the current configuration, which helps to find configuration
+    /// mismatches between two devices. [None] is returned for other errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error returned when decoding a stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let error = dle_encoder
+    ///     .decode(&[0x02, 0x10, 0x99, 0x03], &mut decoding_buffer, &mut read_len)
+    ///     .unwrap_err();
+    /// let mismatch = dle_encoder.explain_invalid_escape(&error).unwrap();
+    /// assert_eq!(mismatch.found, 0x99);
+    /// assert_eq!(
+    ///     mismatch.to_string(),
+    ///     "found 0x99, expected one of DLE, 0x42, 0x43"
+    /// );
+    /// ```
+    pub fn explain_invalid_escape(&self, error: &DleError) -> Option<EscapeMismatch> {
+        let found = match error.decode_kind()? {
+            DecodeErrorKind::InvalidDleSequence { found } => found,
+            _ => return None,
+        };
+        Some(EscapeMismatch {
+            found,
+            expected: self.accepted_after_dle(),
+        })
+    }
+
+    /// Returns the body of the first frame of the given stream between the start and end
+    /// markers and the terminator character. The frame is validated like
+    /// [DleEncoder::decode] validates it
+    fn frame_body<'a>(&self, source_stream: &'a [u8]) -> Result<(&'a [u8], &'a [u8]), DleError> {
+        self.frame_parts(source_stream)
+            .map(|(body, terminator, _)| (body, terminator))
+    }
+
+    /// Returns the body and the terminator character of the first frame of the given stream
+    /// like [DleEncoder::frame_body], together with the read length [DleEncoder::decode]
+    /// assigns for the frame
+    fn frame_parts<'a>(
+        &self,
+        source_stream: &'a [u8],
+    ) -> Result<(&'a [u8], &'a [u8], usize), DleError> {
+        let idle_len = self.idle_prefix_len(source_stream);
+        let frame = source_tail(source_stream, idle_len);
+        let (_, read_len) = self
+            .scan_frame_body(frame, usize::MAX)
+            .map_err(|error| error.shifted(idle_len))?;
+        let frame_end = self.skip_padding(frame, read_len);
+        self.check_trailing(frame, frame_end)
+            .map_err(|error| error.shifted(idle_len))?;
+        let body_end = read_len.saturating_sub(self.end_marker().len());
+        let terminator_start = read_len.saturating_sub(1);
+        Ok((
+            frame
+                .get(self.start_marker().len()..body_end)
+                .unwrap_or_default(),
+            frame.get(terminator_start..read_len).unwrap_or_default(),
+            idle_len + frame_end,
+        ))
+    }
+
+    /// Returns the read length [DleEncoder::decode] assigns if it fails with the given
+    /// error, for methods which validate the frame without decoding it
+    fn error_read_len(&self, error: &DleError) -> usize {
+        let (kind, offset) = match *error {
+            DleError::DecodingError { kind, offset } => (kind, offset),
+            _ => return 0,
+        };
+        match kind {
+            // The non-escaped decoder keeps the DLE of the sequence in the source stream
+            DecodeErrorKind::InvalidDleSequence { .. } if !self.escape_stx_etx => {
+                offset.saturating_sub(1)
+            }
+            DecodeErrorKind::InvalidDleSequence { .. } | DecodeErrorKind::UnexpectedStart
+                if self.escape_stx_etx =>
+            {
+                offset + 1
+            }
+            _ => offset,
+        }
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_typed]. Returns the message type and the message payload, which
+    /// is a subslice of the destination stream. [DleError::InvalidHeader] is returned if the
+    /// decoded frame is shorter than the header or the length in the header does not match
+    /// the length of the payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded frame including the header will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let (msg_type, payload) = dle_encoder
+    ///     .decode_typed(&[0x02, 0x07, 0x00, 0x01, 0x10, 0x42, 0x03], &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(msg_type, 0x07);
+    /// assert_eq!(payload, &[0x02]);
+    /// ```
+    pub fn decode_typed<'a>(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &'a mut [u8],
+    ) -> Result<(u8, &'a [u8]), DleError> {
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, dest_stream, &mut read_len)?;
+        match dest_stream.get(..decoded_len).unwrap_or_default() {
+            &[msg_type, len_hi, len_lo, ref payload @ ..]
+                if usize::from(u16::from_be_bytes([len_hi, len_lo])) == payload.len() =>
+            {
+                Ok((msg_type, payload))
+            }
+            _ => Err(DleError::InvalidHeader),
+        }
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_sparse]. Returns an iterator over the pairs of offsets and
+    /// values, which are read from the decoded payload in the destination stream.
+    /// [DleError::InvalidHeader] is returned if the decoded payload is not a whole number of
+    /// updates.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded payload will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut updates = dle_encoder
+    ///     .decode_sparse(&[0x02, 0x00, 0x04, 0x10, 0x42, 0x03], &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(updates.next(), Some((0x0004, 0x02)));
+    /// assert_eq!(updates.next(), None);
+    /// ```
+    pub fn decode_sparse<'a>(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &'a mut [u8],
+    ) -> Result<impl Iterator<Item = (u16, u8)> + 'a, DleError> {
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, dest_stream, &mut read_len)?;
+        let payload = dest_stream.get(..decoded_len).unwrap_or_default();
+        if payload.len() % 3 != 0 {
+            return Err(DleError::InvalidHeader);
+        }
+        // Every chunk has three bytes, so the number of updates is known in advance
+        Ok(payload.chunks_exact(3).map(|update| {
+            let [offset_hi, offset_lo, value] = <[u8; 3]>::try_from(update).unwrap_or_default();
+            (u16::from_be_bytes([offset_hi, offset_lo]), value)
+        }))
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_framed_len]. Only the number of bytes announced in the length
+    /// header are read. [DleError::SourceIncomplete] is returned if the source stream is
+    /// shorter than the announced frame and [DleError::InvalidHeader] is returned if the
+    /// end marker is not found directly after the announced number of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of bytes of the frame in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let decoded_len = dle_encoder
+    ///     .decode_framed_len(
+    ///         &[0x02, 0x00, 0x03, 0x01, 0x10, 0x10, 0x03, 0x55],
+    ///         &mut decoding_buffer,
+    ///         &mut read_len,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01, 0x10]);
+    /// assert_eq!(read_len, 7);
+    /// ```
+    pub fn decode_framed_len(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        *read_len = 0;
+        let start_marker = self.start_marker();
+        let frame = match source_stream.strip_prefix(start_marker) {
+            Some(&[len_hi, len_lo, ref rest @ ..]) => {
+                let body_len = usize::from(u16::from_be_bytes([len_hi, len_lo]));
+                rest.get(..body_len + self.end_marker().len())
+                    .ok_or(DleError::SourceIncomplete)?
+            }
+            Some(_) => return Err(DleError::SourceIncomplete),
+            None => return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0)),
+        };
+        // The body is decoded as regular frame without the length header
+        let mut frame_bytes = start_marker.iter().chain(frame).copied();
+        let decoded_len = match self.decode_pull(|| frame_bytes.next(), dest_stream) {
+            Ok(decoded_len) => decoded_len,
+            Err(DleError::SourceIncomplete) => return Err(DleError::InvalidHeader),
+            Err(error) => return Err(error.shifted(2)),
+        };
+        if frame_bytes.next().is_some() {
+            return Err(DleError::InvalidHeader);
+        }
+        *read_len = start_marker.len() + 2 + frame.len();
+        Ok(decoded_len)
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_with_crc]. The end of the frame is determined without decoding
+    /// it, and the CRC following the frame is verified before the frame is decoded.
+    /// [DleError::CrcMismatch] is returned if the CRC does not match and
+    /// [DleError::SourceIncomplete] is returned if the source stream ends before the CRC.
+    /// Malformed frames are reported with the same errors as [DleEncoder::decode].
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of bytes of the frame and the CRC in the source stream will
+    ///   be assigned to this variable if decoding succeeds, and 0 otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut encoded: [u8; 16] = [0; 16];
+    /// let encoded_len = dle_encoder.encode_with_crc(&[0x01, 0x02], &mut encoded).unwrap();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let decoded_len = dle_encoder
+    ///     .decode_with_crc(&encoded, &mut decoding_buffer, &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01, 0x02]);
+    /// assert_eq!(read_len, encoded_len);
+    ///
+    /// encoded[1] = 0x05;
+    /// let decode_result = dle_encoder.decode_with_crc(&encoded, &mut decoding_buffer, &mut read_len);
+    /// assert_eq!(decode_result, Err(DleError::CrcMismatch));
+    /// ```
+    pub fn decode_with_crc(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        *read_len = 0;
+        let (_, frame_len) = self.scan_frame(source_stream, usize::MAX)?;
+        let frame_end = self.skip_padding(source_stream, frame_len);
+        let frame = source_stream.get(..frame_end).unwrap_or_default();
+        let crc = match *source_tail(source_stream, frame_end) {
+            [crc_hi, crc_lo, ..] => u16::from_be_bytes([crc_hi, crc_lo]),
+            _ => return Err(DleError::SourceIncomplete),
+        };
+        if crc16(frame) != crc {
+            return Err(DleError::CrcMismatch);
+        }
+        let mut frame_read_len = 0;
+        let decoded_len = self.decode(frame, dest_stream, &mut frame_read_len)?;
+        *read_len = frame_end + 2;
+        Ok(decoded_len)
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_hex]. Each pair of hexadecimal digits is converted back into a
+    /// byte before it is decoded, uppercase and lowercase digits are accepted. A character
+    /// which is not a hexadecimal digit is reported as [DecodeErrorKind::InvalidHexDigit].
+    /// The offsets of all decoding errors refer to the characters of the hexadecimal form.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The hexadecimal form of the stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read characters in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let decoded_len = dle_encoder
+    ///     .decode_hex(b"021042AB03", &mut decoding_buffer, &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x02, 0xab]);
+    /// assert_eq!(read_len, 10);
+    /// ```
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_hex(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        let mut hex_idx = 0;
+        let mut invalid_digit = false;
+        let result = self.decode_pull(
+            || {
+                let digits = source_stream.get(hex_idx..hex_idx + 2)?;
+                let mut byte = 0;
+                for &digit in digits {
+                    match char::from(digit).to_digit(16) {
+                        Some(value) => byte = (byte << 4) | value,
+                        None => {
+                            invalid_digit = true;
+                            return None;
+                        }
+                    }
+                    hex_idx += 1;
+                }
+                u8::try_from(byte).ok()
+            },
+            dest_stream,
+        );
+        // A frame which did not fit into the destination stream can be decoded again
+        *read_len = if result == Err(DleError::StreamTooShort) {
+            0
+        } else {
+            hex_idx
+        };
+        if invalid_digit {
+            return Err(DleError::decoding(
+                DecodeErrorKind::InvalidHexDigit,
+                hex_idx,
+            ));
+        }
+        result.map_err(|error| match error {
+            DleError::DecodingError { kind, offset } => {
+                DleError::decoding(kind, offset.saturating_mul(2))
+            }
+            DleError::SourceIncomplete => {
+                DleError::decoding(DecodeErrorKind::MissingEnd, source_stream.len())
+            }
+            error => error,
+        })
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [DleEncoder::encode_framed_no_escape]. The frame starts with STX and the payload is
+    /// copied up to the first ETX. Raw STX and DLE characters inside the frame are rejected
+    /// with [DecodeErrorKind::RawControlChar], because they can not be part of such a frame.
+    /// The other settings of the configuration are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let decoded_len = dle_encoder
+    ///     .decode_framed_no_escape(&[0x02, b'o', b'k', 0x03, 0x55], &mut decoding_buffer, &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], b"ok");
+    /// assert_eq!(read_len, 4);
+    /// ```
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_framed_no_escape(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        if source_stream.first() != Some(&STX_CHAR) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        for (source_idx, &byte) in source_stream.iter().enumerate().skip(1) {
+            if byte == ETX_CHAR {
+                *read_len = source_idx + 1;
+                return Ok(source_idx - 1);
+            } else if byte == STX_CHAR || byte == DLE_CHAR {
+                return Err(DleError::decoding(
+                    DecodeErrorKind::RawControlChar,
+                    source_idx,
+                ));
+            }
+            write_byte(dest_stream, source_idx - 1, byte)?;
+        }
+        Err(DleError::decoding(
+            DecodeErrorKind::MissingEnd,
+            source_stream.len(),
+        ))
+    }
+
+    /// This method decodes the first frame of a given byte stream into a [Frame], which
+    /// holds the decoded payload together with metadata about the frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let frame = dle_encoder
+    ///     .decode_frame_typed(&[0x02, 0x01, 0x10, 0x42, 0x03, 0x55])
+    ///     .unwrap();
+    /// assert_eq!(frame.payload, vec![0x01, 0x02]);
+    /// assert!(frame.escape_stx_etx);
+    /// assert!(!frame.cr_escaped);
+    /// assert_eq!(frame.source_range, 0..5);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_frame_typed(&self, source_stream: &[u8]) -> Result<Frame, DleError> {
+        let mut payload = vec![0; self.min_decode_dest_len(source_stream)];
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, &mut payload, &mut read_len)?;
+        payload.truncate(decoded_len);
+        let source_range = 0..read_len;
+        Ok(Frame {
+            payload,
+            escape_stx_etx: self.escape_stx_etx,
+            cr_escaped: self.escape_stx_etx
+                && self.contains_escaped_cr(source_stream.get(..read_len).unwrap_or_default()),
+            source_range,
+        })
+    }
+
+    /// This method decodes the first frame of a given byte stream into a [String]. The
+    /// decoded payload must be valid UTF-8, otherwise [DleError::InvalidUtf8] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let text = dle_encoder.decode_to_string(&[0x02, b'o', b'k', 0x03]).unwrap();
+    /// assert_eq!(text, "ok");
+    /// assert_eq!(
+    ///     dle_encoder.decode_to_string(&[0x02, 0xff, 0x03]),
+    ///     Err(DleError::InvalidUtf8)
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn decode_to_string(&self, source_stream: &[u8]) -> Result<String, DleError> {
+        let mut payload = vec![0; self.min_decode_dest_len(source_stream)];
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, &mut payload, &mut read_len)?;
+        payload.truncate(decoded_len);
+        String::from_utf8(payload).map_err(|_| DleError::InvalidUtf8)
+    }
+
+    /// This method decodes all consecutive frames of a given byte stream and concatenates
+    /// their decoded payloads in the destination stream. This is useful if application data
+    /// was split across multiple frames. The frames must follow each other directly, only
+    /// separated by idle sequences, and idle sequences after the last frame are skipped. The
+    /// offsets of decoding errors are relative to the start of the source stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded payloads will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_frames: [u8; 7] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x03];
+    /// let decoded_len = dle_encoder
+    ///     .decode_concat(&encoded_frames, &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01, 0x02]);
+    /// ```
+    pub fn decode_concat(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut source_idx = 0;
+        let mut decoded_len = 0;
+        while source_idx < source_stream.len() {
+            let frame = source_tail(source_stream, source_idx);
+            // Idle sequences after the last frame do not start another frame
+            if self.idle_prefix_len(frame) == frame.len() {
+                break;
+            }
+            let mut read_len = 0;
+            decoded_len += self
+                .decode(frame, dest_tail(dest_stream, decoded_len)?, &mut read_len)
+                .map_err(|error| error.shifted(source_idx))?;
+            source_idx += read_len;
+        }
+        Ok(decoded_len)
+    }
+
+    /// This method decodes all consecutive frames of a given byte stream one after another
+    /// into the same destination stream and calls the given closure with the decoded
+    /// payload of each frame. This processes multiple frames without allocations. The
+    /// frames must follow each other directly, only separated by idle sequences, and idle
+    /// sequences after the last frame are skipped. Returns the number of decoded frames. On
+    /// the first error, decoding stops and the error is returned. The offsets of decoding
+    /// errors are relative to the start of the source stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Each decoded payload will be written here. It must be large enough
+    ///   for the largest frame
+    /// * `f` - Closure called with the decoded payload of each frame
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_frames: [u8; 7] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x03];
+    /// let mut sum = 0;
+    /// let frame_count = dle_encoder
+    ///     .for_each_frame(&encoded_frames, &mut decoding_buffer, |payload| {
+    ///         sum += payload.iter().sum::<u8>()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(frame_count, 2);
+    /// assert_eq!(sum, 0x03);
+    /// ```
+    pub fn for_each_frame(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        mut f: impl FnMut(&[u8]),
+    ) -> Result<usize, DleError> {
+        let mut source_idx = 0;
+        let mut frame_count = 0;
+        while source_idx < source_stream.len() {
+            let frame = source_tail(source_stream, source_idx);
+            // Idle sequences after the last frame do not start another frame
+            if self.idle_prefix_len(frame) == frame.len() {
+                break;
+            }
+            let mut read_len = 0;
+            let decoded_len = self
+                .decode(frame, dest_stream, &mut read_len)
+                .map_err(|error| error.shifted(source_idx))?;
+            f(dest_stream.get(..decoded_len).unwrap_or_default());
+            frame_count += 1;
+            source_idx += read_len;
+        }
+        Ok(frame_count)
+    }
+
+    /// This method checks that a given byte stream consists of consecutive well-formed
+    /// frames without decoding them and returns the number of frames. Unlike
+    /// [DleEncoder::for_each_frame], no destination stream is required. The first malformed
+    /// or incomplete frame is returned as an error like [DleEncoder::decode] returns it, and
+    /// bytes following the last frame other than idle sequences are returned as
+    /// [DecodeErrorKind::MissingStart]. The
+    /// offsets of decoding errors are relative to the start of the source stream. An empty
+    /// stream is a batch without frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to validate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DecodeErrorKind, DleEncoder};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded_frames: [u8; 8] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x03, 0x55];
+    /// assert_eq!(dle_encoder.validate_batch(&encoded_frames[..7]), Ok(2));
+    /// let error = dle_encoder.validate_batch(&encoded_frames).unwrap_err();
+    /// assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingStart));
+    /// assert_eq!(error.offset(), Some(7));
+    /// ```
+    pub fn validate_batch(&self, source_stream: &[u8]) -> Result<usize, DleError> {
+        let mut source_idx = 0;
+        let mut frame_count = 0;
+        while source_idx < source_stream.len() {
+            let rest = source_tail(source_stream, source_idx);
+            let idle_len = self.idle_prefix_len(rest);
+            // Idle sequences after the last frame do not start another frame
+            if idle_len == rest.len() {
+                break;
+            }
+            // The padding is counted from the start of the frame like in DleEncoder::decode
+            let frame = source_tail(rest, idle_len);
+            let (_, read_len) = self
+                .scan_frame_body(frame, usize::MAX)
+                .map_err(|error| error.shifted(source_idx + idle_len))?;
+            frame_count += 1;
+            source_idx += idle_len + self.skip_padding(frame, read_len);
+        }
+        Ok(frame_count)
+    }
+
+    /// This method decodes a given byte stream which was encoded with a ASCII
+    /// DLE encoder into a fixed-capacity [heapless::Vec]. This allows returning owned
+    /// decoded streams without a global allocator. [DleError::StreamTooShort] is returned
+    /// if the capacity `N` is exhausted.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut read_len = 0;
+    /// let decoded = dle_encoder
+    ///     .decode_heapless::<16>(&[0x02, 0x10, 0x42, 0x03], &mut read_len)
+    ///     .unwrap();
+    /// assert_eq!(decoded.as_slice(), &[0x02]);
+    /// assert_eq!(read_len, 4);
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn decode_heapless<const N: usize>(
+        &self,
+        source_stream: &[u8],
+        read_len: &mut usize,
+    ) -> Result<heapless::Vec<u8, N>, DleError> {
+        let mut decoded = heapless::Vec::new();
+        // Can not fail because the new length is the capacity
+        let _ = decoded.resize(N, 0);
+        let decoded_len = self.decode(source_stream, &mut decoded, read_len)?;
+        decoded.truncate(decoded_len);
+        Ok(decoded)
+    }
+
+    /// This method decodes a given byte stream which was encoded with a ASCII
+    /// DLE encoder into a stack array with the capacity `N`. Returns the array together with
+    /// the decoded length. Bytes after the decoded length are zero.
+    /// [DleError::StreamTooShort] is returned if the decoded payload exceeds `N` bytes. This
+    /// is convenient for fixed-format messages in `no_std` environments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let (decoded, decoded_len) = dle_encoder
+    ///     .decode_array::<4>(&[0x02, 0x10, 0x42, 0x05, 0x03])
+    ///     .unwrap();
+    /// assert_eq!(&decoded[..decoded_len], &[0x02, 0x05]);
+    /// ```
+    pub fn decode_array<const N: usize>(
+        &self,
+        source_stream: &[u8],
+    ) -> Result<([u8; N], usize), DleError> {
+        let mut decoded = [0; N];
+        let mut read_len = 0;
+        let decoded_len = self.decode(source_stream, &mut decoded, &mut read_len)?;
+        Ok((decoded, decoded_len))
+    }
+
+    /// This method decodes the first frame of a given byte stream into a stack array with
+    /// the capacity `N` like [DleEncoder::decode_array], but truncates payloads exceeding
+    /// the capacity instead of returning [DleError::StreamTooShort]. Truncated payloads are
+    /// flagged as spilled. This is a lossy mode for telemetry where partial data is still
+    /// useful. Malformed frames are still rejected with an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let result = dle_encoder
+    ///     .decode_bounded::<2>(&[0x02, 0x01, 0x10, 0x42, 0x05, 0x03])
+    ///     .unwrap();
+    /// assert_eq!(result.payload(), &[0x01, 0x02]);
+    /// assert!(result.spilled);
+    /// ```
+    pub fn decode_bounded<const N: usize>(
+        &self,
+        source_stream: &[u8],
+    ) -> Result<DecodeResult<N>, DleError> {
+        let mut result = DecodeResult {
+            buffer: [0; N],
+            len: 0,
+            spilled: false,
+        };
+        for chunk in self.decode_chunks(source_stream)? {
+            let copy_len = chunk.len().min(N.saturating_sub(result.len));
+            result.len = write_bytes(
+                &mut result.buffer,
+                result.len,
+                chunk.get(..copy_len).unwrap_or_default(),
+            )?;
+            if copy_len < chunk.len() {
+                result.spilled = true;
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    /// This method decodes the first frame of a given byte stream like [DleEncoder::decode],
+    /// but keeps the decoded prefix if the destination stream is too short instead of
+    /// failing. The returned progress contains the decoded length and the source offset
+    /// reached, so the caller can process the prefix and continue with
+    /// [DleEncoder::resume_partial] and a fresh destination stream. Escape sequences are
+    /// never split between two destination streams. The frame is validated before it is
+    /// decoded, so decoding errors are returned like [DleEncoder::decode] returns them and
+    /// no bytes are written for erroneous frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded prefix of the frame will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded = [0x02, 0x01, 0x10, 0x42, 0x05, 0x03];
+    /// let mut decoding_buffer: [u8; 2] = [0; 2];
+    /// let progress = dle_encoder
+    ///     .decode_partial(&encoded, &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..progress.decoded_len], &[0x01, 0x02]);
+    /// assert!(!progress.complete);
+    /// let progress = dle_encoder
+    ///     .resume_partial(&encoded, &progress, &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..progress.decoded_len], &[0x05]);
+    /// assert!(progress.complete);
+    /// assert_eq!(progress.read_len, 6);
+    /// ```
+    pub fn decode_partial(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<DecodePartial, DleError> {
+        self.decode_partial_from(source_stream, 0, dest_stream)
+    }
+
+    /// This method continues decoding a frame after [DleEncoder::decode_partial] or a
+    /// previous call of this method filled the destination stream. The source stream must be
+    /// the same one passed to the previous call, decoding continues at the read length of
+    /// the given progress. If the frame was already complete, nothing is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `progress` - The progress returned by the previous call
+    /// * `dest_stream` - Next part of the decoded frame will be written here
+    pub fn resume_partial(
+        &self,
+        source_stream: &[u8],
+        progress: &DecodePartial,
+        dest_stream: &mut [u8],
+    ) -> Result<DecodePartial, DleError> {
+        if progress.complete {
+            return Ok(DecodePartial {
+                decoded_len: 0,
+                ..*progress
+            });
+        }
+        self.decode_partial_from(source_stream, progress.read_len, dest_stream)
+    }
+
+    /// Decode the body of the first frame starting at the given source offset, which is
+    /// limited to the body of the frame, until the destination stream is full
+    fn decode_partial_from(
+        &self,
+        source_stream: &[u8],
+        resume_idx: usize,
+        dest_stream: &mut [u8],
+    ) -> Result<DecodePartial, DleError> {
+        let (body, terminator) = self.frame_body(source_stream)?;
+        let body_start = self.idle_prefix_len(source_stream) + self.start_marker().len();
+        let body_end = body_start + body.len();
+        let mut progress = DecodePartial {
+            decoded_len: 0,
+            read_len: resume_idx.max(body_start).min(body_end),
+            complete: false,
+        };
+        while progress.read_len < body_end {
+            let (decoded, sequence_len) = match source_tail(source_stream, progress.read_len) {
+                [DLE_CHAR, escaped, ..] => {
+                    if self.is_resync_marker(*escaped) {
+                        progress.read_len += 2;
+                        continue;
+                    }
+                    // The escape sequences were validated, so they can always be unescaped
+                    if *escaped == DLE_CHAR || !self.escape_stx_etx {
+                        (*escaped, 2)
+                    } else {
+                        (self.unescape(*escaped).unwrap_or(*escaped), 2)
+                    }
+                }
+                [byte, ..] => (*byte, 1),
+                [] => break,
+            };
+            if write_byte(dest_stream, progress.decoded_len, decoded).is_err() {
+                return Ok(progress);
+            }
+            progress.decoded_len += 1;
+            progress.read_len += sequence_len;
+        }
+        if self.keep_terminator {
+            match write_bytes(dest_stream, progress.decoded_len, terminator) {
+                Ok(decoded_len) => progress.decoded_len = decoded_len,
+                Err(_) => return Ok(progress),
+            }
+        }
+        let idle_len = self.idle_prefix_len(source_stream);
+        let frame = source_tail(source_stream, idle_len);
+        let frame_end = body_end - idle_len + self.end_marker().len();
+        progress.read_len = idle_len + self.skip_padding(frame, frame_end);
+        progress.complete = true;
+        Ok(progress)
+    }
+
+    /// This method decodes a given byte stream which was encoded with a ASCII
+    /// DLE encoder. It explicitely does so in the escaped mode, which is the default
+    /// mode. It returns the length of the decoded buffer or an error code if
+    /// there is a decoder failure or the destination stream is too short.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_escaped(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        let decoded_len = match self.decode_escaped_fast(source_stream, dest_stream, read_len) {
+            Some(decoded_len) => decoded_len,
+            None => self.decode_escaped_generic(
+                source_stream,
+                dest_stream,
+                read_len,
+                cfg!(feature = "memchr"),
+            )?,
+        };
+        let terminator = DleEncoder::frame_terminator(source_stream, *read_len)?;
+        let decoded_len = self.append_terminator(dest_stream, decoded_len, terminator, read_len)?;
+        *read_len = self.skip_padding(source_stream, *read_len);
+        self.check_trailing(source_stream, *read_len)?;
+        Ok(decoded_len)
+    }
+
+    /// This method decodes the body of a frame in the escaped mode whose STX was already
+    /// consumed, for example by an outer protocol. Decoding starts at the first byte of the
+    /// source stream and ends at the terminator. This is the counterpart of a body encoded
+    /// with `add_stx_etx` disabled and followed by ETX, or encoded with
+    /// [FragmentRole::Last]. Bytes following the terminator are ignored. The offsets of
+    /// decoding errors are relative to the start of the source stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The frame body to decode, including the terminator
+    /// * `dest_stream` - Decoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let decoded_len = dle_encoder
+    ///     .decode_escaped_body(&[0x01, 0x10, 0x42, 0x03], &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01, 0x02]);
+    /// ```
+    pub fn decode_escaped_body(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let dle_encoder = DleEncoder {
+            escape_stx_etx: true,
+            ..*self
+        };
+        // The implied STX is pulled first, so all offsets are shifted by one
+        let mut source = Some(STX_CHAR)
+            .into_iter()
+            .chain(source_stream.iter().copied());
+        dle_encoder
+            .decode_pull(|| source.next(), dest_stream)
+            .map_err(|error| match error {
+                DleError::DecodingError { kind, offset } => {
+                    DleError::decoding(kind, offset.saturating_sub(1))
+                }
+                DleError::SourceIncomplete => {
+                    DleError::decoding(DecodeErrorKind::MissingEnd, source_stream.len())
+                }
+                error => error,
+            })
+    }
+
+    /// This method decodes a frame encoded with [DleEncoder::encode_with_predicate]. The
+    /// predicate must be the same one used for encoding, escape sequences of other bytes are
+    /// rejected if `strict_escapes` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `needs_escape` - Returns true for each byte which was escaped by the encoder
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut read_len = 0;
+    /// let decoded_len = dle_encoder
+    ///     .decode_with_predicate(
+    ///         &[0x02, 0x10, 0x41, 0x20, 0x03],
+    ///         |byte| byte < 0x20,
+    ///         &mut decoding_buffer,
+    ///         &mut read_len,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x01, 0x20]);
+    /// assert_eq!(read_len, 5);
+    /// ```
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_with_predicate(
+        &self,
+        source_stream: &[u8],
+        needs_escape: impl Fn(u8) -> bool,
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        self.with_escape_predicate(needs_escape).decode_escaped(
+            source_stream,
+            dest_stream,
+            read_len,
+        )
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with an
+    /// unknown additive escape offset, which is useful when bringing up a link to a peer
+    /// with an unknown configuration. The candidate offsets are tried in the given order
+    /// with [DleEncoder::decode] and [EscapeScheme::Additive]. Returns the decoded length
+    /// and the first offset which decoded the frame without errors. Candidates which are
+    /// not a valid escape scheme according to [DleEncoder::validate_config] are skipped.
+    /// A frame without escape sequences is decoded by the first valid candidate, so the
+    /// returned offset is only meaningful for frames containing escaped characters.
+    ///
+    /// If no candidate decodes the frame, the error of the last tried candidate is
+    /// returned, or [DleError::SourceIncomplete] if no candidate was tried.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `candidate_offsets` - The escape offsets to try
+    /// * `dest_stream` - Decoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let (decoded_len, offset) = dle_encoder
+    ///     .decode_probe_offset(&[0x02, 0x10, 0x22, 0x03], &[0x40, 0x20], &mut decoding_buffer)
+    ///     .unwrap();
+    /// assert_eq!(&decoding_buffer[..decoded_len], &[0x02]);
+    /// assert_eq!(offset, 0x20);
+    /// ```
+    pub fn decode_probe_offset(
+        &self,
+        source_stream: &[u8],
+        candidate_offsets: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<(usize, u8), DleError> {
+        let mut last_error = DleError::SourceIncomplete;
+        for &offset in candidate_offsets {
+            let candidate = DleEncoder {
+                escape_scheme: EscapeScheme::Additive(offset),
+                ..*self
+            };
+            if candidate.validate_config().is_err() {
+                continue;
+            }
+            let mut read_len = 0;
+            match candidate.decode(source_stream, dest_stream, &mut read_len) {
+                Ok(decoded_len) => return Ok((decoded_len, offset)),
+                Err(error) => last_error = error,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Fast path for escaped frames which do not contain any DLE characters. The frame body
+    /// is copied into the destination at once. Returns [None] if the frame needs to be
+    /// decoded byte by byte, which is also the case for all erroneous frames.
+    fn decode_escaped_fast(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Option<usize> {
+        let body = source_stream.strip_prefix(&[STX_CHAR])?;
+        let body_len = self.plain_run_len(body);
+        if self.etx_terminates_only_after_dle || !self.is_terminator(*body.get(body_len)?) {
+            return None;
+        }
+        dest_stream
+            .get_mut(..body_len)?
+            .copy_from_slice(body.get(..body_len)?);
+        *read_len = body_len + 2;
+        Some(body_len)
+    }
+
+    /// Decode an escaped frame byte by byte. If `bulk_copy` is set, runs of plain bytes
+    /// between escape sequences are copied at once, which is enabled with the `memchr`
+    /// feature.
+    fn decode_escaped_generic(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+        bulk_copy: bool,
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = 0;
+        let mut decoded_idx = 0;
+        let source_stream_len = source_stream.len();
+        let dest_stream_len = dest_stream.len();
+        *read_len = 0;
+        if !matches!(source_stream.first(), Some(&byte) if self.matches_start(STX_CHAR, byte)) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        encoded_idx += 1;
+        // The last byte is never decoded as payload, so it can be checked for the terminator
+        let last_idx = source_stream_len.saturating_sub(1);
+        while encoded_idx < last_idx && decoded_idx < dest_stream_len {
+            let byte = match source_stream.get(encoded_idx) {
+                Some(&byte) if !self.is_bare_terminator(byte) && byte != STX_CHAR => byte,
+                _ => break,
+            };
+            let decoded_byte = if byte == DLE_CHAR {
+                let next_byte = match source_stream.get(encoded_idx + 1) {
+                    Some(&next_byte) => next_byte,
+                    None => {
+                        *read_len = source_stream_len;
+                        return Err(DleError::decoding(
+                            DecodeErrorKind::MissingEnd,
+                            source_stream_len,
+                        ));
+                    }
+                };
+                encoded_idx += 1;
+                if self.is_dle_terminator(next_byte) {
+                    *read_len = encoded_idx + 1;
+                    return Ok(decoded_idx);
+                } else if self.is_resync_marker(next_byte) {
+                    encoded_idx += 1;
+                    continue;
+                } else if next_byte == DLE_CHAR {
+                    next_byte
+                } else if let Some(byte) = self.unescape(next_byte) {
+                    byte
+                } else {
+                    *read_len = encoded_idx + 1;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::InvalidDleSequence { found: next_byte },
+                        encoded_idx,
+                    ));
+                }
+            } else if bulk_copy && !self.is_terminator(byte) {
+                let run = source_stream.get(encoded_idx..last_idx).unwrap_or_default();
+                let run_len = self
+                    .plain_run_len(run)
+                    .min(dest_stream_len.saturating_sub(decoded_idx));
+                decoded_idx = write_bytes(
+                    dest_stream,
+                    decoded_idx,
+                    run.get(..run_len).unwrap_or_default(),
+                )?;
+                encoded_idx += run_len;
+                continue;
+            } else {
+                byte
+            };
+            write_byte(dest_stream, decoded_idx, decoded_byte)?;
+            encoded_idx += 1;
+            decoded_idx += 1
+        }
+
+        let last_byte = match source_stream.get(encoded_idx) {
+            Some(&last_byte) => last_byte,
+            None => {
+                // The stream ended with the start marker or an escape sequence
+                *read_len = source_stream_len;
+                return Err(DleError::decoding(
+                    DecodeErrorKind::MissingEnd,
+                    source_stream_len,
+                ));
+            }
+        };
+        // The loop also stops before a DLE terminator sequence if the destination is full
+        let terminated = if self.etx_terminates_only_after_dle {
+            last_byte == DLE_CHAR
+                && matches!(source_stream.get(encoded_idx + 1), Some(&byte) if self.is_dle_terminator(byte))
+        } else {
+            self.is_terminator(last_byte)
+        };
+        if !terminated {
+            if decoded_idx == dest_stream_len {
+                *read_len = 0;
+                Err(DleError::StreamTooShort)
+            } else if last_byte == STX_CHAR {
+                *read_len = encoded_idx + 1;
+                Err(DleError::decoding(
+                    DecodeErrorKind::UnexpectedStart,
+                    encoded_idx,
+                ))
+            } else {
+                *read_len = encoded_idx + 1;
+                Err(DleError::decoding(
+                    DecodeErrorKind::MissingEnd,
+                    source_stream_len,
+                ))
+            }
+        } else {
+            *read_len = encoded_idx + 1 + usize::from(self.etx_terminates_only_after_dle);
+            Ok(decoded_idx)
+        }
+    }
+
+    /// This method decodes a given byte stream which was encoded with a ASCII
+    /// DLE encoder. It explicitely does so in the non-escaped mode.
+    /// It returns the length of the decoded buffer or an error code if
+    /// there is a decoder failure or the destination stream is too short.
+    /// Raw STX and ETX characters inside the frame are decoded as data and are not
+    /// treated as frame boundaries, unless `strict_non_escaped` is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read bytes in the source stream will be
+    ///   assigned to this variable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let encoded_array: [u8; 6] = [0x10, 0x02, 0x02, 0x03, 0x10, 0x03];
+    /// let mut read_len = 0;
+    /// let decode_result = dle_encoder.decode_non_escaped(
+    ///     &encoded_array, &mut decoding_buffer, &mut read_len
+    /// );
+    /// assert!(decode_result.is_ok());
+    /// let decoded_len = decode_result.unwrap();
+    /// assert_eq!(decoded_len, 2);
+    ///
+    /// println!("Source buffer: {:?}", encoded_array);
+    /// println!("Encoded stream: {:?}", &decoding_buffer[ .. decoded_len])
+    /// ```
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_non_escaped(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        let decoded_len = match self.decode_non_escaped_fast(source_stream, dest_stream, read_len) {
+            Some(decoded_len) => decoded_len,
+            None => self.decode_non_escaped_generic(source_stream, dest_stream, read_len)?,
+        };
+        let terminator = DleEncoder::frame_terminator(source_stream, *read_len)?;
+        let decoded_len = self.append_terminator(dest_stream, decoded_len, terminator, read_len)?;
+        *read_len = self.skip_padding(source_stream, *read_len);
+        self.check_trailing(source_stream, *read_len)?;
+        Ok(decoded_len)
+    }
+
+    /// Append the terminator character which ended the frame to a decoded stream if this
+    /// was configured
+    fn append_terminator(
+        &self,
+        dest_stream: &mut [u8],
+        decoded_len: usize,
+        terminator: u8,
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        if !self.keep_terminator {
+            return Ok(decoded_len);
+        }
+        match dest_stream.get_mut(decoded_len) {
+            Some(dest) => {
+                *dest = terminator;
+                Ok(decoded_len + 1)
+            }
+            None => {
+                *read_len = 0;
+                Err(DleError::StreamTooShort)
+            }
+        }
+    }
+
+    /// Returns the end offset of the padding following a decoded frame which ends at the given
+    /// offset. Fill bytes are only skipped up to the padded length
+    fn skip_padding(&self, source_stream: &[u8], frame_end: usize) -> usize {
+        let (padded_len, fill) = match self.pad_to {
+            Some(pad_to) => pad_to,
+            None => return frame_end,
+        };
+        let padding_end = padded_len.max(frame_end).min(source_stream.len());
+        source_stream
+            .get(frame_end..padding_end)
+            .unwrap_or_default()
+            .iter()
+            .position(|&byte| byte != fill)
+            .map_or(padding_end, |pad_len| frame_end + pad_len)
+    }
+
+    /// Check the bytes following a decoded frame which ends at the given offset according to
+    /// the configured trailing policy
+    fn check_trailing(&self, source_stream: &[u8], frame_end: usize) -> Result<(), DleError> {
+        let trailing = source_tail(source_stream, frame_end);
+        let allowed = match self.trailing_policy {
+            TrailingPolicy::Ignore => true,
+            // A start marker which was cut off at the end of the stream is accepted as well,
+            // and idle sequences may precede the next frame
+            TrailingPolicy::Error => {
+                let trailing = source_tail(trailing, self.idle_prefix_len(trailing));
+                trailing.starts_with(self.start_marker())
+                    || self.start_marker().starts_with(trailing)
+            }
+            TrailingPolicy::RequireEmpty => trailing.is_empty(),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(DleError::decoding(
+                DecodeErrorKind::TrailingBytes,
+                frame_end,
+            ))
+        }
+    }
+
+    /// Fast path for non-escaped frames which do not contain any doubled DLE characters.
+    /// The frame body is copied into the destination at once. Returns [None] if the frame
+    /// needs to be decoded byte by byte, which is also the case for all erroneous frames.
+    fn decode_non_escaped_fast(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Option<usize> {
+        if self.strict_non_escaped {
+            return None;
+        }
+        let body = source_stream.strip_prefix(&[DLE_CHAR, STX_CHAR])?;
+        let body_len = body.iter().position(|&byte| byte == DLE_CHAR)?;
+        let terminated = matches!(body.get(body_len + 1), Some(&byte) if self.is_terminator(byte));
+        if !terminated {
+            return None;
+        }
+        dest_stream
+            .get_mut(..body_len)?
+            .copy_from_slice(body.get(..body_len)?);
+        *read_len = body_len + 4;
+        Some(body_len)
+    }
+
+    fn decode_non_escaped_generic(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &mut [u8],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        let mut encoded_idx = 0;
+        let mut decoded_idx = 0;
+        let source_stream_len = source_stream.len();
+        *read_len = 0;
+
+        if source_stream.first() != Some(&DLE_CHAR) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        encoded_idx += 1;
+        match source_stream.get(encoded_idx) {
+            Some(&byte) if self.matches_start(STX_CHAR, byte) => (),
+            Some(_) => {
+                *read_len = 1;
+                return Err(DleError::decoding(DecodeErrorKind::MissingStart, 1));
+            }
+            None => {
+                *read_len = 1;
+                return Err(DleError::decoding(DecodeErrorKind::MissingEnd, 1));
+            }
+        }
+        encoded_idx += 1;
+        while let Some(&byte) = source_stream.get(encoded_idx) {
+            let mut next_decoded = byte;
+            if next_decoded == DLE_CHAR {
+                let next_byte = match source_stream.get(encoded_idx + 1) {
+                    Some(&next_byte) => next_byte,
+                    None => {
+                        *read_len = source_stream_len;
+                        return Err(DleError::decoding(
+                            DecodeErrorKind::MissingEnd,
+                            source_stream_len,
+                        ));
+                    }
+                };
+                if self.is_resync_marker(next_byte) {
+                    encoded_idx += 2;
+                    continue;
+                } else if next_byte == STX_CHAR {
+                    // Set read_len so the DLE/STX char combination is preserved
+                    // It could be the start of another frame
+                    *read_len = encoded_idx;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::UnexpectedStart,
+                        encoded_idx,
+                    ));
+                } else if next_byte == DLE_CHAR {
+                    next_decoded = next_byte;
+                    encoded_idx += 1;
+                } else if self.is_terminator(next_byte) {
+                    // End of stream reached
+                    *read_len = encoded_idx + 2;
+                    return Ok(decoded_idx);
+                } else if self.lenient_dle {
+                    next_decoded = next_byte;
+                    encoded_idx += 1;
+                } else {
+                    *read_len = encoded_idx;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::InvalidDleSequence { found: next_byte },
+                        encoded_idx + 1,
+                    ));
+                }
+            } else if self.is_raw_control(next_decoded) {
+                *read_len = encoded_idx;
+                return Err(DleError::decoding(
+                    DecodeErrorKind::RawControlChar,
+                    encoded_idx,
+                ));
+            }
+            match dest_stream.get_mut(decoded_idx) {
+                Some(dest) => *dest = next_decoded,
+                None => {
+                    // So far we did not find anything wrong here, let the user try
+                    // again
+                    *read_len = 0;
+                    return Err(DleError::StreamTooShort);
+                }
+            }
+            encoded_idx += 1;
+            decoded_idx += 1;
+        }
+
+        *read_len = encoded_idx;
+        Err(DleError::decoding(
+            DecodeErrorKind::MissingEnd,
+            source_stream_len,
+        ))
+    }
+
+    /// This method decodes a frame from bytes which are pulled one at a time from the given
+    /// closure. This decouples the decoder from any specific buffer or I/O type. Bytes are
+    /// pulled until a complete frame was decoded, so no bytes following the frame are
+    /// consumed. If the closure returns [None] before the end of the frame,
+    /// [DleError::SourceIncomplete] is returned. The offsets of decoding errors are the
+    /// number of bytes pulled before the offending byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - Closure returning the next byte of the source stream, or [None] if no
+    ///   more bytes are available
+    /// * `dest_stream` - Decoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut decoding_buffer: [u8; 16] = [0; 16];
+    /// let mut source = [0x02, 0x10, 0x02 + 0x40, 0x03].iter().copied();
+    /// let decoded_len = dle_encoder.decode_pull(|| source.next(), &mut decoding_buffer);
+    /// assert_eq!(decoded_len, Ok(1));
+    /// assert_eq!(decoding_buffer[0], 0x02);
+    ///
+    /// let mut source = [0x02, 0x00].iter().copied();
+    /// let decode_result = dle_encoder.decode_pull(|| source.next(), &mut decoding_buffer);
+    /// assert_eq!(decode_result, Err(DleError::SourceIncomplete));
+    /// ```
+    pub fn decode_pull(
+        &self,
+        mut next: impl FnMut() -> Option<u8>,
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let mut pulled: usize = 0;
+        // Returns the next byte and its offset in the source stream
+        let mut pull = || {
+            let offset = pulled;
+            pulled = pulled.saturating_add(1);
+            next()
+                .map(|byte| (offset, byte))
+                .ok_or(DleError::SourceIncomplete)
+        };
+        for &marker in self.start_marker() {
+            let (offset, byte) = pull()?;
+            if !self.matches_start(marker, byte) {
+                return Err(DleError::decoding(DecodeErrorKind::MissingStart, offset));
+            }
+        }
+        let mut decoded_idx = 0;
+        let terminator = loop {
+            let (offset, byte) = pull()?;
+            let decoded = if self.is_bare_terminator(byte) {
+                break byte;
+            } else if self.escape_stx_etx && byte == STX_CHAR {
+                return Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, offset));
+            } else if byte == DLE_CHAR {
+                let (_, next_byte) = pull()?;
+                if self.is_resync_marker(next_byte) {
+                    continue;
+                } else if next_byte == DLE_CHAR {
+                    DLE_CHAR
+                } else if self.is_dle_terminator(next_byte) {
+                    break next_byte;
+                } else if !self.escape_stx_etx && next_byte == STX_CHAR {
+                    return Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, offset));
+                } else if !self.escape_stx_etx && self.lenient_dle {
+                    next_byte
+                } else {
+                    match self.unescape(next_byte).filter(|_| self.escape_stx_etx) {
+                        Some(decoded) => decoded,
+                        None => {
+                            return Err(DleError::decoding(
+                                DecodeErrorKind::InvalidDleSequence { found: next_byte },
+                                offset + 1,
+                            ))
+                        }
+                    }
+                }
+            } else if self.is_raw_control(byte) {
+                return Err(DleError::decoding(DecodeErrorKind::RawControlChar, offset));
+            } else {
+                byte
+            };
+            *dest_stream
+                .get_mut(decoded_idx)
+                .ok_or(DleError::StreamTooShort)? = decoded;
+            decoded_idx += 1;
+        };
+        self.append_terminator(dest_stream, decoded_idx, terminator, &mut 0)
+    }
+
+    /// This method returns the length of the decoded stream of the first frame in the given
+    /// stream without decoding it into a destination. This is the length [DleEncoder::decode]
+    /// returns for the given source stream. If the frame is malformed, the same error as
+    /// returned by [DleEncoder::decode] is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded_array: [u8; 6] = [0x02, 0x00, 0x10, 0x42, 0x10, 0x03];
+    /// assert!(dle_encoder.decoded_len(&encoded_array).is_err());
+    /// assert_eq!(dle_encoder.decoded_len(&encoded_array[..4]).unwrap_err().offset(), Some(4));
+    /// assert_eq!(dle_encoder.decoded_len(&[0x02, 0x00, 0x10, 0x42, 0x03]), Ok(2));
+    /// ```
+    pub fn decoded_len(&self, source_stream: &[u8]) -> Result<usize, DleError> {
+        self.scan_frame(source_stream, usize::MAX)
+            .map(|(decoded_len, _)| decoded_len)
+    }
+
+    /// This method returns the length of the decoded stream of the first frame in the given
+    /// stream like [DleEncoder::decoded_len], but stops scanning with
+    /// [DleError::FrameTooLarge] as soon as the decoded length exceeds the given limit. This
+    /// allows rejecting oversized frames from untrusted sources cheaply before decoding
+    /// them into a fixed buffer, without scanning the whole frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `limit` - Maximum accepted decoded length
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{DleEncoder, DleError};
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded_array: [u8; 5] = [0x02, 0x00, 0x10, 0x42, 0x03];
+    /// assert_eq!(dle_encoder.decoded_len_bounded(&encoded_array, 2), Ok(2));
+    /// assert_eq!(
+    ///     dle_encoder.decoded_len_bounded(&encoded_array, 1),
+    ///     Err(DleError::FrameTooLarge)
+    /// );
+    /// ```
+    pub fn decoded_len_bounded(
+        &self,
+        source_stream: &[u8],
+        limit: usize,
+    ) -> Result<usize, DleError> {
+        self.scan_frame(source_stream, limit)
+            .map(|(decoded_len, _)| decoded_len)
+    }
+
+    /// This method heuristically checks whether the given buffer already is a single DLE
+    /// encoded frame with this configuration. This is useful to avoid encoding data twice.
+    /// The buffer must start with the start marker, end with the end marker of the frame and
+    /// only contain valid escape sequences in between. Frames encoded without framing
+    /// characters can not be detected.
+    ///
+    /// Please note that raw payloads can have the same structure as an encoded frame, so
+    /// false positives are possible. For example, in the escaped mode, any payload starting
+    /// with STX, ending with ETX and containing no other control characters looks like an
+    /// encoded frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The buffer to check
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// assert!(dle_encoder.looks_encoded(&[0x02, 0x01, 0x10, 0x42, 0x03]));
+    /// assert!(!dle_encoder.looks_encoded(&[0x01, 0x02, 0x03]));
+    /// // False positive for a raw payload
+    /// assert!(dle_encoder.looks_encoded(&[0x02, 0x41, 0x03]));
+    /// ```
+    pub fn looks_encoded(&self, source_stream: &[u8]) -> bool {
+        matches!(self.scan_frame(source_stream, usize::MAX), Ok((_, read_len)) if read_len == source_stream.len())
+    }
+
+    /// Scan the first frame of the given stream without decoding it. Returns the decoded
+    /// length and the number of bytes of the frame, or the error [DleEncoder::decode] returns.
+    /// [DleError::FrameTooLarge] is returned once the decoded length exceeds the given limit
+    fn scan_frame(&self, source_stream: &[u8], limit: usize) -> Result<(usize, usize), DleError> {
+        let idle_len = self.idle_prefix_len(source_stream);
+        self.scan_frame_body(source_tail(source_stream, idle_len), limit)
+            .map(|(decoded_len, read_len)| (decoded_len, idle_len + read_len))
+            .map_err(|error| error.shifted(idle_len))
+    }
+
+    fn scan_frame_body(
+        &self,
+        source_stream: &[u8],
+        limit: usize,
+    ) -> Result<(usize, usize), DleError> {
+        let (mut encoded_idx, start_marker) = if self.escape_stx_etx {
+            (1, &[STX_CHAR][..])
+        } else {
+            (2, &[DLE_CHAR, STX_CHAR][..])
+        };
+        for (idx, marker) in start_marker.iter().enumerate() {
+            match source_stream.get(idx) {
+                Some(&byte) if self.matches_start(*marker, byte) => (),
+                // The stream ended inside the non-escaped start marker
+                None if idx > 0 => {
+                    return Err(DleError::decoding(DecodeErrorKind::MissingEnd, idx))
+                }
+                _ => return Err(DleError::decoding(DecodeErrorKind::MissingStart, idx)),
+            }
+        }
+        let mut decoded_len = usize::from(self.keep_terminator);
+        while let Some(&byte) = source_stream.get(encoded_idx) {
+            if decoded_len > limit {
+                return Err(DleError::FrameTooLarge);
+            }
+            if self.is_bare_terminator(byte) {
+                return Ok((decoded_len, encoded_idx + 1));
+            } else if self.escape_stx_etx && byte == STX_CHAR {
+                return Err(DleError::decoding(
+                    DecodeErrorKind::UnexpectedStart,
+                    encoded_idx,
+                ));
+            } else if byte == DLE_CHAR {
+                let next_byte = match source_stream.get(encoded_idx + 1) {
+                    Some(&next_byte) => next_byte,
+                    None => break,
+                };
+                if self.is_resync_marker(next_byte) {
+                    encoded_idx += 2;
+                    continue;
+                } else if self.is_dle_terminator(next_byte) {
+                    return Ok((decoded_len, encoded_idx + 2));
+                } else if !self.escape_stx_etx && next_byte == STX_CHAR {
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::UnexpectedStart,
+                        encoded_idx,
+                    ));
+                } else if next_byte != DLE_CHAR
+                    && !(if self.escape_stx_etx {
+                        self.unescape(next_byte).is_some()
+                    } else {
+                        self.lenient_dle
+                    })
+                {
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::InvalidDleSequence { found: next_byte },
+                        encoded_idx + 1,
+                    ));
+                }
+                encoded_idx += 1;
+            } else if self.is_raw_control(byte) {
+                return Err(DleError::decoding(
+                    DecodeErrorKind::RawControlChar,
+                    encoded_idx,
+                ));
+            }
+            decoded_len += 1;
+            encoded_idx += 1;
+        }
+        Err(DleError::decoding(
+            DecodeErrorKind::MissingEnd,
+            source_stream.len(),
+        ))
+    }
+
+    /// This method returns the minimum length of a destination stream which guarantees that
+    /// [DleEncoder::decode] does not return [DleError::StreamTooShort] for the given source
+    /// stream. For a well-formed frame, this is equal to the length returned by
+    /// [DleEncoder::decoded_len]. For malformed frames, the length of the source stream is
+    /// returned as a safe upper bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    pub fn min_decode_dest_len(&self, source_stream: &[u8]) -> usize {
+        self.decoded_len(source_stream)
+            .unwrap_or(source_stream.len())
+    }
+
+    /// This method checks whether every payload survives encoding and decoding with this
+    /// configuration unchanged. This is useful to catch misconfigurations at startup.
+    /// In addition to the checks of [DleEncoder::validate_config], configurations which
+    /// can not be decoded or alter the decoded data are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{ByteSet, ConfigError, DleEncoder};
+    ///
+    /// let mut dle_encoder = DleEncoder::default();
+    /// assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+    /// // 0xC2 is escaped to STX with the default escape scheme
+    /// dle_encoder.escape_extra = ByteSet::from_bytes(&[0xC2]);
+    /// assert_eq!(
+    ///     dle_encoder.assert_lossless(),
+    ///     Err(ConfigError::ControlCharEscape { byte: 0xC2 })
+    /// );
+    /// ```
+    pub fn assert_lossless(&self) -> Result<(), ConfigError> {
+        if !self.add_stx_etx {
+            return Err(ConfigError::MissingFraming);
+        }
+        if self.keep_terminator {
+            return Err(ConfigError::KeepTerminator);
+        }
+        if self.strict_non_escaped && !self.escape_stx_etx {
+            return Err(ConfigError::StrictNonEscaped);
+        }
+        self.validate_config()
+    }
+
+    /// This method checks whether the given payload survives encoding and decoding with this
+    /// configuration unchanged, by encoding and decoding it internally. Unlike
+    /// [DleEncoder::assert_lossless], this also detects problems which only occur for some
+    /// payloads, for example an alternative terminator inside the payload which is not
+    /// escaped. Returns false if encoding or decoding fails or if `add_stx_etx` is disabled,
+    /// because the encoded stream can not be decoded without external framing then.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to check
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let mut dle_encoder = DleEncoder::default();
+    /// assert!(dle_encoder.is_reversible(&[0x10, 0x02, 0x0d]));
+    /// dle_encoder.alt_terminator = Some(0x0d);
+    /// assert!(!dle_encoder.is_reversible(&[0x10, 0x02, 0x0d]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn is_reversible(&self, payload: &[u8]) -> bool {
+        if !self.add_stx_etx {
+            return false;
+        }
+        let mut encoded = vec![0; self.max_encoded_len(payload.len())];
+        let encoded_len = match self.encode(payload, &mut encoded) {
+            Ok(encoded_len) => encoded_len,
+            Err(_) => return false,
+        };
+        let encoded = encoded.get(..encoded_len).unwrap_or_default();
+        let mut decoded = vec![0; encoded_len];
+        let mut read_len = 0;
+        matches!(
+            self.decode(encoded, &mut decoded, &mut read_len),
+            Ok(decoded_len) if read_len == encoded_len && decoded.get(..decoded_len) == Some(payload)
+        )
+    }
+
+    /// This method checks whether the escaped forms of all escaped characters are distinct
+    /// from each other and from the control characters, so the decoder can restore the
+    /// original characters. The escape configuration is only checked in the escaped mode
+    /// because it is ignored in the non-escaped mode. The alternative terminator is checked
+    /// in both modes and must not be STX, ETX or DLE, and the fill byte of the padding and
+    /// the idle sequence must not contain STX or DLE. This should be called after
+    /// configuring custom escaped characters, escape schemes, terminators, padding or idle
+    /// sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{ConfigError, DleEncoder, EscapeScheme};
+    ///
+    /// let mut dle_encoder = DleEncoder::default();
+    /// assert_eq!(dle_encoder.validate_config(), Ok(()));
+    /// // STX XOR 0x01 is ETX
+    /// dle_encoder.escape_scheme = EscapeScheme::Xor(0x01);
+    /// assert_eq!(
+    ///     dle_encoder.validate_config(),
+    ///     Err(ConfigError::ControlCharEscape { byte: 0x02 })
+    /// );
+    /// ```
+    pub fn validate_config(&self) -> Result<(), ConfigError> {
+        if let Some(byte) = self.alt_terminator {
+            if byte == STX_CHAR || byte == ETX_CHAR || byte == DLE_CHAR {
+                return Err(ConfigError::AltTerminatorConflict { byte });
+            }
+        }
+        if let Some((_, byte)) = self.pad_to {
+            if byte == STX_CHAR || byte == DLE_CHAR {
+                return Err(ConfigError::FillConflict { byte });
+            }
+        }
+        let idle = self.idle_on_empty.unwrap_or_default();
+        if let Some(&byte) = idle
+            .iter()
+            .find(|&&byte| byte == STX_CHAR || byte == DLE_CHAR)
+        {
+            return Err(ConfigError::IdleConflict { byte });
+        }
+        if !self.escape_stx_etx {
+            return Ok(());
+        }
+        let valid_escapes = self.valid_escapes();
+        // The escaped form of DLE is irrelevant if DLE is escaped by doubling it
+        let is_escaped = |byte: u8| {
+            (byte != DLE_CHAR || self.dle_escape_style == DleStyle::Offset)
+                && valid_escapes.contains(byte)
+        };
+        let escaped_bytes = (0..=u8::MAX).filter(|&byte| is_escaped(byte));
+        let mut escaped_forms = ByteSet::new();
+        for byte in escaped_bytes {
+            let escaped = self.escape_scheme.escape(byte);
+            if escaped == STX_CHAR
+                || escaped == ETX_CHAR
+                || escaped == DLE_CHAR
+                || (escaped == SYN_CHAR && self.resync_interval.is_some())
+            {
+                return Err(ConfigError::ControlCharEscape { byte });
+            }
+            if escaped_forms.contains(escaped) {
+                let first = (0..byte)
+                    .find(|&other| is_escaped(other) && self.escape_scheme.escape(other) == escaped)
+                    .unwrap_or(byte);
+                return Err(ConfigError::AmbiguousEscape {
+                    first,
+                    second: byte,
+                });
+            }
+            escaped_forms.insert(escaped);
+        }
+        Ok(())
+    }
+
+    /// This method returns a fingerprint of the complete configuration, which can be used
+    /// to key caches of encoded data or to detect configuration changes. The fingerprint is
+    /// a FNV-1a hash and stable across runs and platforms. It is also kept stable across
+    /// crate versions for configurations which do not use new fields. Equal configurations
+    /// always have equal fingerprints, while different configurations have different
+    /// fingerprints with a high probability.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// assert_eq!(
+    ///     dle_encoder.config_fingerprint(),
+    ///     DleEncoder::escaped().config_fingerprint()
+    /// );
+    /// assert_ne!(
+    ///     dle_encoder.config_fingerprint(),
+    ///     DleEncoder::non_escaped().config_fingerprint()
+    /// );
+    /// ```
+    pub fn config_fingerprint(&self) -> u32 {
+        let flags = [
+            self.escape_stx_etx,
+            self.escape_cr,
+            self.add_stx_etx,
+            self.keep_terminator,
+            self.strict_escapes,
+            self.strict_non_escaped,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0u8, |flags, (idx, &flag)| flags | (u8::from(flag) << idx));
+        let mut hash = fnv1a(FNV_OFFSET_BASIS, &[flags]);
+        for word in self.escape_extra.0.iter() {
+            hash = fnv1a(hash, &word.to_le_bytes());
+        }
+        hash = match self.escape_scheme {
+            EscapeScheme::Additive(offset) => fnv1a(hash, &[0, offset]),
+            EscapeScheme::Xor(mask) => fnv1a(hash, &[1, mask]),
+            EscapeScheme::Table(table) => fnv1a(fnv1a(hash, &[2]), table),
+        };
+        hash = match self.alt_terminator {
+            Some(terminator) => fnv1a(hash, &[1, terminator]),
+            None => fnv1a(hash, &[0]),
+        };
+        let text_format = match self.text_format {
+            TextFormat::Char => 0,
+            TextFormat::Hex => 1,
+        };
+        hash = fnv1a(hash, &[text_format]);
+        // Lengths are hashed as 64 bit values so the fingerprint does not depend on the
+        // pointer width
+        hash = match self.resync_interval {
+            Some(interval) => fnv1a(fnv1a(hash, &[1]), &(interval as u64).to_le_bytes()),
+            None => fnv1a(hash, &[0]),
+        };
+        let trailing_policy = match self.trailing_policy {
+            TrailingPolicy::Ignore => 0,
+            TrailingPolicy::Error => 1,
+            TrailingPolicy::RequireEmpty => 2,
+        };
+        hash = fnv1a(hash, &[trailing_policy]);
+        hash = match self.pad_to {
+            Some((len, fill)) => fnv1a(fnv1a(hash, &[1, fill]), &(len as u64).to_le_bytes()),
+            None => fnv1a(hash, &[0]),
+        };
+        // Only hashed if set, so the fingerprints of existing configurations do not change
+        if let Some(idle) = self.idle_on_empty {
+            hash = fnv1a(fnv1a(hash, &(idle.len() as u64).to_le_bytes()), idle);
+        }
+        if self.lenient_stx {
+            hash = fnv1a(hash, &[0x01]);
+        }
+        if self.lenient_dle {
+            hash = fnv1a(hash, &[0x02]);
+        }
+        if self.dle_escape_style == DleStyle::Offset {
+            hash = fnv1a(hash, &[0x03]);
+        }
+        if self.etx_terminates_only_after_dle {
+            hash = fnv1a(hash, &[0x04]);
+        }
+        hash
+    }
+
+    /// This method encodes the given payload with this and the other configuration and
+    /// compares the encoded forms. This shows whether migrating to another configuration
+    /// changes the data on the wire, and where. An error is returned if the payload can not
+    /// be encoded with one of the configurations.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The configuration to compare with
+    /// * `payload` - The payload to encode with both configurations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let diff = dle_encoder
+    ///     .diff_against(&DleEncoder::non_escaped(), &[0x01, 0x02])
+    ///     .unwrap();
+    /// assert_eq!(diff.encoded, &[0x02, 0x01, 0x10, 0x42, 0x03]);
+    /// assert_eq!(diff.other_encoded, &[0x10, 0x02, 0x01, 0x02, 0x10, 0x03]);
+    /// assert_eq!(diff.first_difference, Some(0));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn diff_against(&self, other: &DleEncoder, payload: &[u8]) -> Result<EncodeDiff, DleError> {
+        let encode = |dle_encoder: &DleEncoder| -> Result<Vec<u8>, DleError> {
+            let mut encoded = vec![0; dle_encoder.max_encoded_len(payload.len())];
+            let encoded_len = dle_encoder.encode(payload, &mut encoded)?;
+            encoded.truncate(encoded_len);
+            Ok(encoded)
+        };
+        let encoded = encode(self)?;
+        let other_encoded = encode(other)?;
+        let first_difference = encoded
+            .iter()
+            .zip(other_encoded.iter())
+            .position(|(byte, other_byte)| byte != other_byte)
+            .or_else(|| {
+                (encoded.len() != other_encoded.len())
+                    .then(|| encoded.len().min(other_encoded.len()))
+            });
+        Ok(EncodeDiff {
+            encoded,
+            other_encoded,
+            first_difference,
+        })
+    }
+
+    /// This method returns the source byte ranges of all complete frames inside the given
+    /// stream, including the framing characters. The frames are not decoded, so each range
+    /// can be stored and decoded lazily later. Incomplete frames and bytes outside of frames
+    /// are skipped. Escaped ETX characters and doubled DLE characters inside a frame are
+    /// handled correctly. Frames can only be detected if they were encoded with STX and ETX
+    /// characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to search for frames
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let encoded_frames: [u8; 9] = [0x02, 0x01, 0x03, 0xff, 0x02, 0x10, 0x42, 0x05, 0x03];
+    /// let ranges = dle_encoder.frame_ranges(&encoded_frames);
+    /// assert_eq!(ranges, vec![0..3, 4..9]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn frame_ranges(&self, source_stream: &[u8]) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        let mut offset = 0;
+        while let Some(range) = self.find_frame(source_tail(source_stream, offset)) {
+            ranges.push(offset + range.start..offset + range.end);
+            offset += range.end;
+        }
+        ranges
+    }
+
+    /// This method extracts and decodes all valid frames inside a stream which also contains
+    /// other data, for example the serial output of a device where frames are interleaved
+    /// with log messages. Everything outside of frames as well as malformed frames are
+    /// skipped. For each valid frame, the source byte range including the framing characters
+    /// and the decoded payload are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to search for frames
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoder;
+    ///
+    /// let dle_encoder = DleEncoder::default();
+    /// let mut log_output = b"boot ok\n".to_vec();
+    /// log_output.extend_from_slice(&[0x02, 0x10, 0x42, 0x01, 0x03]);
+    /// log_output.extend_from_slice(b"idle\n");
+    /// let frames = dle_encoder.scan_frames(&log_output);
+    /// assert_eq!(frames, vec![(8..13, vec![0x02, 0x01])]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn scan_frames(&self, source_stream: &[u8]) -> Vec<(Range<usize>, Vec<u8>)> {
+        let mut frames = Vec::new();
+        let mut offset = 0;
+        while let Some(range) = self.find_frame(source_tail(source_stream, offset)) {
+            let range = offset + range.start..offset + range.end;
+            offset = range.end;
+            let mut payload = vec![0; range.len()];
+            let mut read_len = 0;
+            let frame = source_stream.get(range.clone()).unwrap_or_default();
+            if let Ok(decoded_len) = self.decode(frame, &mut payload, &mut read_len) {
+                payload.truncate(decoded_len);
+                frames.push((range, payload));
+            }
+        }
+        frames
+    }
+
+    /// Find the first complete frame inside the given stream and return its byte range,
+    /// including the framing characters. The escape sequences inside the frame are not
+    /// validated.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn find_frame(&self, source_stream: &[u8]) -> Option<Range<usize>> {
+        let mut start = None;
+        let mut idx = 0;
+        while let Some(&byte) = source_stream.get(idx) {
+            if self.escape_stx_etx {
+                if byte == STX_CHAR {
+                    // A new STX always restarts the frame
+                    start = Some(idx);
+                } else if self.is_bare_terminator(byte) {
+                    if let Some(start) = start {
+                        return Some(start..idx + 1);
+                    }
+                } else if byte == DLE_CHAR && source_stream.get(idx + 1) != Some(&STX_CHAR) {
+                    let dle_terminated = matches!(source_stream.get(idx + 1), Some(&byte) if self.is_dle_terminator(byte));
+                    if let Some(start) = start.filter(|_| dle_terminated) {
+                        return Some(start..idx + 2);
+                    }
+                    // The escaped form of a character might be the alternate terminator
+                    idx += 1;
+                }
+            } else if byte == DLE_CHAR {
+                match source_stream.get(idx + 1) {
+                    Some(&STX_CHAR) => start = Some(idx),
+                    Some(&byte) if start.is_some() && self.is_terminator(byte) => {
+                        return start.map(|start| start..idx + 2);
+                    }
+                    Some(_) => (),
+                    None => return None,
+                }
+                // Skip the character following DLE so doubled DLE chars are not mistaken for
+                // the start of a control sequence
+                idx += 1;
+            }
+            idx += 1;
+        }
+        None
+    }
+
+    /// Check whether a valid frame in the escaped mode contains an escaped CR character
+    #[cfg(feature = "alloc")]
+    fn contains_escaped_cr(&self, frame: &[u8]) -> bool {
+        let mut bytes = frame.iter();
+        while let Some(&byte) = bytes.next() {
+            if byte == DLE_CHAR {
+                if let Some(&next_byte) = bytes.next() {
+                    if next_byte != DLE_CHAR && self.unescape(next_byte) == Some(CR_CHAR) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the encoded form of a single payload byte, which is either one or two
+    /// bytes long, together with its length
+    fn encode_byte(&self, byte: u8) -> ([u8; 2], usize) {
+        if byte == DLE_CHAR {
+            ([DLE_CHAR, self.escaped_dle()], 2)
+        } else if self.escape_stx_etx && self.needs_escape(byte) {
+            ([DLE_CHAR, self.escape_scheme.escape(byte)], 2)
+        } else {
+            ([byte, 0], 1)
+        }
+    }
+
+    /// The characters which mark the start of a frame in the configured mode
+    fn start_marker(&self) -> &'static [u8] {
+        if self.escape_stx_etx {
+            &[STX_CHAR]
+        } else {
+            &[DLE_CHAR, STX_CHAR]
+        }
+    }
+
+    /// Returns the terminator character of a decoded frame with the given read length
+    fn frame_terminator(source_stream: &[u8], read_len: usize) -> Result<u8, DleError> {
+        read_len
+            .checked_sub(1)
+            .and_then(|idx| source_stream.get(idx))
+            .copied()
+            .ok_or_else(|| DleError::decoding(DecodeErrorKind::MissingEnd, source_stream.len()))
+    }
+
+    /// Check whether a received byte of a start marker matches the expected byte of the
+    /// start marker, tolerating single bit errors of STX if `lenient_stx` is enabled
+    fn matches_start(&self, marker: u8, byte: u8) -> bool {
+        byte == marker
+            || (marker == STX_CHAR
+                && self.lenient_stx
+                && (byte ^ STX_CHAR).count_ones() == 1
+                && !self.is_terminator(byte))
+    }
+
+    /// The characters which mark the end of a frame in the configured mode
+    fn end_marker(&self) -> &'static [u8] {
+        if self.escape_stx_etx && !self.etx_terminates_only_after_dle {
+            &[ETX_CHAR]
+        } else {
+            &[DLE_CHAR, ETX_CHAR]
+        }
+    }
+
+    /// Upper bound for the encoded length of a source stream with the given length
+    #[cfg(feature = "alloc")]
+    fn max_encoded_len(&self, source_len: usize) -> usize {
+        let max_len = source_len
+            .saturating_add(self.resync_marker_count(source_len))
+            .saturating_mul(2)
+            .saturating_add(4);
+        let max_len = match self.pad_to {
+            Some((padded_len, _)) => max_len.max(padded_len),
+            None => max_len,
+        };
+        match self.idle_on_empty {
+            Some(idle) => max_len.max(idle.len()),
+            None => max_len,
+        }
+    }
+
+    /// Returns the length of the idle sequences at the start of the given stream
+    fn idle_prefix_len(&self, source_stream: &[u8]) -> usize {
+        let idle = match self.idle_on_empty {
+            Some(idle) if !idle.is_empty() => idle,
+            _ => return 0,
+        };
+        let mut idle_len = 0;
+        while source_tail(source_stream, idle_len).starts_with(idle) {
+            idle_len += idle.len();
+        }
+        idle_len
+    }
+
+    /// Check whether a resync marker is written before the payload byte with the given index
+    fn resync_due(&self, source_idx: usize) -> bool {
+        matches!(self.resync_interval, Some(interval)
+            if source_idx > 0 && source_idx.checked_rem(interval) == Some(0))
+    }
+
+    /// Number of resync markers written when encoding a source stream with the given length
+    #[cfg(feature = "alloc")]
+    fn resync_marker_count(&self, source_len: usize) -> usize {
+        match self.resync_interval {
+            Some(interval) if interval > 0 => source_len.saturating_sub(1) / interval,
+            _ => 0,
+        }
+    }
+
+    /// Check whether the character following a DLE character completes a resync marker
+    /// which is skipped by the decoder
+    fn is_resync_marker(&self, byte: u8) -> bool {
+        self.resync_interval.is_some() && byte == SYN_CHAR
+    }
+
+    /// Find the start marker of the next frame inside the given stream
+    #[cfg(feature = "alloc")]
+    pub(crate) fn find_frame_start(&self, source_stream: &[u8]) -> Option<usize> {
+        if self.escape_stx_etx {
+            source_stream.iter().position(|&byte| byte == STX_CHAR)
+        } else {
+            source_stream
+                .windows(2)
+                .position(|window| window == [DLE_CHAR, STX_CHAR])
+        }
+    }
+
+    /// Returns the number of bytes at the start of the given stream which are copied
+    /// verbatim when decoding in the escaped mode. The run ends at the first DLE, STX or
+    /// terminator character
+    fn plain_run_len(&self, stream: &[u8]) -> usize {
+        #[cfg(feature = "memchr")]
+        if self.alt_terminator.is_none() {
+            return memchr::memchr3(DLE_CHAR, STX_CHAR, ETX_CHAR, stream).unwrap_or(stream.len());
+        }
+        stream
+            .iter()
+            .position(|&byte| self.is_terminator(byte) || byte == STX_CHAR || byte == DLE_CHAR)
+            .unwrap_or(stream.len())
+    }
+
+    /// Check whether a byte ends a frame when decoding
+    fn is_terminator(&self, byte: u8) -> bool {
+        byte == ETX_CHAR || self.alt_terminator == Some(byte)
+    }
+
+    /// Check whether a byte ends a frame in the escaped mode without a preceding DLE
+    fn is_bare_terminator(&self, byte: u8) -> bool {
+        self.escape_stx_etx && !self.etx_terminates_only_after_dle && self.is_terminator(byte)
+    }
+
+    /// Check whether a byte following a DLE character ends a frame, which is the case in the
+    /// non-escaped mode and in the escaped mode with `etx_terminates_only_after_dle`
+    fn is_dle_terminator(&self, byte: u8) -> bool {
+        (!self.escape_stx_etx || self.etx_terminates_only_after_dle) && self.is_terminator(byte)
+    }
+
+    /// Returns the set of characters other than DLE which are escaped in the escaped mode
+    fn escaped_bytes(&self) -> ByteSet {
+        let escaped_bytes = if self.etx_terminates_only_after_dle {
+            self.escape_extra.with(STX_CHAR).without(ETX_CHAR)
+        } else {
+            self.escape_extra.with(STX_CHAR).with(ETX_CHAR)
+        };
+        if self.escape_cr {
+            escaped_bytes.with(CR_CHAR)
+        } else {
+            escaped_bytes
+        }
+    }
+
+    /// Returns the configuration in the escaped mode which escapes the bytes selected by
+    /// the predicate instead of `escape_extra`
+    fn with_escape_predicate(&self, needs_escape: impl Fn(u8) -> bool) -> DleEncoder {
+        let mut escape_extra = ByteSet::new();
+        for byte in (0..=u8::MAX).filter(|&byte| needs_escape(byte)) {
+            escape_extra.insert(byte);
+        }
+        DleEncoder {
+            escape_stx_etx: true,
+            escape_extra,
+            ..*self
+        }
+    }
+
+    /// Returns the set of characters which the decoder accepts after a DLE character
+    fn accepted_after_dle(&self) -> ByteSet {
+        let mut accepted = ByteSet::new();
+        for byte in 0..=u8::MAX {
+            let control = if self.escape_stx_etx {
+                self.unescape(byte).is_some()
+            } else {
+                byte == STX_CHAR || self.is_terminator(byte)
+            };
+            if control || byte == DLE_CHAR || self.is_resync_marker(byte) {
+                accepted.insert(byte);
+            }
+        }
+        accepted
+    }
+
+    /// Check whether a byte other than DLE is escaped with DLE and its escaped form
+    /// in the escaped mode
+    fn needs_escape(&self, byte: u8) -> bool {
+        if byte == ETX_CHAR {
+            return !self.etx_terminates_only_after_dle;
+        }
+        byte == STX_CHAR || (self.escape_cr && byte == CR_CHAR) || self.escape_extra.contains(byte)
+    }
+
+    /// Check whether a byte inside the body of a frame is rejected because of
+    /// `strict_non_escaped`
+    pub(crate) fn is_raw_control(&self, byte: u8) -> bool {
+        self.strict_non_escaped && !self.escape_stx_etx && (byte == STX_CHAR || byte == ETX_CHAR)
+    }
+
+    /// Returns the character following a DLE character which escapes a DLE character of the
+    /// payload
+    fn escaped_dle(&self) -> u8 {
+        if self.escape_stx_etx && self.dle_escape_style == DleStyle::Offset {
+            self.escape_scheme.escape(DLE_CHAR)
+        } else {
+            DLE_CHAR
+        }
+    }
+
+    /// Returns the set of characters whose escape sequences are accepted by the decoder in
+    /// the escaped mode. Doubled DLE characters are always accepted in addition
+    fn valid_escapes(&self) -> ByteSet {
+        let mut valid_escapes = self.escape_extra.with(STX_CHAR).with(ETX_CHAR);
+        if self.escape_cr || !self.strict_escapes {
+            valid_escapes.insert(CR_CHAR);
+        }
+        if self.dle_escape_style == DleStyle::Offset {
+            valid_escapes.insert(DLE_CHAR);
+        }
+        valid_escapes
+    }
+
+    /// Returns the original character for the character following a DLE character in the
+    /// escaped mode, or [None] if the escape sequence is invalid
+    fn unescape(&self, escaped: u8) -> Option<u8> {
+        self.escape_scheme.unescape(escaped, &self.valid_escapes())
+    }
+}
+
+/// Write a byte into the destination stream at the given index. Returns
+/// [DleError::StreamTooShort] instead of panicking if the index is out of bounds
+fn write_byte(dest_stream: &mut [u8], idx: usize, byte: u8) -> Result<(), DleError> {
+    *dest_stream.get_mut(idx).ok_or(DleError::StreamTooShort)? = byte;
+    Ok(())
+}
+
+/// Copy bytes into the destination stream starting at the given index. Returns the index
+/// following the copied bytes, or [DleError::StreamTooShort] if they do not fit
+fn write_bytes(dest_stream: &mut [u8], idx: usize, bytes: &[u8]) -> Result<usize, DleError> {
+    let end = idx
+        .checked_add(bytes.len())
+        .ok_or(DleError::StreamTooShort)?;
+    dest_stream
+        .get_mut(idx..end)
+        .ok_or(DleError::StreamTooShort)?
+        .copy_from_slice(bytes);
+    Ok(end)
+}
+
+/// Returns the part of the destination stream starting at the given index, or
+/// [DleError::StreamTooShort] if the index is out of bounds
+fn dest_tail(dest_stream: &mut [u8], idx: usize) -> Result<&mut [u8], DleError> {
+    dest_stream.get_mut(idx..).ok_or(DleError::StreamTooShort)
+}
+
+/// Returns the part of the source stream starting at the given index, which is empty if
+/// the index is out of bounds
+fn source_tail(source_stream: &[u8], idx: usize) -> &[u8] {
+    source_stream.get(idx..).unwrap_or_default()
+}
+
+/// Iterator over the decoded chunks of a validated frame body, see
+/// [DleEncoder::decode_chunks]
+struct DecodeChunks<'a> {
+    encoder: DleEncoder,
+    /// Escaped bytes of the body which were not decoded yet
+    body: &'a [u8],
+}
+
+impl<'a> Iterator for DecodeChunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        loop {
+            let escaped = match self.body {
+                [] => return None,
+                [DLE_CHAR, escaped, rest @ ..] => {
+                    self.body = rest;
+                    escaped
+                }
+                _ => {
+                    let run_len = self
+                        .body
+                        .iter()
+                        .position(|&byte| byte == DLE_CHAR)
+                        .unwrap_or(self.body.len());
+                    let (run, rest) = self.body.split_at(run_len);
+                    self.body = rest;
+                    return Some(run);
+                }
+            };
+            if self.encoder.is_resync_marker(*escaped) {
+                continue;
+            }
+            if *escaped == DLE_CHAR || !self.encoder.escape_stx_etx {
+                return Some(core::slice::from_ref(escaped));
+            }
+            // The escape sequences were validated, so they can always be unescaped
+            let decoded = self.encoder.unescape(*escaped)?;
+            return BYTE_VALUES
+                .get(usize::from(decoded))
+                .map(core::slice::from_ref);
+        }
+    }
+
+    /// Each chunk consumes at least one byte of the body. Only resync markers are skipped
+    /// without yielding a chunk, so a body which does not start with one yields at least
+    /// one chunk
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let lower = match self.body {
+            [] => 0,
+            [DLE_CHAR, escaped, ..] if self.encoder.is_resync_marker(*escaped) => 0,
+            _ => 1,
+        };
+        (lower, Some(self.body.len()))
+    }
+}
+
+/// All byte values in ascending order, so a single decoded byte can be returned as a
+/// borrowed slice by [DleEncoder::decode_chunks]
+static BYTE_VALUES: [u8; 256] = byte_values();
+
+const fn byte_values() -> [u8; 256] {
+    let mut byte_values = [0; 256];
+    let mut idx = 0;
+    while idx < byte_values.len() {
+        byte_values[idx] = idx as u8;
+        idx += 1;
+    }
+    byte_values
+}
+
+/// Initial state of the 32 bit FNV-1a hash used by [DleEncoder::config_fingerprint]
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+
+/// Uppercase hexadecimal digits used by [DleEncoder::encode_hex]
+const HEX_DIGITS: [u8; 16] = *b"0123456789ABCDEF";
+
+/// Initial state of the CRC-16/CCITT-FALSE checksum
+const CRC16_INIT: u16 = 0xffff;
+
+/// Calculate the CRC-16/CCITT-FALSE checksum of the given bytes
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes
+        .iter()
+        .fold(CRC16_INIT, |crc, &byte| crc16_update(crc, byte))
+}
+
+/// Continue the CRC-16/CCITT-FALSE checksum with the given state over a single byte
+fn crc16_update(crc: u16, byte: u8) -> u16 {
+    (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+        if crc & 0x8000 != 0 {
+            (crc << 1) ^ 0x1021
+        } else {
+            crc << 1
+        }
+    })
+}
+
+/// Continue the 32 bit FNV-1a hash with the given state over the given bytes
+fn fnv1a(hash: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{assert_frame_eq, corrupt_escape, drop_etx, drop_stx, CORRUPTION_BYTE};
+
+    const TEST_ARRAY_0: [u8; 5] = [0, 0, 0, 0, 0];
+    const TEST_ARRAY_1: [u8; 3] = [0, DLE_CHAR, 5];
+    const TEST_ARRAY_2: [u8; 3] = [0, STX_CHAR, 5];
+    const TEST_ARRAY_3: [u8; 3] = [0, CR_CHAR, ETX_CHAR];
+    const TEST_ARRAY_4: [u8; 3] = [DLE_CHAR, ETX_CHAR, STX_CHAR];
+
+    const TEST_ARRAY_0_ENCODED_ESCPAED: &[u8] = &[STX_CHAR, 0, 0, 0, 0, 0, ETX_CHAR];
+    const TEST_ARRAY_0_ENCODED_NON_ESCPAED: &[u8] =
+        &[DLE_CHAR, STX_CHAR, 0, 0, 0, 0, 0, DLE_CHAR, ETX_CHAR];
+
+    const TEST_ARRAY_1_ENCODED_ESCPAED: [u8; 6] = [STX_CHAR, 0, DLE_CHAR, DLE_CHAR, 5, ETX_CHAR];
+    const TEST_ARRAY_1_ENCODED_NON_ESCPAED: [u8; 8] = [
+        DLE_CHAR, STX_CHAR, 0, DLE_CHAR, DLE_CHAR, 5, DLE_CHAR, ETX_CHAR,
+    ];
+
+    const TEST_ARRAY_2_ENCODED_ESCPAED: &[u8] =
+        &[STX_CHAR, 0, DLE_CHAR, STX_CHAR + 0x40, 5, ETX_CHAR];
+    const TEST_ARRAY_2_ENCODED_NON_ESCPAED: &[u8] =
+        &[DLE_CHAR, STX_CHAR, 0, STX_CHAR, 5, DLE_CHAR, ETX_CHAR];
+
+    const TEST_ARRAY_3_ENCODED_ESCPAED: &[u8] =
+        &[STX_CHAR, 0, CR_CHAR, DLE_CHAR, ETX_CHAR + 0x40, ETX_CHAR];
+    const TEST_ARRAY_3_ENCODED_NON_ESCPAED: &[u8] =
+        &[DLE_CHAR, STX_CHAR, 0, CR_CHAR, ETX_CHAR, DLE_CHAR, ETX_CHAR];
+
+    const TEST_ARRAY_4_ENCODED_ESCPAED: &[u8] = &[
+        STX_CHAR,
+        DLE_CHAR,
+        DLE_CHAR,
+        DLE_CHAR,
+        ETX_CHAR + 0x40,
+        DLE_CHAR,
+        STX_CHAR + 0x40,
+        ETX_CHAR,
+    ];
+    const TEST_ARRAY_4_ENCODED_NON_ESCPAED: [u8; 8] = [
+        DLE_CHAR, STX_CHAR, DLE_CHAR, DLE_CHAR, ETX_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR,
+    ];
+
+    #[test]
+    fn test_encoder() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut buffer: [u8; 32] = [0; 32];
+        let test_encode_closure = |dle_encoder: &DleEncoder,
+                                   buf_to_encode: &[u8],
+                                   expected_buf: &[u8],
+                                   buffer: &mut [u8]| {
+            let encode_res = dle_encoder.encode(buf_to_encode, buffer);
+            assert!(encode_res.is_ok());
+            assert_frame_eq(&buffer[..expected_buf.len()], expected_buf);
+            assert_eq!(encode_res.unwrap(), expected_buf.len());
+        };
+
+        let test_faulty_encoding = |dle_encoder: &DleEncoder,
+                                    buf_to_encode: &[u8],
+                                    expected_buf: &[u8],
+                                    buffer: &mut [u8]| {
+            for faulty_dest_size in 0..expected_buf.len() {
+                let encode_res =
+                    dle_encoder.encode(buf_to_encode, &mut buffer[0..faulty_dest_size]);
+                assert!(encode_res.is_err());
+                assert_eq!(encode_res.unwrap_err(), DleError::StreamTooShort);
+            }
+        };
+
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_0,
+            TEST_ARRAY_0_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_1_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_2,
+            TEST_ARRAY_2_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_3,
+            TEST_ARRAY_3_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_4,
+            TEST_ARRAY_4_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_0,
+            TEST_ARRAY_0_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_1_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_2,
+            TEST_ARRAY_2_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_3,
+            TEST_ARRAY_3_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_4,
+            TEST_ARRAY_4_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+
+        dle_encoder.escape_stx_etx = false;
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_0,
+            TEST_ARRAY_0_ENCODED_NON_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_1_ENCODED_NON_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_2,
+            TEST_ARRAY_2_ENCODED_NON_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_3,
+            TEST_ARRAY_3_ENCODED_NON_ESCPAED,
+            &mut buffer,
+        );
+        test_encode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_4,
+            &TEST_ARRAY_4_ENCODED_NON_ESCPAED,
+            &mut buffer,
+        );
+
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_0,
+            TEST_ARRAY_0_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_1_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_2,
+            TEST_ARRAY_2_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_3,
+            TEST_ARRAY_3_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+        test_faulty_encoding(
+            &dle_encoder,
+            &TEST_ARRAY_4,
+            TEST_ARRAY_4_ENCODED_ESCPAED,
+            &mut buffer,
+        );
+    }
+
+    #[test]
+    fn test_decoder() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut buffer: [u8; 32] = [0; 32];
+        let test_decode_closure = |dle_encoder: &DleEncoder,
+                                   encoded_test_vec: &[u8],
+                                   expected_buf: &[u8],
+                                   buffer: &mut [u8]| {
+            let mut read_len = 0;
+            let decode_res = dle_encoder.decode(encoded_test_vec, buffer, &mut read_len);
+            assert!(decode_res.is_ok());
+            assert_frame_eq(&buffer[..expected_buf.len()], expected_buf);
+            assert_eq!(read_len, encoded_test_vec.len());
+            assert_eq!(decode_res.unwrap(), expected_buf.len());
+        };
+
+        let test_faulty_decoding =
+            |dle_encoder: &DleEncoder, faulty_encoded_buf: &[u8], buffer: &mut [u8]| {
+                let mut read_len = 0;
+                let decode_res = dle_encoder.decode(faulty_encoded_buf, buffer, &mut read_len);
+                assert!(decode_res.is_err());
+                assert!(decode_res.unwrap_err().decode_kind().is_some());
+            };
+
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_0_ENCODED_ESCPAED,
+            &TEST_ARRAY_0,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_1_ENCODED_ESCPAED,
+            &TEST_ARRAY_1,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_2_ENCODED_ESCPAED,
+            &TEST_ARRAY_2,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_3_ENCODED_ESCPAED,
+            &TEST_ARRAY_3,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_4_ENCODED_ESCPAED,
+            &TEST_ARRAY_4,
+            &mut buffer,
+        );
+
+        dle_encoder.escape_stx_etx = false;
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_0_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_0,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_1_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_1,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_2_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_2,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            TEST_ARRAY_3_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_3,
+            &mut buffer,
+        );
+        test_decode_closure(
+            &dle_encoder,
+            &TEST_ARRAY_4_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_4,
+            &mut buffer,
+        );
+
+        let mut test_array_1_encoded_faulty = TEST_ARRAY_1_ENCODED_NON_ESCPAED;
+        let mut prev_val = test_array_1_encoded_faulty[0];
+        test_array_1_encoded_faulty[0] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[0] = prev_val;
+        prev_val = test_array_1_encoded_faulty[1];
+        test_array_1_encoded_faulty[1] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[1] = prev_val;
+        prev_val = test_array_1_encoded_faulty[6];
+        test_array_1_encoded_faulty[6] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[6] = prev_val;
+        test_array_1_encoded_faulty[7] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        let mut test_array_4_encoded_faulty = TEST_ARRAY_4_ENCODED_NON_ESCPAED;
+        test_array_4_encoded_faulty[3] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_4_encoded_faulty, &mut buffer);
+
+        dle_encoder.escape_stx_etx = true;
+        let mut test_array_1_encoded_faulty = TEST_ARRAY_1_ENCODED_ESCPAED;
+        prev_val = test_array_1_encoded_faulty[3];
+        test_array_1_encoded_faulty[3] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[3] = prev_val;
+        prev_val = test_array_1_encoded_faulty[0];
+        test_array_1_encoded_faulty[0] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[0] = prev_val;
+        prev_val = test_array_1_encoded_faulty[5];
+        test_array_1_encoded_faulty[5] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        test_array_1_encoded_faulty[5] = prev_val;
+        test_array_1_encoded_faulty[2] = 0;
+        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+
+        let mut decoding_buffer: [u8; 16] = [0; 16];
+        let encoded_array: [u8; 4] = [0x02, 0x10, 0x02 + 0x40, 0x03];
+        let mut read_len = 0;
+        let decode_result = dle_encoder.decode(&encoded_array, &mut decoding_buffer, &mut read_len);
+        assert!(decode_result.is_ok());
+        let decoded_len = decode_result.unwrap();
+        assert_eq!(decoded_len, 1);
+    }
+
+    #[test]
+    fn test_escape_extra() {
+        let mut dle_encoder = DleEncoder::default();
+        dle_encoder.escape_extra.insert(LF_CHAR);
+        let payload: [u8; 6] = [b'a', LF_CHAR, b'b', CR_CHAR, LF_CHAR, STX_CHAR];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[
+                STX_CHAR,
+                b'a',
+                DLE_CHAR,
+                LF_CHAR + 0x40,
+                b'b',
+                CR_CHAR,
+                DLE_CHAR,
+                LF_CHAR + 0x40,
+                DLE_CHAR,
+                STX_CHAR + 0x40,
+                ETX_CHAR
+            ]
+        );
+        assert!(!encoded[1..encoded_len - 1].contains(&LF_CHAR));
+
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_eq!(read_len, encoded_len);
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+
+        // An escaped LF is rejected if the decoder was not configured to escape it
+        let dle_encoder = DleEncoder::default();
+        let decode_res = dle_encoder.decode(&encoded[..encoded_len], &mut decoded, &mut read_len);
+        assert_eq!(
+            decode_res.unwrap_err().decode_kind(),
+            Some(DecodeErrorKind::InvalidDleSequence { found: 0x4a })
+        );
+    }
+
+    #[test]
+    fn test_decode_fast_path() {
+        let payloads: [&[u8]; 6] = [
+            &[],
+            &[0x55; 64],
+            &[CR_CHAR, 1, 2, 3],
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_2,
+            &TEST_ARRAY_4,
+        ];
+        let mut dle_encoder = DleEncoder::default();
+        let mut encoded: [u8; 160] = [0; 160];
+        let mut fast_buf: [u8; 80] = [0; 80];
+        let mut generic_buf: [u8; 80] = [0; 80];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            for payload in payloads.iter() {
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                // Also check undersized destinations
+                for dest_len in 0..=payload.len() + 1 {
+                    let source = &encoded[..encoded_len];
+                    let (mut fast_read_len, mut generic_read_len) = (0, 0);
+                    let (fast_res, generic_res) = if escape_stx_etx {
+                        (
+                            dle_encoder.decode_escaped(
+                                source,
+                                &mut fast_buf[..dest_len],
+                                &mut fast_read_len,
+                            ),
+                            dle_encoder.decode_escaped_generic(
+                                source,
+                                &mut generic_buf[..dest_len],
+                                &mut generic_read_len,
+                                false,
+                            ),
+                        )
+                    } else {
+                        (
+                            dle_encoder.decode_non_escaped(
+                                source,
+                                &mut fast_buf[..dest_len],
+                                &mut fast_read_len,
+                            ),
+                            dle_encoder.decode_non_escaped_generic(
+                                source,
+                                &mut generic_buf[..dest_len],
+                                &mut generic_read_len,
+                            ),
+                        )
+                    };
+                    assert_eq!(fast_res, generic_res);
+                    assert_eq!(fast_read_len, generic_read_len);
+                    if let Ok(decoded_len) = fast_res {
+                        assert_frame_eq(&fast_buf[..decoded_len], &generic_buf[..decoded_len]);
+                        assert_frame_eq(&fast_buf[..decoded_len], payload);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_frame_ranges() {
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &[], &TEST_ARRAY_4, &[DLE_CHAR, ETX_CHAR]];
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            // Frames are separated by noise and followed by an incomplete frame
+            let mut stream: Vec<u8> = vec![0x55, ETX_CHAR];
+            let mut expected_ranges = Vec::new();
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                expected_ranges.push(stream.len()..stream.len() + encoded_len);
+                stream.extend_from_slice(&encoded[..encoded_len]);
+                stream.push(0x55);
+            }
+            stream.extend_from_slice(&[DLE_CHAR, STX_CHAR, STX_CHAR, 0x01]);
+
+            let ranges = dle_encoder.frame_ranges(&stream);
+            assert_eq!(ranges, expected_ranges);
+            for (range, payload) in ranges.into_iter().zip(payloads.iter()) {
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(&stream[range.clone()], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(read_len, range.len());
+                assert_frame_eq(&decoded[..decoded_len], payload);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_error_kinds() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut buffer: [u8; 32] = [0; 32];
+        let mut check_error = |dle_encoder: &DleEncoder,
+                               faulty_encoded_buf: &[u8],
+                               expected_kind: DecodeErrorKind,
+                               expected_offset: usize| {
+            let mut read_len = 0;
+            let error = dle_encoder
+                .decode(faulty_encoded_buf, &mut buffer, &mut read_len)
+                .unwrap_err();
+            assert_eq!(error.decode_kind(), Some(expected_kind));
+            assert_eq!(error.offset(), Some(expected_offset));
+            assert_eq!(
+                error,
+                DleError::DecodingError {
+                    kind: expected_kind,
+                    offset: expected_offset
+                }
+            );
+        };
+
+        check_error(
+            &dle_encoder,
+            &[0, 1, ETX_CHAR],
+            DecodeErrorKind::MissingStart,
+            0,
+        );
+        check_error(
+            &dle_encoder,
+            &[STX_CHAR, 1, DLE_CHAR, 0x99, ETX_CHAR],
+            DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+            3,
+        );
+        check_error(
+            &dle_encoder,
+            &[STX_CHAR, 1, STX_CHAR, 2, ETX_CHAR],
+            DecodeErrorKind::UnexpectedStart,
+            2,
+        );
+        check_error(
+            &dle_encoder,
+            &[STX_CHAR, 0x11, 0x22, 0x33],
+            DecodeErrorKind::MissingEnd,
+            4,
+        );
+
+        dle_encoder.escape_stx_etx = false;
+        check_error(
+            &dle_encoder,
+            &[DLE_CHAR, 0, 1, DLE_CHAR, ETX_CHAR],
+            DecodeErrorKind::MissingStart,
+            1,
+        );
+        check_error(
+            &dle_encoder,
+            &[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, 0x99, DLE_CHAR, ETX_CHAR],
+            DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+            4,
+        );
+        check_error(
+            &dle_encoder,
+            &[
+                DLE_CHAR, STX_CHAR, 1, DLE_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR,
+            ],
+            DecodeErrorKind::UnexpectedStart,
+            3,
+        );
+        check_error(
+            &dle_encoder,
+            &[DLE_CHAR, STX_CHAR, 1, 2, DLE_CHAR],
+            DecodeErrorKind::MissingEnd,
+            5,
+        );
+
+        // Frames corrupted with the test utilities are rejected with the matching kinds
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut frame: [u8; 16] = [0; 16];
+            let frame_len = dle_encoder
+                .encode(&[0x01, DLE_CHAR, 0x02], &mut frame)
+                .unwrap();
+            let frame = &frame[..frame_len];
+            let mut corrupted: [u8; 16] = [0; 16];
+            let corrupted = &mut corrupted[..frame_len];
+
+            corrupted.copy_from_slice(frame);
+            let offset = corrupt_escape(corrupted, 0).unwrap();
+            let kind = DecodeErrorKind::InvalidDleSequence {
+                found: CORRUPTION_BYTE,
+            };
+            check_error(&dle_encoder, corrupted, kind, offset);
+            corrupted.copy_from_slice(frame);
+            assert!(drop_stx(corrupted));
+            check_error(&dle_encoder, corrupted, DecodeErrorKind::MissingStart, 0);
+            corrupted.copy_from_slice(frame);
+            assert!(drop_etx(corrupted));
+            check_error(
+                &dle_encoder,
+                corrupted,
+                DecodeErrorKind::MissingEnd,
+                frame_len,
+            );
+        }
+
+        let error = DleError::StreamTooShort;
+        assert_eq!(error.decode_kind(), None);
+        assert_eq!(error.offset(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_error_ordering() {
+        let decoding_error = |kind, offset| DleError::DecodingError { kind, offset };
+        let mut errors = vec![
+            decoding_error(DecodeErrorKind::MissingEnd, 4),
+            DleError::FrameTooLarge,
+            decoding_error(DecodeErrorKind::MissingStart, 7),
+            DleError::StreamTooShort,
+            decoding_error(DecodeErrorKind::MissingEnd, 2),
+            DleError::FrameTooLarge,
+            decoding_error(DecodeErrorKind::MissingStart, 0),
+            DleError::AmbiguousPayload,
+            decoding_error(DecodeErrorKind::MissingEnd, 4),
+        ];
+        errors.sort();
+        errors.dedup();
+        assert_eq!(
+            errors,
+            [
+                DleError::StreamTooShort,
+                DleError::FrameTooLarge,
+                DleError::AmbiguousPayload,
+                decoding_error(DecodeErrorKind::MissingStart, 0),
+                decoding_error(DecodeErrorKind::MissingStart, 7),
+                decoding_error(DecodeErrorKind::MissingEnd, 2),
+                decoding_error(DecodeErrorKind::MissingEnd, 4),
+            ]
+        );
+        assert!(DleError::StreamTooShort < decoding_error(DecodeErrorKind::MissingStart, 0));
+    }
+
+    #[test]
+    fn test_encode_fragments() {
+        let fragments: [&[u8]; 3] = [&[0, STX_CHAR], &[DLE_CHAR, 5, ETX_CHAR], &[CR_CHAR, 7]];
+        let payload: Vec<u8> = fragments.concat();
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let mut encoded_len = 0;
+            let roles = [
+                FragmentRole::First,
+                FragmentRole::Middle,
+                FragmentRole::Last,
+            ];
+            for (fragment, role) in fragments.iter().zip(roles.iter()) {
+                encoded_len += dle_encoder
+                    .encode_fragment(fragment, *role, &mut encoded[encoded_len..])
+                    .unwrap();
+            }
+
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&payload, &mut expected).unwrap();
+            assert_frame_eq(&encoded[..encoded_len], &expected[..expected_len]);
+            let whole_len = dle_encoder
+                .encode_fragment(&payload, FragmentRole::Whole, &mut expected)
+                .unwrap();
+            assert_frame_eq(&encoded[..encoded_len], &expected[..whole_len]);
+
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], payload.as_slice());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_reframe() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let escape_heavy = [DLE_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR, DLE_CHAR];
+            let payloads: [&[u8]; 5] = [
+                &[0, 0x55, 0xff, 0x41],
+                &[],
+                &TEST_ARRAY_1,
+                &TEST_ARRAY_4,
+                &escape_heavy,
+            ];
+            for payload in payloads {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                // Bytes after the frame are not part of the re-encoded frame
+                encoded[encoded_len] = 0x55;
+                let raw_frame = &encoded[..encoded_len + 1];
+
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(raw_frame, &mut decoded, &mut read_len)
+                    .unwrap();
+                let mut expected: [u8; 32] = [0; 32];
+                let expected_len = dle_encoder
+                    .encode(&decoded[..decoded_len], &mut expected)
+                    .unwrap();
+
+                let reframed = dle_encoder.reframe(raw_frame).unwrap();
+                assert_frame_eq(reframed.as_ref(), &expected[..expected_len]);
+                let has_escapes = payload.iter().any(|&byte| {
+                    byte == DLE_CHAR || (escape_stx_etx && dle_encoder.needs_escape(byte))
+                });
+                assert_eq!(matches!(reframed, Cow::Borrowed(_)), !has_escapes);
+            }
+
+            // The fast path does not apply to configurations which change the frame
+            dle_encoder.keep_terminator = true;
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&[0x55], &mut encoded).unwrap();
+            let reframed = dle_encoder.reframe(&encoded[..encoded_len]).unwrap();
+            assert!(matches!(reframed, Cow::Owned(_)));
+            assert!(reframed.len() > encoded_len);
+            dle_encoder.keep_terminator = false;
+
+            assert!(dle_encoder.reframe(&[0x55, 0x01]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_payload() {
+        let mut dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            add_stx_etx: false,
+            ..Default::default()
+        };
+        let mut encoded: [u8; 16] = [0; 16];
+        for payload in [
+            &[0x01, ETX_CHAR, 0x55][..],
+            &[STX_CHAR],
+            &[DLE_CHAR, ETX_CHAR],
+        ] {
+            assert_eq!(
+                dle_encoder.encode(payload, &mut encoded),
+                Err(DleError::AmbiguousPayload)
+            );
+        }
+        let encoded_len = dle_encoder
+            .encode(&[0x01, DLE_CHAR, 0x55], &mut encoded)
+            .unwrap();
+        assert_frame_eq(&encoded[..encoded_len], &[0x01, DLE_CHAR, DLE_CHAR, 0x55]);
+
+        // All encoders of complete frames reject ambiguous payloads
+        let payload = [0x01, ETX_CHAR, 0x55];
+        let ambiguous = Err(DleError::AmbiguousPayload);
+        assert_eq!(dle_encoder.encode_one(STX_CHAR, &mut encoded), ambiguous);
+        assert_eq!(
+            dle_encoder.encode_ranged(&payload, 0..payload.len(), &mut encoded),
+            ambiguous
+        );
+        let (first, second) = encoded.split_at_mut(2);
+        assert_eq!(
+            dle_encoder.encode_scatter(&payload, first, second),
+            ambiguous
+        );
+        assert_eq!(
+            dle_encoder.encode_typed(ETX_CHAR, &[], &mut encoded),
+            ambiguous
+        );
+        assert_eq!(
+            dle_encoder.encode_reserve(&payload, 1, &mut encoded),
+            ambiguous
+        );
+        assert_eq!(
+            dle_encoder.encode_sparse(&[(0x0203, 0x55)], &mut encoded),
+            ambiguous
+        );
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            dle_encoder.encode_cow(&payload),
+            Err(DleError::AmbiguousPayload)
+        );
+
+        // Framing characters or escaping make ETX in the payload unambiguous
+        let payload = [0x01, ETX_CHAR, 0x55];
+        dle_encoder.add_stx_etx = true;
+        assert!(dle_encoder.encode(&payload, &mut encoded).is_ok());
+        dle_encoder.add_stx_etx = false;
+        dle_encoder.escape_stx_etx = true;
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        assert_frame_eq(
+            &encoded[..encoded_len],
+            &[0x01, DLE_CHAR, ETX_CHAR + 0x40, 0x55],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encode_cow() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            dle_encoder.add_stx_etx = false;
+            let plain: [u8; 4] = [0, 0x55, 0xff, 0x41];
+            let encoded = dle_encoder.encode_cow(&plain).unwrap();
+            assert!(matches!(encoded, Cow::Borrowed(_)));
+            assert_frame_eq(encoded.as_ref(), &plain);
+
+            // Framing always requires a copy
+            dle_encoder.add_stx_etx = true;
+            let encoded = dle_encoder.encode_cow(&plain).unwrap();
+            assert!(matches!(encoded, Cow::Owned(_)));
+            let mut expected: [u8; 16] = [0; 16];
+            let expected_len = dle_encoder.encode(&plain, &mut expected).unwrap();
+            assert_frame_eq(encoded.as_ref(), &expected[..expected_len]);
+
+            // Bytes which need to be escaped require a copy
+            dle_encoder.add_stx_etx = false;
+            for payload in [&TEST_ARRAY_1, &TEST_ARRAY_4] {
+                // Ambiguous payloads are rejected like by encode
+                let expected_len = match dle_encoder.encode(payload, &mut expected) {
+                    Ok(expected_len) => expected_len,
+                    Err(error) => {
+                        assert_eq!(dle_encoder.encode_cow(payload), Err(error));
+                        continue;
+                    }
+                };
+                let encoded = dle_encoder.encode_cow(payload).unwrap();
+                assert!(matches!(encoded, Cow::Owned(_)));
+                assert_frame_eq(encoded.as_ref(), &expected[..expected_len]);
+            }
+        }
+
+        // STX and ETX are escaped in the escaped mode and ambiguous in the non-escaped mode
+        dle_encoder.escape_stx_etx = false;
+        assert_eq!(
+            dle_encoder.encode_cow(&TEST_ARRAY_2),
+            Err(DleError::AmbiguousPayload)
+        );
+        dle_encoder.escape_stx_etx = true;
+        assert!(matches!(
+            dle_encoder.encode_cow(&TEST_ARRAY_2),
+            Ok(Cow::Owned(_))
+        ));
+
+        // Encoding errors are returned instead of an empty stream
+        dle_encoder.pad_to = Some((3, 0));
+        assert_eq!(
+            dle_encoder.encode_cow(&[0x01, 0x02, 0x03, 0x04]),
+            Err(DleError::FrameTooLarge)
+        );
+        dle_encoder.pad_to = Some((30, 0));
+        let encoded = dle_encoder.encode_cow(&[0x01, 0x02, 0x03, 0x04]).unwrap();
+        assert_eq!(encoded.len(), 30);
+    }
+
+    #[test]
+    fn test_keep_terminator() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            for payload in [&TEST_ARRAY_0[..], &TEST_ARRAY_4[..], &[]] {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+
+                dle_encoder.keep_terminator = false;
+                let decoded_len = dle_encoder
+                    .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(decoded_len, payload.len());
+
+                dle_encoder.keep_terminator = true;
+                let decoded_len_with_etx = dle_encoder
+                    .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(read_len, encoded_len);
+                assert_eq!(decoded_len_with_etx, decoded_len + 1);
+                assert_frame_eq(&decoded[..decoded_len], payload);
+                assert_eq!(decoded[decoded_len], ETX_CHAR);
+            }
+        }
+
+        // The terminator requires space in the destination
+        dle_encoder.escape_stx_etx = true;
+        let mut decoded: [u8; 5] = [0; 5];
+        let mut read_len = 0;
+        assert_eq!(
+            dle_encoder.decode(TEST_ARRAY_0_ENCODED_ESCPAED, &mut decoded, &mut read_len),
+            Err(DleError::StreamTooShort)
+        );
+        assert_eq!(read_len, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "heapless")]
+    fn test_heapless() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let encoded = dle_encoder.encode_heapless::<32>(&TEST_ARRAY_4).unwrap();
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&TEST_ARRAY_4, &mut expected).unwrap();
+            assert_frame_eq(encoded.as_slice(), &expected[..expected_len]);
+
+            let mut read_len = 0;
+            let decoded = dle_encoder
+                .decode_heapless::<8>(&encoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(decoded.as_slice(), &TEST_ARRAY_4);
+            assert_eq!(read_len, encoded.len());
+
+            assert_eq!(
+                dle_encoder.encode_heapless::<4>(&TEST_ARRAY_4),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(
+                dle_encoder.decode_heapless::<2>(&encoded, &mut read_len),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_array() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let encoded = &encoded[..encoded_len];
+
+            let (decoded, decoded_len) = dle_encoder.decode_array::<8>(encoded).unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+            assert!(decoded[decoded_len..].iter().all(|&byte| byte == 0));
+            let (decoded, decoded_len) = dle_encoder
+                .decode_array::<{ TEST_ARRAY_4.len() }>(encoded)
+                .unwrap();
+            assert_eq!(decoded_len, TEST_ARRAY_4.len());
+            assert_frame_eq(&decoded, &TEST_ARRAY_4);
+
+            assert_eq!(
+                dle_encoder.decode_array::<2>(encoded),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(
+                dle_encoder.decode_array::<8>(&encoded[..encoded_len - 1]),
+                Err(DleError::decoding(
+                    DecodeErrorKind::MissingEnd,
+                    encoded_len - 1
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_bounded() {
+        let mut dle_encoder = DleEncoder::default();
+        let payload = [0x55, DLE_CHAR, STX_CHAR, 0x55];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let encoded = &encoded[..encoded_len];
+
+            let result = dle_encoder.decode_bounded::<8>(encoded).unwrap();
+            assert_eq!(result.payload(), &payload);
+            assert_eq!(&result.buffer[4..], &[0; 4]);
+            assert!(!result.spilled);
+
+            let result = dle_encoder.decode_bounded::<4>(encoded).unwrap();
+            assert_eq!(result.payload(), &payload);
+            assert!(!result.spilled);
+
+            // Truncated in the middle of the payload and directly after an escape sequence
+            let result = dle_encoder.decode_bounded::<3>(encoded).unwrap();
+            assert_eq!(result.payload(), &payload[..3]);
+            assert!(result.spilled);
+            let result = dle_encoder.decode_bounded::<2>(encoded).unwrap();
+            assert_eq!(result.payload(), &payload[..2]);
+            assert!(result.spilled);
+            let result = dle_encoder.decode_bounded::<0>(encoded).unwrap();
+            assert_eq!(result.payload(), &[]);
+            assert!(result.spilled);
+
+            let result = dle_encoder.decode_bounded::<4>(&encoded[..encoded_len - 1]);
+            assert_eq!(
+                result.unwrap_err().decode_kind(),
+                Some(DecodeErrorKind::MissingEnd)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_partial() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut payload = [0x55; 40];
+        for (idx, byte) in payload.iter_mut().enumerate().step_by(3) {
+            *byte = [DLE_CHAR, STX_CHAR, ETX_CHAR][idx % 3];
+        }
+        for (escape_stx_etx, keep_terminator) in [(true, false), (false, false), (true, true)] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            dle_encoder.keep_terminator = keep_terminator;
+            let mut encoded: [u8; 128] = [0; 128];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let encoded = &encoded[..encoded_len];
+            let mut expected = payload.to_vec();
+            if keep_terminator {
+                expected.push(ETX_CHAR);
+            }
+
+            // A large frame decoded into two small destinations
+            let mut first: [u8; 24] = [0; 24];
+            let progress = dle_encoder.decode_partial(encoded, &mut first).unwrap();
+            assert_eq!(progress.decoded_len, 24);
+            assert!(!progress.complete);
+            let mut second: [u8; 24] = [0; 24];
+            let resumed = dle_encoder
+                .resume_partial(encoded, &progress, &mut second)
+                .unwrap();
+            assert!(resumed.complete);
+            assert_eq!(resumed.read_len, encoded_len);
+            let mut decoded = first.to_vec();
+            decoded.extend_from_slice(&second[..resumed.decoded_len]);
+            assert_eq!(decoded, expected);
+
+            // The decoded prefixes are complete for any destination length, so no escape
+            // sequence was split
+            for dest_len in 1..8 {
+                let mut dest = [0; 8];
+                let mut decoded = Vec::new();
+                let mut progress = dle_encoder.decode_partial(encoded, &mut dest[..dest_len]);
+                loop {
+                    let partial = progress.unwrap();
+                    decoded.extend_from_slice(&dest[..partial.decoded_len]);
+                    if partial.complete {
+                        assert_eq!(partial.read_len, encoded_len);
+                        break;
+                    }
+                    progress = dle_encoder.resume_partial(encoded, &partial, &mut dest[..dest_len]);
+                }
+                assert_eq!(decoded, expected);
+            }
+
+            // A complete frame is not decoded again
+            let mut dest = [0; 64];
+            let progress = dle_encoder.decode_partial(encoded, &mut dest).unwrap();
+            assert!(progress.complete);
+            assert_eq!(progress.decoded_len, expected.len());
+            let resumed = dle_encoder
+                .resume_partial(encoded, &progress, &mut dest)
+                .unwrap();
+            assert_eq!(resumed.decoded_len, 0);
+            assert!(resumed.complete);
+
+            // Erroneous frames are rejected before any byte is written
+            let error = dle_encoder
+                .decode_partial(&encoded[..encoded_len - 1], &mut dest)
+                .unwrap_err();
+            assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingEnd));
+        }
+    }
+
+    #[test]
+    fn test_strip_framing() {
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &TEST_ARRAY_3, &TEST_ARRAY_4, &[]];
+        for dle_encoder in [DleEncoder::default(), DleEncoder::non_escaped()] {
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                encoded[encoded_len] = 0x55;
+                let body = dle_encoder
+                    .strip_framing(&encoded[..encoded_len + 1])
+                    .unwrap();
+                let marker_len = dle_encoder.start_marker().len();
+                assert_eq!(body, &encoded[marker_len..encoded_len - marker_len]);
+
+                // The body is forwarded and framed again by the receiver
+                let mut forwarded: [u8; 32] = [0; 32];
+                let mut forwarded_len =
+                    write_bytes(&mut forwarded, 0, dle_encoder.start_marker()).unwrap();
+                forwarded_len = write_bytes(&mut forwarded, forwarded_len, body).unwrap();
+                forwarded_len =
+                    write_bytes(&mut forwarded, forwarded_len, dle_encoder.end_marker()).unwrap();
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(&forwarded[..forwarded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], payload);
+            }
+        }
+
+        // Idle sequences and padding are skipped
+        let dle_encoder = DleEncoder {
+            pad_to: Some((8, 0x00)),
+            idle_on_empty: Some(&[DLE_CHAR, SYN_CHAR]),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 10] = [DLE_CHAR, SYN_CHAR, 0, 0, 0, 0, 0, 0, 0, 0];
+        dle_encoder.encode(&[DLE_CHAR], &mut encoded[2..]).unwrap();
+        assert_eq!(
+            dle_encoder.strip_framing(&encoded),
+            Ok(&[DLE_CHAR, DLE_CHAR][..])
+        );
+
+        // The framing and the escape sequences are validated
+        let dle_encoder = DleEncoder::default();
+        assert_eq!(
+            dle_encoder.strip_framing(&[0x55, STX_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 0))
+        );
+        assert_eq!(
+            dle_encoder.strip_framing(&[STX_CHAR, 0x55]),
+            Err(DleError::decoding(DecodeErrorKind::MissingEnd, 2))
+        );
+        assert_eq!(
+            dle_encoder.strip_framing(&[STX_CHAR, DLE_CHAR, 0x99, ETX_CHAR]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_split_first_frame() {
+        let payloads: [&[u8]; 3] = [&TEST_ARRAY_1, &TEST_ARRAY_4, &[]];
+        for dle_encoder in [DleEncoder::default(), DleEncoder::non_escaped()] {
+            let mut stream: [u8; 64] = [0; 64];
+            let mut frame_ends = [0; 3];
+            let mut stream_len = 0;
+            for (payload, frame_end) in payloads.iter().zip(frame_ends.iter_mut()) {
+                stream_len += dle_encoder
+                    .encode(payload, &mut stream[stream_len..])
+                    .unwrap();
+                *frame_end = stream_len;
+            }
+
+            // The frames of a multi-frame buffer are split off one by one
+            let mut rest = &stream[..stream_len];
+            let mut frame_start = 0;
+            for (payload, &frame_end) in payloads.iter().zip(frame_ends.iter()) {
+                let (frame, next_rest) = dle_encoder.split_first_frame(rest).unwrap();
+                assert_eq!(frame, &stream[frame_start..frame_end]);
+                assert_eq!(next_rest, &stream[frame_end..stream_len]);
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(frame, &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], payload);
+                assert_eq!(read_len, frame.len());
+                rest = next_rest;
+                frame_start = frame_end;
+            }
+            assert_eq!(
+                dle_encoder.split_first_frame(rest),
+                Err(DleError::SourceIncomplete)
+            );
+
+            // Every truncated frame is incomplete
+            for truncated_len in frame_ends[0] + 1..frame_ends[1] {
+                let (_, rest) = dle_encoder
+                    .split_first_frame(&stream[..truncated_len])
+                    .unwrap();
+                assert_eq!(
+                    dle_encoder.split_first_frame(rest),
+                    Err(DleError::SourceIncomplete)
+                );
+            }
+        }
+
+        // Idle sequences and padding are part of the frame
+        let dle_encoder = DleEncoder {
+            pad_to: Some((8, 0x00)),
+            idle_on_empty: Some(&[DLE_CHAR, SYN_CHAR]),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 11] = [DLE_CHAR, SYN_CHAR, 0, 0, 0, 0, 0, 0, 0, 0, 0x55];
+        dle_encoder.encode(&[DLE_CHAR], &mut encoded[2..]).unwrap();
+        assert_eq!(
+            dle_encoder.split_first_frame(&encoded),
+            Ok((&encoded[..10], &[0x55][..]))
+        );
+        assert_eq!(
+            dle_encoder.split_first_frame(&encoded[..2]),
+            Err(DleError::SourceIncomplete)
+        );
+
+        // Malformed frames are reported like the decoder reports them
+        let dle_encoder = DleEncoder::default();
+        assert_eq!(
+            dle_encoder.split_first_frame(&[0x55, STX_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 0))
+        );
+        assert_eq!(
+            dle_encoder.split_first_frame(&[STX_CHAR, DLE_CHAR, 0x99, ETX_CHAR]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_split() {
+        let payload = [0x01, DLE_CHAR, STX_CHAR, 0x55, ETX_CHAR];
+        for dle_encoder in [DleEncoder::default(), DleEncoder::non_escaped()] {
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            encoded[encoded_len] = 0x55;
+            let mut read_len = 0;
+
+            // Frames longer than, as long as and shorter than the header
+            for header_len in [2, payload.len(), payload.len() + 2] {
+                let mut header: [u8; 8] = [0; 8];
+                let mut rest: [u8; 8] = [0; 8];
+                let (split_header_len, rest_len) = dle_encoder
+                    .decode_split(
+                        &encoded[..encoded_len + 1],
+                        &mut header[..header_len],
+                        &mut rest,
+                        &mut read_len,
+                    )
+                    .unwrap();
+                assert_eq!(read_len, encoded_len);
+                assert_eq!(split_header_len, header_len.min(payload.len()));
+                assert_eq!(rest_len, payload.len().saturating_sub(header_len));
+                assert_frame_eq(&header[..split_header_len], &payload[..split_header_len]);
+                assert_frame_eq(&rest[..rest_len], &payload[split_header_len..]);
+            }
+
+            let mut header: [u8; 2] = [0; 2];
+            let mut rest: [u8; 2] = [0; 2];
+            read_len = usize::MAX;
+            assert_eq!(
+                dle_encoder.decode_split(
+                    &encoded[..encoded_len],
+                    &mut header,
+                    &mut rest,
+                    &mut read_len
+                ),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(read_len, 0);
+
+            // Errors assign the same read length as decode for every corrupted byte
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut decode_read_len = 0;
+            let mut header: [u8; 2] = [0; 2];
+            let mut rest: [u8; 16] = [0; 16];
+            for idx in 0..encoded_len {
+                for corrupted_byte in [STX_CHAR, ETX_CHAR, DLE_CHAR, 0x55] {
+                    let mut corrupted = encoded;
+                    corrupted[idx] = corrupted_byte;
+                    for corrupted in [&corrupted[..encoded_len], &corrupted[..idx]] {
+                        let expected =
+                            dle_encoder.decode(corrupted, &mut decoded, &mut decode_read_len);
+                        let result = dle_encoder.decode_split(
+                            corrupted,
+                            &mut header,
+                            &mut rest,
+                            &mut read_len,
+                        );
+                        assert_eq!(
+                            result.map(|(header_len, rest_len)| header_len + rest_len),
+                            expected
+                        );
+                        assert_eq!(read_len, decode_read_len);
+                    }
+                }
+            }
+        }
+
+        // The terminator is part of the payload and padding is part of the read length
+        let dle_encoder = DleEncoder {
+            keep_terminator: true,
+            pad_to: Some((8, 0)),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 8] = [0; 8];
+        dle_encoder.encode(&[0x01, 0x02], &mut encoded).unwrap();
+        let mut header: [u8; 1] = [0; 1];
+        let mut rest: [u8; 4] = [0; 4];
+        let mut read_len = 0;
+        assert_eq!(
+            dle_encoder.decode_split(&encoded, &mut header, &mut rest, &mut read_len),
+            Ok((1, 2))
+        );
+        assert_eq!(read_len, 8);
+        assert_eq!(&rest[..2], &[0x02, ETX_CHAR]);
+    }
+
+    #[test]
+    fn test_decode_escaped_body() {
+        let dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 3] = [&TEST_ARRAY_1, &TEST_ARRAY_4, &[]];
+        for payload in payloads.iter() {
+            let mut body: [u8; 32] = [0; 32];
+            let body_len = dle_encoder
+                .encode_fragment(payload, FragmentRole::Last, &mut body)
+                .unwrap();
+            let mut decoded: [u8; 32] = [0; 32];
+            let decoded_len = dle_encoder
+                .decode_escaped_body(&body[..body_len], &mut decoded)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], payload);
+
+            // Headerless bodies are also decoded with an encoder using the non-escaped mode
+            let mut body_len = DleEncoder::raw_escaped_no_framing()
+                .encode(payload, &mut body)
+                .unwrap();
+            body[body_len] = ETX_CHAR;
+            body_len += 1;
+            let decoded_len = DleEncoder::non_escaped()
+                .decode_escaped_body(&body[..body_len], &mut decoded)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], payload);
+        }
+
+        let mut decoded: [u8; 4] = [0; 4];
+        let errors = [
+            (&[0x01, 0x55][..], DecodeErrorKind::MissingEnd, 2),
+            (
+                &[0x01, STX_CHAR, ETX_CHAR][..],
+                DecodeErrorKind::UnexpectedStart,
+                1,
+            ),
+            (
+                &[DLE_CHAR, 0x01, ETX_CHAR][..],
+                DecodeErrorKind::InvalidDleSequence { found: 0x01 },
+                1,
+            ),
+        ];
+        for (body, kind, offset) in errors.iter() {
+            assert_eq!(
+                dle_encoder.decode_escaped_body(body, &mut decoded),
+                Err(DleError::decoding(*kind, *offset))
+            );
+        }
+        assert_eq!(
+            dle_encoder.decode_escaped_body(&[0x01; 5], &mut decoded),
+            Err(DleError::StreamTooShort)
+        );
+    }
+
+    #[test]
+    fn test_typed_message() {
+        let mut dle_encoder = DleEncoder::default();
+        // The length 0x0210 is encoded as STX and DLE in the header
+        let mut long_payload = [0x55; 0x210];
+        long_payload[..TEST_ARRAY_4.len()].copy_from_slice(&TEST_ARRAY_4);
+        let messages: [(u8, &[u8]); 4] = [
+            (0x01, &[]),
+            (DLE_CHAR, &TEST_ARRAY_4),
+            (ETX_CHAR, &TEST_ARRAY_1),
+            (STX_CHAR, &long_payload),
+        ];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            for (msg_type, payload) in messages.iter() {
+                let mut encoded = [0; 2 * 0x210 + 16];
+                let encoded_len = dle_encoder
+                    .encode_typed(*msg_type, payload, &mut encoded)
+                    .unwrap();
+                let mut decoded = [0; 0x210 + 3];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(decoded_len, payload.len() + 3);
+                assert_eq!(decoded[0], *msg_type);
+
+                let (decoded_type, decoded_payload) = dle_encoder
+                    .decode_typed(&encoded[..encoded_len], &mut decoded)
+                    .unwrap();
+                assert_eq!(decoded_type, *msg_type);
+                assert_frame_eq(decoded_payload, payload);
+            }
+        }
+
+        dle_encoder.escape_stx_etx = true;
+        let mut encoded: [u8; 16] = [0; 16];
+        let mut decoded: [u8; 16] = [0; 16];
+        let invalid_messages: [&[u8]; 3] = [
+            &[0x01, 0x00],
+            &[0x01, 0x00, 0x02, 0x55],
+            &[0x01, 0x00, 0x00, 0x55],
+        ];
+        for message in invalid_messages.iter() {
+            let encoded_len = dle_encoder.encode(message, &mut encoded).unwrap();
+            assert_eq!(
+                dle_encoder.decode_typed(&encoded[..encoded_len], &mut decoded),
+                Err(DleError::InvalidHeader)
+            );
+        }
+        assert_eq!(
+            dle_encoder.encode_typed(0x01, &[0; 0x10000], &mut encoded),
+            Err(DleError::FrameTooLarge)
+        );
+        assert_eq!(
+            dle_encoder.encode_typed(0x01, &[0x55], &mut encoded[..4]),
+            Err(DleError::StreamTooShort)
+        );
+    }
+
+    #[test]
+    fn test_sparse_updates() {
+        let updates = [
+            (0x0000, DLE_CHAR),
+            (0x0210, STX_CHAR),
+            (0x1003, ETX_CHAR),
+            (0xffff, 0x55),
+        ];
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode_sparse(&updates, &mut encoded).unwrap();
+            let mut decoded: [u8; 16] = [0; 16];
+            let decoded_updates = dle_encoder
+                .decode_sparse(&encoded[..encoded_len], &mut decoded)
+                .unwrap();
+            assert!(decoded_updates.eq(updates.iter().copied()));
+
+            // The offsets and values are escaped like a plain payload
+            let mut serialized: [u8; 12] = [0; 12];
+            for (idx, &(offset, value)) in updates.iter().enumerate() {
+                serialized[idx * 3..idx * 3 + 2].copy_from_slice(&offset.to_be_bytes());
+                serialized[idx * 3 + 2] = value;
+            }
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&serialized, &mut expected).unwrap();
+            assert_frame_eq(&encoded[..encoded_len], &expected[..expected_len]);
+
+            let empty_len = dle_encoder.encode_sparse(&[], &mut encoded).unwrap();
+            let decoded_updates = dle_encoder.decode_sparse(&encoded[..empty_len], &mut decoded);
+            assert_eq!(decoded_updates.unwrap().count(), 0);
+
+            // A truncated update
+            let encoded_len = dle_encoder.encode(&[0x00, 0x01], &mut encoded).unwrap();
+            assert!(matches!(
+                dle_encoder.decode_sparse(&encoded[..encoded_len], &mut decoded),
+                Err(DleError::InvalidHeader)
+            ));
+            assert_eq!(
+                dle_encoder.encode_sparse(&updates, &mut encoded[..8]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
+
+    #[test]
+    fn test_framed_len() {
+        let mut dle_encoder = DleEncoder::default();
+        let payload = [0x55, DLE_CHAR, STX_CHAR, ETX_CHAR, 0x55];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder
+                .encode_framed_len(&payload, &mut encoded)
+                .unwrap();
+            // The length field holds the length of the encoded body
+            let mut body: [u8; 32] = [0; 32];
+            let body_len = dle_encoder
+                .encode_fragment(&payload, FragmentRole::Middle, &mut body)
+                .unwrap();
+            let start_len = dle_encoder.start_marker().len();
+            assert_eq!(
+                &encoded[start_len..start_len + 2],
+                &(body_len as u16).to_be_bytes()
+            );
+            assert_frame_eq(
+                &encoded[start_len + 2..encoded_len - start_len],
+                &body[..body_len],
+            );
+
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            encoded[encoded_len] = 0x77;
+            let decoded_len = dle_encoder
+                .decode_framed_len(&encoded[..encoded_len + 1], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+            assert_eq!(read_len, encoded_len);
+
+            assert_eq!(
+                dle_encoder.decode_framed_len(
+                    &encoded[..encoded_len - 1],
+                    &mut decoded,
+                    &mut read_len
+                ),
+                Err(DleError::SourceIncomplete)
+            );
+            // A length which does not match the position of the end marker
+            encoded[start_len + 1] -= 1;
+            assert_eq!(
+                dle_encoder.decode_framed_len(&encoded[..encoded_len], &mut decoded, &mut read_len),
+                Err(DleError::InvalidHeader)
+            );
+            encoded[start_len + 1] += 2;
+            assert_eq!(
+                dle_encoder.decode_framed_len(
+                    &encoded[..encoded_len + 1],
+                    &mut decoded,
+                    &mut read_len
+                ),
+                Err(DleError::InvalidHeader)
+            );
+            assert_eq!(read_len, 0);
+        }
+    }
+
+    #[test]
+    fn test_crc_after_frame() {
+        // Check value of the CRC-16/CCITT-FALSE algorithm
+        assert_eq!(crc16(b"123456789"), 0x29b1);
+
+        let payload: [u8; 5] = [STX_CHAR, 0x55, DLE_CHAR, ETX_CHAR, 0x55];
+        for dle_encoder in [DleEncoder::escaped(), DleEncoder::non_escaped()] {
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode_with_crc(&payload, &mut encoded).unwrap();
+            let frame_len = dle_encoder.encode(&payload, &mut [0; 32]).unwrap();
+            assert_eq!(encoded_len, frame_len + 2);
+            let crc = crc16(&encoded[..frame_len]);
+            assert_eq!(&encoded[frame_len..encoded_len], &crc.to_be_bytes());
+
+            // Bytes following the CRC are not read
+            encoded[encoded_len] = 0x55;
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode_with_crc(&encoded[..encoded_len + 1], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_eq!(read_len, encoded_len);
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+
+            let error = dle_encoder
+                .decode_with_crc(&encoded[..encoded_len - 1], &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(error, DleError::SourceIncomplete);
+            assert_eq!(read_len, 0);
+
+            // A corrupted payload byte still forms a valid frame, only the CRC catches it
+            let mut corrupted = encoded;
+            let corrupted_idx = corrupted[..frame_len]
+                .iter()
+                .position(|&byte| byte == 0x55)
+                .unwrap();
+            corrupted[corrupted_idx] = 0x54;
+            assert!(dle_encoder
+                .decode(&corrupted[..frame_len], &mut decoded, &mut read_len)
+                .is_ok());
+            let error = dle_encoder
+                .decode_with_crc(&corrupted[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(error, DleError::CrcMismatch);
+
+            // A corrupted CRC is detected as well
+            let mut corrupted = encoded;
+            corrupted[encoded_len - 1] ^= 0x01;
+            let error = dle_encoder
+                .decode_with_crc(&corrupted[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(error, DleError::CrcMismatch);
+        }
+
+        // The CRC covers the padding and the frame is always framed
+        let dle_encoder = DleEncoder {
+            add_stx_etx: false,
+            pad_to: Some((8, 0)),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder.encode_with_crc(&[0x55], &mut encoded).unwrap();
+        assert_eq!(encoded_len, 10);
+        assert_eq!(&encoded[..4], &[STX_CHAR, 0x55, ETX_CHAR, 0]);
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode_with_crc(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_eq!(read_len, encoded_len);
+        assert_frame_eq(&decoded[..decoded_len], &[0x55]);
+    }
+
+    #[test]
+    fn test_crc_single_pass() {
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &TEST_ARRAY_4, &[], b"123456789"];
+        for dle_encoder in [DleEncoder::escaped(), DleEncoder::non_escaped()] {
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder
+                    .encode_crc_single_pass(payload, &mut encoded)
+                    .unwrap();
+
+                // Same frame as calculating the CRC first and encoding the payload with it
+                let crc = crc16(payload);
+                let mut expected: [u8; 32] = [0; 32];
+                let first_len = dle_encoder
+                    .encode_fragment(payload, FragmentRole::First, &mut expected)
+                    .unwrap();
+                let last_len = dle_encoder
+                    .encode_fragment(
+                        &crc.to_be_bytes(),
+                        FragmentRole::Last,
+                        &mut expected[first_len..],
+                    )
+                    .unwrap();
+                assert_eq!(&encoded[..encoded_len], &expected[..first_len + last_len]);
+
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(read_len, encoded_len);
+                assert_eq!(decoded_len, payload.len() + 2);
+                assert_frame_eq(&decoded[..payload.len()], payload);
+                assert_eq!(&decoded[payload.len()..decoded_len], &crc.to_be_bytes());
+                assert_eq!(crc16(&decoded[..decoded_len]), 0);
+
+                // A corrupted payload byte is detected by the CRC
+                if !payload.is_empty() {
+                    decoded[0] ^= 0x01;
+                    assert_ne!(crc16(&decoded[..decoded_len]), 0);
+                }
+                assert_eq!(
+                    dle_encoder.encode_crc_single_pass(payload, &mut encoded[..encoded_len - 1]),
+                    Err(DleError::StreamTooShort)
+                );
+            }
+        }
+
+        // The CRC is escaped like the payload
+        let mut encoded: [u8; 16] = [0; 16];
+        assert_eq!(crc16(&[0x1f]), 0x022e);
+        let encoded_len = DleEncoder::default()
+            .encode_crc_single_pass(&[0x1f], &mut encoded)
+            .unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[STX_CHAR, 0x1f, DLE_CHAR, 0x42, 0x2e, ETX_CHAR]
+        );
+    }
+
+    #[test]
+    fn test_encode_reserve() {
+        let payload = [0x01, DLE_CHAR, STX_CHAR, 0x55, ETX_CHAR];
+        let checksum = payload
+            .iter()
+            .fold(0x20u8, |sum, &byte| sum.wrapping_add(byte));
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0xff; 32];
+            let slot_offset = dle_encoder
+                .encode_reserve(&payload, 2, &mut encoded)
+                .unwrap();
+            // The reserved slot is not written
+            assert_eq!(&encoded[slot_offset..slot_offset + 2], &[0xff, 0xff]);
+            encoded[slot_offset] = checksum;
+            encoded[slot_offset + 1] = !checksum;
+            let frame_len = slot_offset + 2 + dle_encoder.start_marker().len();
+
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(&encoded[..frame_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_eq!(read_len, frame_len);
+            assert_frame_eq(&decoded[..payload.len()], &payload);
+            assert_eq!(&decoded[payload.len()..decoded_len], &[checksum, !checksum]);
+
+            // Without framing characters, the slot is at the end of the stream
+            dle_encoder.add_stx_etx = false;
+            let slot_offset = dle_encoder
+                .encode_reserve(&payload[..2], 1, &mut encoded)
+                .unwrap();
+            assert_frame_eq(&encoded[..slot_offset], &[0x01, DLE_CHAR, DLE_CHAR]);
+            dle_encoder.add_stx_etx = true;
+
+            // The slot and the end marker must fit into the destination
+            let end_len = dle_encoder.start_marker().len();
+            assert_eq!(
+                dle_encoder.encode_reserve(&payload, 2, &mut encoded[..frame_len - end_len]),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(
+                dle_encoder.encode_reserve(&payload, usize::MAX, &mut encoded),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_escapes() {
+        let payload: [u8; 3] = [1, CR_CHAR, 0x22];
+        let mut encoded: [u8; 16] = [0; 16];
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+
+        let cr_encoder = DleEncoder {
+            escape_cr: true,
+            ..Default::default()
+        };
+        let encoded_len = cr_encoder.encode(&payload, &mut encoded).unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[STX_CHAR, 1, DLE_CHAR, CR_CHAR + 0x40, 0x22, ETX_CHAR]
+        );
+
+        // The decoder was not configured to escape CR, so the escape sequence is rejected
+        let mut dle_encoder = DleEncoder::default();
+        let error = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            DleError::DecodingError {
+                kind: DecodeErrorKind::InvalidDleSequence { found: 0x4d },
+                offset: 3
+            }
+        );
+
+        dle_encoder.strict_escapes = false;
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+
+        // A raw CR is always accepted as data, even if the decoder escapes CR
+        let encoded_len = DleEncoder::default()
+            .encode(&payload, &mut encoded)
+            .unwrap();
+        let decoded_len = cr_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+    }
+
+    #[test]
+    fn test_encode_chunk() {
+        let payload: [u8; 9] = [0, DLE_CHAR, 1, STX_CHAR, ETX_CHAR, 2, DLE_CHAR, 3, 4];
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&payload, &mut expected).unwrap();
+            for chunk_len in 2..6 {
+                let mut encoded: [u8; 32] = [0; 32];
+                let mut encoded_len = 0;
+                let mut consumed = 0;
+                let mut role = FragmentRole::Whole;
+                let mut calls = 0;
+                loop {
+                    let mut chunk: [u8; 8] = [0; 8];
+                    let progress = dle_encoder.encode_chunk(
+                        &payload[consumed..],
+                        &mut chunk[..chunk_len],
+                        role,
+                    );
+                    assert!(progress.written <= chunk_len);
+                    encoded[encoded_len..encoded_len + progress.written]
+                        .copy_from_slice(&chunk[..progress.written]);
+                    encoded_len += progress.written;
+                    consumed += progress.src_consumed;
+                    calls += 1;
+                    if progress.complete {
+                        break;
+                    }
+                    // Every call must make progress, otherwise the chunks are too small
+                    assert!(progress.written > 0);
+                    role = FragmentRole::Last;
+                }
+                assert!(calls > 1);
+                assert_eq!(consumed, payload.len());
+                assert_frame_eq(&encoded[..encoded_len], &expected[..expected_len]);
+            }
+
+            // Suppress the end marker until the final call
+            let mut chunk: [u8; 32] = [0; 32];
+            let progress = dle_encoder.encode_chunk(&payload, &mut chunk, FragmentRole::First);
+            assert!(progress.complete);
+            assert_eq!(progress.src_consumed, payload.len());
+            assert_eq!(
+                &chunk[..progress.written],
+                &expected[..expected_len - dle_encoder.end_marker().len()]
+            );
+        }
+    }
+
+    const ESCAPE_TABLE: [u8; 256] = {
+        let mut table = [0; 256];
+        table[STX_CHAR as usize] = 0xa2;
+        table[ETX_CHAR as usize] = 0xb3;
+        table[CR_CHAR as usize] = 0xcd;
+        table
+    };
+
+    #[test]
+    fn test_escape_schemes() {
+        let payload: [u8; 6] = [0, STX_CHAR, CR_CHAR, DLE_CHAR, ETX_CHAR, 0xa2];
+        let schemes = [
+            (EscapeScheme::Additive(0x40), [0x42, 0x4d, 0x43]),
+            (EscapeScheme::Additive(0x20), [0x22, 0x2d, 0x23]),
+            (EscapeScheme::Xor(0x80), [0x82, 0x8d, 0x83]),
+            (EscapeScheme::Table(&ESCAPE_TABLE), [0xa2, 0xcd, 0xb3]),
+        ];
+        for (escape_scheme, escaped) in schemes.iter() {
+            let dle_encoder = DleEncoder {
+                escape_cr: true,
+                escape_scheme: *escape_scheme,
+                ..Default::default()
+            };
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            assert_eq!(
+                &encoded[..encoded_len],
+                &[
+                    STX_CHAR, 0, DLE_CHAR, escaped[0], DLE_CHAR, escaped[1], DLE_CHAR, DLE_CHAR,
+                    DLE_CHAR, escaped[2], 0xa2, ETX_CHAR
+                ]
+            );
+
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_eq!(read_len, encoded_len);
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+
+            // Escape sequences of other schemes are rejected
+            let faulty = [STX_CHAR, DLE_CHAR, 0x62, ETX_CHAR];
+            let error = dle_encoder
+                .decode(&faulty, &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(
+                error.decode_kind(),
+                Some(DecodeErrorKind::InvalidDleSequence { found: 0x62 })
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_probe_offset() {
+        let payload = [0x55, STX_CHAR, DLE_CHAR, ETX_CHAR];
+        let peer = DleEncoder {
+            escape_scheme: EscapeScheme::Additive(0x20),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 16] = [0; 16];
+        let encoded_len = peer.encode(&payload, &mut encoded).unwrap();
+        let dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 16] = [0; 16];
+        // The offset 0x01 is skipped because it escapes STX to ETX
+        let (decoded_len, offset) = dle_encoder
+            .decode_probe_offset(
+                &encoded[..encoded_len],
+                &[0x01, 0x40, 0x20, 0x80],
+                &mut decoded,
+            )
+            .unwrap();
+        assert_eq!(&decoded[..decoded_len], &payload);
+        assert_eq!(offset, 0x20);
+
+        // No candidate decodes the frame
+        let error = dle_encoder
+            .decode_probe_offset(&encoded[..encoded_len], &[0x40, 0x80], &mut decoded)
+            .unwrap_err();
+        assert_eq!(
+            error.decode_kind(),
+            Some(DecodeErrorKind::InvalidDleSequence { found: 0x22 })
+        );
+        assert_eq!(
+            dle_encoder.decode_probe_offset(&encoded[..encoded_len], &[0x01], &mut decoded),
+            Err(DleError::SourceIncomplete)
+        );
+        assert_eq!(
+            dle_encoder.decode_probe_offset(&encoded[..encoded_len], &[], &mut decoded),
+            Err(DleError::SourceIncomplete)
+        );
+
+        // Frames without escape sequences are decoded with the first valid candidate
+        let (decoded_len, offset) = dle_encoder
+            .decode_probe_offset(
+                &[STX_CHAR, 0x55, ETX_CHAR],
+                &[0x01, 0x40, 0x20],
+                &mut decoded,
+            )
+            .unwrap();
+        assert_eq!(&decoded[..decoded_len], &[0x55]);
+        assert_eq!(offset, 0x40);
+    }
+
+    #[test]
+    fn test_decode_and_remainder() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let first_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let second_len = dle_encoder
+                .encode(&TEST_ARRAY_1, &mut encoded[first_len..])
+                .unwrap();
+            let encoded = &encoded[..first_len + second_len];
+
+            let mut decoded: [u8; 16] = [0; 16];
+            let (decoded_len, remainder) = dle_encoder
+                .decode_and_remainder(encoded, &mut decoded)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+            assert_eq!(remainder.len(), second_len);
+
+            let (decoded_len, remainder) = dle_encoder
+                .decode_and_remainder(remainder, &mut decoded)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_1);
+            assert!(remainder.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_decode_with_raw() {
+        let configs = [
+            DleEncoder::escaped(),
+            DleEncoder::non_escaped(),
+            DleEncoder {
+                keep_terminator: true,
+                pad_to: Some((16, ETX_CHAR)),
+                ..DleEncoder::escaped()
+            },
+        ];
+        for dle_encoder in configs.iter() {
+            let mut encoded: [u8; 32] = [0; 32];
+            let first_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let second_len = dle_encoder
+                .encode(&TEST_ARRAY_1, &mut encoded[first_len..])
+                .unwrap();
+            let encoded = &encoded[..first_len + second_len];
+            let mut frame: [u8; 32] = [0; 32];
+            let frame_len = DleEncoder {
+                pad_to: None,
+                ..*dle_encoder
+            }
+            .encode(&TEST_ARRAY_4, &mut frame)
+            .unwrap();
+
+            let mut decoded: [u8; 16] = [0; 16];
+            let (decoded_len, raw) = dle_encoder.decode_with_raw(encoded, &mut decoded).unwrap();
+            let payload_len = TEST_ARRAY_4.len();
+            assert_frame_eq(&decoded[..payload_len], &TEST_ARRAY_4);
+            assert_eq!(
+                decoded_len,
+                payload_len + usize::from(dle_encoder.keep_terminator)
+            );
+            assert_frame_eq(raw, &frame[..frame_len]);
+            assert!(core::ptr::eq(raw.as_ptr(), encoded.as_ptr()));
+        }
+    }
+
+    #[test]
+    fn test_decode_chunks() {
+        let configs = [
+            DleEncoder::escaped(),
+            DleEncoder::non_escaped(),
+            DleEncoder {
+                keep_terminator: true,
+                resync_interval: Some(3),
+                ..DleEncoder::escaped()
+            },
+            DleEncoder {
+                escape_cr: true,
+                pad_to: Some((24, 0)),
+                ..DleEncoder::non_escaped()
+            },
+        ];
+        let payloads: [&[u8]; 5] = [
+            &[],
+            &[0x01, 0x55, 0xff],
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_4,
+            &[DLE_CHAR, DLE_CHAR, STX_CHAR, 0x55, CR_CHAR, ETX_CHAR, 0x77],
+        ];
+        for dle_encoder in configs.iter() {
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                let encoded = &encoded[..encoded_len];
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(encoded, &mut decoded, &mut read_len)
+                    .unwrap();
+
+                let mut reassembled: [u8; 32] = [0; 32];
+                let mut reassembled_len = 0;
+                for chunk in dle_encoder.decode_chunks(encoded).unwrap() {
+                    assert!(!chunk.is_empty());
+                    reassembled[reassembled_len..reassembled_len + chunk.len()]
+                        .copy_from_slice(chunk);
+                    reassembled_len += chunk.len();
+                }
+                assert_frame_eq(&reassembled[..reassembled_len], &decoded[..decoded_len]);
+            }
+        }
+
+        // An escape-free payload is a single chunk borrowed from the source
+        let dle_encoder = DleEncoder::default();
+        let encoded = [STX_CHAR, 0x01, 0x55, 0xff, ETX_CHAR];
+        let mut chunks = dle_encoder.decode_chunks(&encoded).unwrap();
+        let chunk = chunks.next().unwrap();
+        assert!(core::ptr::eq(chunk, &encoded[1..4]));
+        assert!(chunks.next().is_none());
+
+        // Escape sequences break the runs
+        let encoded = [
+            STX_CHAR, 0x01, DLE_CHAR, 0x42, 0x55, DLE_CHAR, DLE_CHAR, ETX_CHAR,
+        ];
+        let mut chunks = dle_encoder.decode_chunks(&encoded).unwrap();
+        assert_eq!(chunks.next(), Some(&[0x01][..]));
+        assert_eq!(chunks.next(), Some(&[STX_CHAR][..]));
+        assert_eq!(chunks.next(), Some(&[0x55][..]));
+        assert_eq!(chunks.next(), Some(&[DLE_CHAR][..]));
+        assert_eq!(chunks.next(), None);
+
+        // Erroneous frames are rejected before any chunk is yielded
+        let encoded = [STX_CHAR, 0x01, DLE_CHAR, 0x55, ETX_CHAR];
+        assert_eq!(
+            dle_encoder.decode_chunks(&encoded).err(),
+            Some(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x55 },
+                3
+            ))
+        );
+        let dle_encoder = DleEncoder {
+            idle_on_empty: Some(&[0x55]),
+            ..DleEncoder::default()
+        };
+        let mut chunks = dle_encoder
+            .decode_chunks(&[0x55, STX_CHAR, 0x01, ETX_CHAR])
+            .unwrap();
+        assert_eq!(chunks.next(), Some(&[0x01][..]));
+        assert_eq!(chunks.next(), None);
+        assert_eq!(
+            dle_encoder.decode_chunks(&[0x55, STX_CHAR, 0x01]).err(),
+            Some(DleError::decoding(DecodeErrorKind::MissingEnd, 3))
+        );
+    }
+
+    #[test]
+    fn test_iterator_size_hints() {
+        let configs = [
+            DleEncoder::escaped(),
+            DleEncoder::non_escaped(),
+            DleEncoder {
+                keep_terminator: true,
+                resync_interval: Some(1),
+                ..DleEncoder::escaped()
+            },
+            DleEncoder {
+                resync_interval: Some(2),
+                ..DleEncoder::non_escaped()
+            },
+        ];
+        let payloads: [&[u8]; 5] = [
+            &[],
+            &[0x01, 0x55, 0xff],
+            &[DLE_CHAR; 4],
+            &TEST_ARRAY_4,
+            &[DLE_CHAR, 0x01, STX_CHAR, 0x55, ETX_CHAR, 0x77],
+        ];
+        for dle_encoder in configs.iter() {
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 64] = [0; 64];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                let encoded = &encoded[..encoded_len];
+                // The size hint before each chunk brackets the number of remaining chunks
+                let mut size_hints = [(0, None); 64];
+                let mut chunk_count = 0;
+                let mut chunks = dle_encoder.decode_chunks(encoded).unwrap();
+                loop {
+                    size_hints[chunk_count] = chunks.size_hint();
+                    if chunks.next().is_none() {
+                        break;
+                    }
+                    chunk_count += 1;
+                }
+                assert!(chunk_count > 0 || payload.is_empty());
+                for (idx, &(lower, upper)) in size_hints[..=chunk_count].iter().enumerate() {
+                    let remaining = chunk_count - idx;
+                    assert!(lower <= remaining);
+                    assert!(remaining <= upper.unwrap());
+                }
+                // Only resync markers are skipped without yielding a chunk
+                if dle_encoder.resync_interval.is_none() && chunk_count > 0 {
+                    assert!(size_hints[0].0 > 0);
+                }
+            }
+        }
+
+        // The number of sparse updates is exact
+        let updates = [(0x0210, STX_CHAR), (0x1003, DLE_CHAR), (0xffff, 0x55)];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = DleEncoder::default()
+            .encode_sparse(&updates, &mut encoded)
+            .unwrap();
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut decoded_updates = DleEncoder::default()
+            .decode_sparse(&encoded[..encoded_len], &mut decoded)
+            .unwrap();
+        for remaining in (0..=updates.len()).rev() {
+            assert_eq!(decoded_updates.size_hint(), (remaining, Some(remaining)));
+            decoded_updates.next();
+        }
+    }
 
-    const TEST_ARRAY_0_ENCODED_ESCPAED: &[u8] = &[STX_CHAR, 0, 0, 0, 0, 0, ETX_CHAR];
-    const TEST_ARRAY_0_ENCODED_NON_ESCPAED: &[u8] =
-        &[DLE_CHAR, STX_CHAR, 0, 0, 0, 0, 0, DLE_CHAR, ETX_CHAR];
+    #[test]
+    fn test_same_payload() {
+        let escaped = DleEncoder::escaped();
+        let non_escaped = DleEncoder::non_escaped();
+        let payload: [u8; 5] = [STX_CHAR, 0x55, DLE_CHAR, ETX_CHAR, CR_CHAR];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = escaped.encode(&payload, &mut encoded).unwrap();
+        let encoded = &encoded[..encoded_len];
+        let mut other_encoded: [u8; 32] = [0; 32];
+        let other_encoded_len = non_escaped.encode(&payload, &mut other_encoded).unwrap();
+        let other_encoded = &other_encoded[..other_encoded_len];
+        assert_ne!(encoded, other_encoded);
+        assert_eq!(
+            escaped.same_payload(encoded, &non_escaped, other_encoded),
+            Ok(true)
+        );
+        assert_eq!(
+            non_escaped.same_payload(other_encoded, &escaped, encoded),
+            Ok(true)
+        );
+        assert_eq!(escaped.same_payload(encoded, &escaped, encoded), Ok(true));
+
+        // Payloads differing in a byte or in their length
+        let mut different: [u8; 32] = [0; 32];
+        let different_len = escaped.encode(&[STX_CHAR, 0x56], &mut different).unwrap();
+        let different = &different[..different_len];
+        assert_eq!(
+            escaped.same_payload(encoded, &escaped, different),
+            Ok(false)
+        );
+        let mut shorter: [u8; 32] = [0; 32];
+        let shorter_len = non_escaped.encode(&payload[..4], &mut shorter).unwrap();
+        assert_eq!(
+            escaped.same_payload(encoded, &non_escaped, &shorter[..shorter_len]),
+            Ok(false)
+        );
+
+        // Errors of either frame are returned
+        assert_eq!(
+            escaped.same_payload(encoded, &non_escaped, &other_encoded[1..]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 0))
+        );
+        assert_eq!(
+            escaped.same_payload(&encoded[..encoded_len - 1], &non_escaped, other_encoded),
+            Err(DleError::decoding(
+                DecodeErrorKind::MissingEnd,
+                encoded_len - 1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_dle_sequence() {
+        let dle_encoder = DleEncoder::default();
+        let mut encoded: [u8; 16] = [0; 16];
+        let encoded_len = dle_encoder
+            .encode(&[0x01, DLE_CHAR, 0x02], &mut encoded)
+            .unwrap();
+        // Replace the second DLE of the doubled DLE
+        assert_eq!(corrupt_escape(&mut encoded[..encoded_len], 0), Some(3));
+        let source_stream = &encoded[..encoded_len];
+        let expected = Err(DleError::decoding(
+            DecodeErrorKind::InvalidDleSequence {
+                found: CORRUPTION_BYTE,
+            },
+            3,
+        ));
+
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+        let result = dle_encoder.decode(source_stream, &mut decoded, &mut read_len);
+        assert_eq!(result, expected);
+        let mismatch = dle_encoder
+            .explain_invalid_escape(&result.unwrap_err())
+            .unwrap();
+        assert_eq!(mismatch.found, CORRUPTION_BYTE);
+        assert_eq!(
+            dle_encoder.decode_escaped(source_stream, &mut decoded, &mut read_len),
+            expected
+        );
+        let mut bytes = source_stream.iter().copied();
+        assert_eq!(
+            dle_encoder.decode_pull(|| bytes.next(), &mut decoded),
+            expected
+        );
+        assert_eq!(dle_encoder.decoded_len(source_stream), expected);
+        let mut byte_decoder = ByteAtATimeDecoder::<16>::new(dle_encoder);
+        let pushed = source_stream
+            .iter()
+            .map(|&byte| byte_decoder.push_byte(byte))
+            .find(|push| *push != DecodePush::More);
+        assert_eq!(
+            pushed,
+            Some(DecodePush::Error(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence {
+                    found: CORRUPTION_BYTE,
+                },
+                3
+            )))
+        );
+
+        // A DLE at the end of the stream still expects the following character
+        assert_eq!(
+            dle_encoder.decode(&source_stream[..3], &mut decoded, &mut read_len),
+            Err(DleError::decoding(DecodeErrorKind::MissingEnd, 3))
+        );
+    }
+
+    #[test]
+    fn test_explain_invalid_escape() {
+        let mut dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+        let source_stream = [STX_CHAR, 0x55, DLE_CHAR, 0x99, ETX_CHAR];
+        let error = dle_encoder
+            .decode(&source_stream, &mut decoded, &mut read_len)
+            .unwrap_err();
+        // The error carries the rejected character, so the stream is not required
+        assert_eq!(
+            error.decode_kind(),
+            Some(DecodeErrorKind::InvalidDleSequence { found: 0x99 })
+        );
+        let mismatch = dle_encoder.explain_invalid_escape(&error).unwrap();
+        assert_eq!(mismatch.found, 0x99);
+        assert_eq!(
+            mismatch.expected,
+            ByteSet::from_bytes(&[DLE_CHAR, STX_CHAR + 0x40, ETX_CHAR + 0x40])
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "found 0x99, expected one of DLE, 0x42, 0x43"
+        );
+
+        // The expected characters follow the configuration
+        dle_encoder.escape_cr = true;
+        dle_encoder.escape_scheme = EscapeScheme::Xor(0x80);
+        let source_stream = [STX_CHAR, DLE_CHAR, 0x42, ETX_CHAR];
+        let error = dle_encoder
+            .decode(&source_stream, &mut decoded, &mut read_len)
+            .unwrap_err();
+        let mismatch = dle_encoder.explain_invalid_escape(&error).unwrap();
+        assert_eq!(mismatch.found, 0x42);
+        assert_eq!(
+            mismatch.to_string(),
+            "found 0x42, expected one of DLE, 0x82, 0x83, 0x8d"
+        );
+
+        let dle_encoder = DleEncoder::non_escaped();
+        let source_stream = [DLE_CHAR, STX_CHAR, DLE_CHAR, 0x55, DLE_CHAR, ETX_CHAR];
+        let error = dle_encoder
+            .decode(&source_stream, &mut decoded, &mut read_len)
+            .unwrap_err();
+        let mismatch = dle_encoder.explain_invalid_escape(&error).unwrap();
+        assert_eq!(
+            mismatch,
+            EscapeMismatch {
+                found: 0x55,
+                expected: ByteSet::from_bytes(&[STX_CHAR, ETX_CHAR, DLE_CHAR]),
+            }
+        );
+
+        // Other errors are not explained
+        let error = DleError::decoding(DecodeErrorKind::MissingEnd, 0);
+        assert_eq!(dle_encoder.explain_invalid_escape(&error), None);
+        assert_eq!(
+            dle_encoder.explain_invalid_escape(&DleError::StreamTooShort),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_with_stats() {
+        let payload = [
+            DLE_CHAR, STX_CHAR, 0x55, DLE_CHAR, ETX_CHAR, CR_CHAR, DLE_CHAR,
+        ];
+        let configs = [
+            (DleEncoder::escaped(), 3, 2),
+            (DleEncoder::non_escaped(), 3, 0),
+            (
+                DleEncoder {
+                    escape_cr: true,
+                    resync_interval: Some(2),
+                    ..DleEncoder::escaped()
+                },
+                3,
+                3,
+            ),
+        ];
+        for (dle_encoder, doubled_dle, escaped_chars) in configs.iter() {
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            let (decoded_len, stats) = dle_encoder
+                .decode_with_stats(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+            assert_eq!(read_len, encoded_len);
+            assert_eq!(
+                stats,
+                DecodeStats {
+                    doubled_dle: *doubled_dle,
+                    escaped_chars: *escaped_chars,
+                }
+            );
+            let framing_len = 2 * dle_encoder.start_marker().len();
+            let resync_len = dle_encoder
+                .resync_interval
+                .map_or(0, |interval| 2 * ((payload.len() - 1) / interval));
+            assert_eq!(
+                stats.overhead(),
+                encoded_len - framing_len - resync_len - payload.len()
+            );
+        }
+
+        // Escape-free frames and erroneous frames
+        let dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+        assert_eq!(
+            dle_encoder.decode_with_stats(&[STX_CHAR, 0x01, ETX_CHAR], &mut decoded, &mut read_len),
+            Ok((1, DecodeStats::default()))
+        );
+        assert_eq!(
+            dle_encoder.decode_with_stats(&[STX_CHAR, DLE_CHAR, 0x55], &mut decoded, &mut read_len),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x55 },
+                2
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_scan_frames() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut stream = b"[INFO] starting up\r\n".to_vec();
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let first_range = stream.len()..stream.len() + encoded_len;
+            stream.extend_from_slice(&encoded[..encoded_len]);
+            // Log text containing control characters, but no valid frame
+            stream.extend_from_slice(b"[WARN] stray \x02 and \x10\x02 markers\r\n");
+            let encoded_len = dle_encoder.encode(&TEST_ARRAY_2, &mut encoded).unwrap();
+            let second_range = stream.len()..stream.len() + encoded_len;
+            stream.extend_from_slice(&encoded[..encoded_len]);
+            // Malformed frame with an invalid escape sequence
+            stream.extend_from_slice(&[STX_CHAR, 1, DLE_CHAR, 0x99, ETX_CHAR]);
+            stream.extend_from_slice(&[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, 0x99, DLE_CHAR, ETX_CHAR]);
+            stream.extend_from_slice(b"[INFO] done\r\n");
+
+            let frames = dle_encoder.scan_frames(&stream);
+            assert_eq!(
+                frames,
+                vec![
+                    (first_range, TEST_ARRAY_4.to_vec()),
+                    (second_range, TEST_ARRAY_2.to_vec())
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_decoded_len_bounded() {
+        let payload: [u8; 16] = [
+            0x01, STX_CHAR, DLE_CHAR, 0x55, ETX_CHAR, 0x66, DLE_CHAR, 0x77, 1, 2, 3, 4, 5, 6, 7, 8,
+        ];
+        for escape_stx_etx in [true, false] {
+            for keep_terminator in [false, true] {
+                let dle_encoder = DleEncoder {
+                    escape_stx_etx,
+                    keep_terminator,
+                    ..Default::default()
+                };
+                let decoded_len = payload.len() + usize::from(keep_terminator);
+                let mut encoded: [u8; 64] = [0; 64];
+                let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+                let encoded = &encoded[..encoded_len];
+                assert_eq!(
+                    dle_encoder.decoded_len_bounded(encoded, decoded_len + 1),
+                    Ok(decoded_len)
+                );
+                assert_eq!(
+                    dle_encoder.decoded_len_bounded(encoded, decoded_len),
+                    Ok(decoded_len)
+                );
+                assert_eq!(
+                    dle_encoder.decoded_len_bounded(encoded, decoded_len - 1),
+                    Err(DleError::FrameTooLarge)
+                );
+                assert_eq!(
+                    dle_encoder.decoded_len_bounded(encoded, 0),
+                    Err(DleError::FrameTooLarge)
+                );
+                // The limit is hit before the end of an unterminated frame is reached
+                let unterminated = &encoded[..encoded_len - 2];
+                assert_eq!(
+                    dle_encoder.decoded_len_bounded(unterminated, 4),
+                    Err(DleError::FrameTooLarge)
+                );
+                // Other errors are still reported
+                assert_eq!(
+                    dle_encoder
+                        .decoded_len_bounded(&encoded[1..], 4)
+                        .unwrap_err()
+                        .decode_kind(),
+                    Some(DecodeErrorKind::MissingStart)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_decode_dest_len() {
+        let mut dle_encoder = DleEncoder::default();
+        let check_dest_len = |dle_encoder: &DleEncoder, source: &[u8]| {
+            let min_len = dle_encoder.min_decode_dest_len(source);
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decode_res = dle_encoder.decode(source, &mut decoded[..min_len], &mut read_len);
+            assert_ne!(decode_res, Err(DleError::StreamTooShort));
+            match dle_encoder.decoded_len(source) {
+                Ok(decoded_len) => {
+                    assert_eq!(decode_res, Ok(decoded_len));
+                    assert_eq!(min_len, decoded_len);
+                    // One byte less is not sufficient
+                    if decoded_len > 0 {
+                        let decode_res = dle_encoder.decode(
+                            source,
+                            &mut decoded[..decoded_len - 1],
+                            &mut read_len,
+                        );
+                        assert_eq!(decode_res, Err(DleError::StreamTooShort));
+                    }
+                }
+                Err(error) => {
+                    assert_eq!(decode_res, Err(error));
+                    assert_eq!(min_len, source.len());
+                }
+            }
+        };
+
+        let payloads: [&[u8]; 6] = [
+            &[],
+            &TEST_ARRAY_0,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_2,
+            &TEST_ARRAY_3,
+            &TEST_ARRAY_4,
+        ];
+        for keep_terminator in [false, true] {
+            dle_encoder.keep_terminator = keep_terminator;
+            for escape_stx_etx in [true, false] {
+                dle_encoder.escape_stx_etx = escape_stx_etx;
+                for payload in payloads.iter() {
+                    let mut encoded: [u8; 32] = [0; 32];
+                    let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                    check_dest_len(&dle_encoder, &encoded[..encoded_len]);
+                    assert_eq!(
+                        dle_encoder.decoded_len(&encoded[..encoded_len]),
+                        Ok(payload.len() + usize::from(keep_terminator))
+                    );
+                }
+            }
+        }
+
+        dle_encoder.keep_terminator = false;
+        dle_encoder.escape_stx_etx = true;
+        check_dest_len(&dle_encoder, &[STX_CHAR, 1, DLE_CHAR, 0x99, ETX_CHAR]);
+        check_dest_len(&dle_encoder, &[STX_CHAR, 1, STX_CHAR, 2, ETX_CHAR]);
+        check_dest_len(&dle_encoder, &[STX_CHAR, 0x11, 0x22, 0x33]);
+        check_dest_len(&dle_encoder, &[0, 1, ETX_CHAR]);
+        dle_encoder.escape_stx_etx = false;
+        check_dest_len(
+            &dle_encoder,
+            &[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, 0x99, DLE_CHAR],
+        );
+        check_dest_len(&dle_encoder, &[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, STX_CHAR]);
+        check_dest_len(&dle_encoder, &[DLE_CHAR, STX_CHAR, 1, 2, DLE_CHAR]);
+        check_dest_len(&dle_encoder, &[DLE_CHAR, 0, 1]);
+    }
+
+    #[test]
+    fn test_assert_lossless() {
+        let mut dle_encoder = DleEncoder::default();
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        dle_encoder.escape_cr = true;
+        dle_encoder.escape_extra = ByteSet::from_bytes(&[LF_CHAR, DLE_CHAR]);
+        dle_encoder.escape_scheme = EscapeScheme::Table(&ESCAPE_TABLE);
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        dle_encoder.escape_scheme = EscapeScheme::Xor(0x20);
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        // CR XOR 0x1D is DLE
+        dle_encoder.escape_scheme = EscapeScheme::Xor(0x1d);
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::ControlCharEscape { byte: CR_CHAR })
+        );
+        // The escaped form of CR is only relevant if CR escapes are accepted
+        dle_encoder.escape_cr = false;
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        dle_encoder.strict_escapes = false;
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::ControlCharEscape { byte: CR_CHAR })
+        );
+
+        static AMBIGUOUS_TABLE: [u8; 256] = {
+            let mut table = [0x55; 256];
+            table[STX_CHAR as usize] = 0x42;
+            table[ETX_CHAR as usize] = 0x43;
+            table[LF_CHAR as usize] = 0x43;
+            table
+        };
+        let mut dle_encoder = DleEncoder {
+            escape_extra: ByteSet::from_bytes(&[LF_CHAR]),
+            escape_scheme: EscapeScheme::Table(&AMBIGUOUS_TABLE),
+            ..Default::default()
+        };
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::AmbiguousEscape {
+                first: ETX_CHAR,
+                second: LF_CHAR
+            })
+        );
+        // The escape configuration is ignored in the non-escaped mode
+        dle_encoder.escape_stx_etx = false;
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        dle_encoder.keep_terminator = true;
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::KeepTerminator)
+        );
+        dle_encoder.add_stx_etx = false;
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::MissingFraming)
+        );
+
+        // Control characters can not be used as alternative terminator in both modes
+        for escape_stx_etx in [true, false] {
+            let mut dle_encoder = DleEncoder {
+                escape_stx_etx,
+                ..Default::default()
+            };
+            for byte in [STX_CHAR, ETX_CHAR, DLE_CHAR] {
+                dle_encoder.alt_terminator = Some(byte);
+                let error = ConfigError::AltTerminatorConflict { byte };
+                assert_eq!(dle_encoder.validate_config(), Err(error));
+                assert_eq!(dle_encoder.assert_lossless(), Err(error));
+            }
+            dle_encoder.alt_terminator = Some(CR_CHAR);
+            assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        }
+
+        // Fill bytes can not be confused with a start marker or an escape sequence
+        for escape_stx_etx in [true, false] {
+            let mut dle_encoder = DleEncoder {
+                escape_stx_etx,
+                ..Default::default()
+            };
+            for byte in [STX_CHAR, DLE_CHAR] {
+                dle_encoder.pad_to = Some((16, byte));
+                let error = ConfigError::FillConflict { byte };
+                assert_eq!(dle_encoder.validate_config(), Err(error));
+                assert_eq!(dle_encoder.assert_lossless(), Err(error));
+            }
+            dle_encoder.pad_to = Some((16, ETX_CHAR));
+            assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_is_reversible() {
+        let mut dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 4] = [
+            &[],
+            &[DLE_CHAR, STX_CHAR],
+            &[STX_CHAR, 0x55, ETX_CHAR, DLE_CHAR],
+            &[DLE_CHAR; 8],
+        ];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            // DLE is doubled in the non-escaped mode, so a DLE STX payload can not be
+            // mistaken for the start of a new frame
+            for payload in payloads {
+                assert!(dle_encoder.is_reversible(payload));
+            }
+
+            let unframed = DleEncoder {
+                add_stx_etx: false,
+                ..dle_encoder
+            };
+            assert!(!unframed.is_reversible(&[0x55]));
+            assert!(!unframed.is_reversible(&[DLE_CHAR, STX_CHAR]));
+
+            let keep_terminator = DleEncoder {
+                keep_terminator: true,
+                ..dle_encoder
+            };
+            assert!(!keep_terminator.is_reversible(&[0x55]));
+        }
+
+        // An alternative terminator is only a problem if it occurs in the payload
+        let alt_terminator = DleEncoder {
+            alt_terminator: Some(0x55),
+            ..Default::default()
+        };
+        assert!(alt_terminator.is_reversible(&[DLE_CHAR, STX_CHAR]));
+        assert!(!alt_terminator.is_reversible(&[0x01, 0x55, 0x01]));
+        let mut escaped_alt_terminator = alt_terminator;
+        escaped_alt_terminator.escape_extra.insert(0x55);
+        assert!(escaped_alt_terminator.is_reversible(&[0x01, 0x55, 0x01]));
+    }
+
+    #[test]
+    fn test_decode_pull() {
+        let mut dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 4] = [&[], &TEST_ARRAY_0, &TEST_ARRAY_3, &TEST_ARRAY_4];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                // A byte following the frame must not be consumed
+                encoded[encoded_len] = 0x55;
+                let mut source = encoded[..encoded_len + 1].iter().copied();
+                let mut decoded: [u8; 32] = [0; 32];
+                let decoded_len = dle_encoder
+                    .decode_pull(|| source.next(), &mut decoded)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], payload);
+                assert_eq!(source.next(), Some(0x55));
+
+                for truncated_len in 0..encoded_len {
+                    let mut source = encoded[..truncated_len].iter().copied();
+                    assert_eq!(
+                        dle_encoder.decode_pull(|| source.next(), &mut decoded),
+                        Err(DleError::SourceIncomplete)
+                    );
+                }
+                if !payload.is_empty() {
+                    let mut source = encoded[..encoded_len].iter().copied();
+                    assert_eq!(
+                        dle_encoder
+                            .decode_pull(|| source.next(), &mut decoded[..payload.len() - 1]),
+                        Err(DleError::StreamTooShort)
+                    );
+                }
+            }
+        }
+
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut decode_slice = |dle_encoder: &DleEncoder, encoded: &[u8]| {
+            let mut source = encoded.iter().copied();
+            dle_encoder.decode_pull(|| source.next(), &mut decoded)
+        };
+        dle_encoder.escape_stx_etx = true;
+        assert_eq!(
+            decode_slice(&dle_encoder, &[0, STX_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 0))
+        );
+        assert_eq!(
+            decode_slice(&dle_encoder, &[STX_CHAR, 1, DLE_CHAR, 0x99, ETX_CHAR]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x99 },
+                3
+            ))
+        );
+        assert_eq!(
+            decode_slice(&dle_encoder, &[STX_CHAR, 1, STX_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, 2))
+        );
+        dle_encoder.keep_terminator = true;
+        assert_eq!(decode_slice(&dle_encoder, &[STX_CHAR, 1, ETX_CHAR]), Ok(2));
+        dle_encoder.keep_terminator = false;
+        dle_encoder.escape_stx_etx = false;
+        assert_eq!(
+            decode_slice(&dle_encoder, &[DLE_CHAR, 0, DLE_CHAR, ETX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::MissingStart, 1))
+        );
+        assert_eq!(
+            decode_slice(&dle_encoder, &[DLE_CHAR, STX_CHAR, DLE_CHAR, 0x42]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x42 },
+                3
+            ))
+        );
+        assert_eq!(
+            decode_slice(&dle_encoder, &[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, STX_CHAR]),
+            Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, 3))
+        );
+    }
+
+    #[test]
+    fn test_alt_terminator() {
+        let mut dle_encoder = DleEncoder {
+            escape_extra: ByteSet::from_bytes(&[LF_CHAR]),
+            alt_terminator: Some(LF_CHAR),
+            ..Default::default()
+        };
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+
+        // Frames ended by the alternate terminator
+        let encoded = [STX_CHAR, 0x11, 0x22, LF_CHAR, 0x55];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_eq!(read_len, 4);
+        assert_frame_eq(&decoded[..2], &[0x11, 0x22]);
+        let encoded = [STX_CHAR, 0x11, DLE_CHAR, DLE_CHAR, LF_CHAR];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_frame_eq(&decoded[..2], &[0x11, DLE_CHAR]);
+        assert_eq!(dle_encoder.decoded_len(&encoded), Ok(2));
+
+        // Escaped forms of the alternate terminator and ETX are still data
+        let payload = [0x11, LF_CHAR, ETX_CHAR, 0x22];
+        let mut encoded: [u8; 16] = [0; 16];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(read_len, encoded_len);
+
+        // The escaped form of a character may be the alternate terminator itself
+        dle_encoder.alt_terminator = Some(ETX_CHAR + 0x40);
+        let encoded = [STX_CHAR, 0x11, DLE_CHAR, ETX_CHAR + 0x40, ETX_CHAR + 0x40];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_frame_eq(&decoded[..2], &[0x11, ETX_CHAR]);
+        #[cfg(feature = "alloc")]
+        assert_eq!(dle_encoder.frame_ranges(&encoded), vec![0..5]);
+        let mut source = encoded.iter().copied();
+        assert_eq!(
+            dle_encoder.decode_pull(|| source.next(), &mut decoded),
+            Ok(2)
+        );
+
+        // The terminator which ended the frame is kept
+        dle_encoder.alt_terminator = Some(LF_CHAR);
+        dle_encoder.keep_terminator = true;
+        let encoded = [STX_CHAR, 0x11, LF_CHAR];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_frame_eq(&decoded[..2], &[0x11, LF_CHAR]);
+        let encoded = [STX_CHAR, 0x11, ETX_CHAR];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_frame_eq(&decoded[..2], &[0x11, ETX_CHAR]);
+        dle_encoder.keep_terminator = false;
+
+        // In the non-escaped mode, the alternate terminator follows a DLE character
+        dle_encoder.escape_stx_etx = false;
+        let encoded = [DLE_CHAR, STX_CHAR, 0x11, LF_CHAR, DLE_CHAR, LF_CHAR];
+        let decoded_len = dle_encoder.decode(&encoded, &mut decoded, &mut read_len);
+        assert_eq!(decoded_len, Ok(2));
+        assert_eq!(read_len, 6);
+        assert_frame_eq(&decoded[..2], &[0x11, LF_CHAR]);
+        assert_eq!(dle_encoder.decoded_len(&encoded), Ok(2));
+        #[cfg(feature = "alloc")]
+        {
+            let mut stream = vec![0x55];
+            stream.extend_from_slice(&encoded);
+            assert_eq!(dle_encoder.frame_ranges(&stream), vec![1..7]);
+        }
+        let mut source = encoded.iter().copied();
+        assert_eq!(
+            dle_encoder.decode_pull(|| source.next(), &mut decoded),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_encode_escaped_differential() {
+        // Reference encoding built from the per-byte encoding
+        let reference_encode = |dle_encoder: &DleEncoder, payload: &[u8]| {
+            let mut encoded = [0; 80];
+            let mut encoded_len = 0;
+            let mut push = |bytes: &[u8]| {
+                encoded[encoded_len..encoded_len + bytes.len()].copy_from_slice(bytes);
+                encoded_len += bytes.len();
+            };
+            if dle_encoder.add_stx_etx {
+                push(&[STX_CHAR]);
+            }
+            for &byte in payload {
+                let (bytes, len) = dle_encoder.encode_byte(byte);
+                push(&bytes[..len]);
+            }
+            if dle_encoder.add_stx_etx {
+                push(&[ETX_CHAR]);
+            }
+            (encoded, encoded_len)
+        };
+        let configs = [
+            DleEncoder::default(),
+            DleEncoder {
+                escape_cr: true,
+                ..Default::default()
+            },
+            DleEncoder {
+                escape_extra: ByteSet::from_bytes(&[LF_CHAR, 0x00, 0xff, DLE_CHAR]),
+                escape_scheme: EscapeScheme::Xor(0x80),
+                ..Default::default()
+            },
+            DleEncoder {
+                escape_cr: true,
+                escape_scheme: EscapeScheme::Table(&ESCAPE_TABLE),
+                add_stx_etx: false,
+                ..Default::default()
+            },
+        ];
+        // Deterministic pseudo random payloads with a high share of special bytes
+        let mut state: u32 = 0x1234_5678;
+        let mut next_byte = || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let special = [STX_CHAR, ETX_CHAR, DLE_CHAR, CR_CHAR, LF_CHAR, 0x00, 0xff];
+            let value = (state >> 16) as u8;
+            if value & 1 == 0 {
+                special[(value as usize / 2) % special.len()]
+            } else {
+                value
+            }
+        };
+        for payload_len in 0..32 {
+            let mut payload = [0; 32];
+            payload[..payload_len]
+                .iter_mut()
+                .for_each(|byte| *byte = next_byte());
+            let payload = &payload[..payload_len];
+            for dle_encoder in configs.iter() {
+                let (expected, expected_len) = reference_encode(dle_encoder, payload);
+                let mut encoded = [0; 80];
+                let encoded_len = dle_encoder.encode_escaped(payload, &mut encoded).unwrap();
+                assert_frame_eq(&encoded[..encoded_len], &expected[..expected_len]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_escaped_bulk_differential() {
+        let configs = [
+            DleEncoder::default(),
+            DleEncoder {
+                alt_terminator: Some(LF_CHAR),
+                ..Default::default()
+            },
+            DleEncoder {
+                escape_cr: true,
+                resync_interval: Some(5),
+                ..Default::default()
+            },
+        ];
+        // Deterministic pseudo random streams with long runs of plain bytes
+        let mut state: u32 = 0x8765_4321;
+        let mut next_byte = || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let special = [STX_CHAR, ETX_CHAR, DLE_CHAR, LF_CHAR, 0x42, 0x43, SYN_CHAR];
+            let value = (state >> 16) as u8;
+            if value < 0x20 {
+                special[value as usize % special.len()]
+            } else {
+                value
+            }
+        };
+        for source_len in 2..64 {
+            let mut source = [0; 64];
+            source[..source_len]
+                .iter_mut()
+                .for_each(|byte| *byte = next_byte());
+            source[0] = STX_CHAR;
+            let source = &source[..source_len];
+            for dle_encoder in configs.iter() {
+                // Include destinations which are too short
+                for dest_len in [4, 16, 64] {
+                    let mut scalar = [0; 64];
+                    let mut scalar_read_len = 0;
+                    let scalar_result = dle_encoder.decode_escaped_generic(
+                        source,
+                        &mut scalar[..dest_len],
+                        &mut scalar_read_len,
+                        false,
+                    );
+                    let mut bulk = [0; 64];
+                    let mut bulk_read_len = 0;
+                    let bulk_result = dle_encoder.decode_escaped_generic(
+                        source,
+                        &mut bulk[..dest_len],
+                        &mut bulk_read_len,
+                        true,
+                    );
+                    assert_eq!(bulk_result, scalar_result);
+                    assert_eq!(bulk_read_len, scalar_read_len);
+                    if let Ok(decoded_len) = scalar_result {
+                        assert_frame_eq(&bulk[..decoded_len], &scalar[..decoded_len]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_frame_typed() {
+        let mut dle_encoder = DleEncoder {
+            escape_cr: true,
+            ..Default::default()
+        };
+        let mut encoded = [0; 32];
+        let encoded_len = dle_encoder.encode(&TEST_ARRAY_3, &mut encoded).unwrap();
+        encoded[encoded_len] = 0x55;
+        let frame = dle_encoder
+            .decode_frame_typed(&encoded[..encoded_len + 1])
+            .unwrap();
+        assert_eq!(frame.payload, TEST_ARRAY_3.to_vec());
+        assert!(frame.escape_stx_etx);
+        assert!(frame.cr_escaped);
+        assert_eq!(frame.source_range, 0..encoded_len);
+
+        let encoded_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+        let frame = dle_encoder
+            .decode_frame_typed(&encoded[..encoded_len])
+            .unwrap();
+        assert_eq!(frame.payload, TEST_ARRAY_4.to_vec());
+        assert!(!frame.cr_escaped);
+
+        // CR escapes are accepted but not produced without escape_cr
+        dle_encoder.escape_cr = false;
+        dle_encoder.strict_escapes = false;
+        let frame = dle_encoder
+            .decode_frame_typed(&[STX_CHAR, CR_CHAR, DLE_CHAR, CR_CHAR + 0x40, ETX_CHAR])
+            .unwrap();
+        assert_eq!(frame.payload, vec![CR_CHAR, CR_CHAR]);
+        assert!(frame.cr_escaped);
+
+        dle_encoder.escape_stx_etx = false;
+        dle_encoder.keep_terminator = true;
+        let encoded_len = dle_encoder.encode(&TEST_ARRAY_3, &mut encoded).unwrap();
+        let frame = dle_encoder
+            .decode_frame_typed(&encoded[..encoded_len])
+            .unwrap();
+        assert_eq!(frame.payload, vec![0, CR_CHAR, ETX_CHAR, ETX_CHAR]);
+        assert!(!frame.escape_stx_etx);
+        assert!(!frame.cr_escaped);
+        assert_eq!(frame.source_range, 0..encoded_len);
+
+        assert_eq!(
+            dle_encoder.decode_frame_typed(&[DLE_CHAR, STX_CHAR, 1, DLE_CHAR, 0x42]),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x42 },
+                4
+            ))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_frame_into_iter() {
+        let dle_encoder = DleEncoder::default();
+        let mut encoded: [u8; 16] = [0; 16];
+        let encoded_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+        let frame = dle_encoder
+            .decode_frame_typed(&encoded[..encoded_len])
+            .unwrap();
+        let expected_sum: u32 = TEST_ARRAY_4.iter().map(|&byte| u32::from(byte)).sum();
+
+        let mut sum = 0;
+        for byte in &frame {
+            sum += u32::from(*byte);
+        }
+        assert_eq!(sum, expected_sum);
+        let sum: u32 = frame.into_iter().map(u32::from).sum();
+        assert_eq!(sum, expected_sum);
+    }
+
+    #[test]
+    fn test_encode_non_escaped_exact_fit() {
+        let dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            ..Default::default()
+        };
+        let expected: [&[u8]; 5] = [
+            TEST_ARRAY_0_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_1_ENCODED_NON_ESCPAED,
+            TEST_ARRAY_2_ENCODED_NON_ESCPAED,
+            TEST_ARRAY_3_ENCODED_NON_ESCPAED,
+            &TEST_ARRAY_4_ENCODED_NON_ESCPAED,
+        ];
+        let payloads: [&[u8]; 5] = [
+            &TEST_ARRAY_0,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_2,
+            &TEST_ARRAY_3,
+            &TEST_ARRAY_4,
+        ];
+        for (payload, expected) in payloads.iter().zip(expected.iter()) {
+            let mut encoded = [0; 16];
+            let exact_len = expected.len();
+            assert_eq!(
+                dle_encoder.encode(payload, &mut encoded[..exact_len]),
+                Ok(exact_len)
+            );
+            assert_frame_eq(&encoded[..exact_len], expected);
+            assert_eq!(
+                dle_encoder.encode(payload, &mut encoded[..exact_len - 1]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+        assert_eq!(dle_encoder.encode(&[], &mut [0; 4]), Ok(4));
+    }
+
+    #[test]
+    fn test_encode_escaped_exact_fit() {
+        let dle_encoder = DleEncoder::default();
+        let expected: [&[u8]; 5] = [
+            TEST_ARRAY_0_ENCODED_ESCPAED,
+            &TEST_ARRAY_1_ENCODED_ESCPAED,
+            TEST_ARRAY_2_ENCODED_ESCPAED,
+            TEST_ARRAY_3_ENCODED_ESCPAED,
+            TEST_ARRAY_4_ENCODED_ESCPAED,
+        ];
+        let payloads: [&[u8]; 5] = [
+            &TEST_ARRAY_0,
+            &TEST_ARRAY_1,
+            &TEST_ARRAY_2,
+            &TEST_ARRAY_3,
+            &TEST_ARRAY_4,
+        ];
+        for (payload, expected) in payloads.iter().zip(expected.iter()) {
+            let mut encoded = [0; 16];
+            let exact_len = expected.len();
+            assert_eq!(
+                dle_encoder.encode(payload, &mut encoded[..exact_len]),
+                Ok(exact_len)
+            );
+            assert_frame_eq(&encoded[..exact_len], expected);
+            assert_eq!(
+                dle_encoder.encode(payload, &mut encoded[..exact_len - 1]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+        assert_eq!(dle_encoder.encode(&[], &mut [0; 2]), Ok(2));
+        assert_eq!(
+            dle_encoder.encode(&[], &mut [0; 1]),
+            Err(DleError::StreamTooShort)
+        );
+    }
+
+    #[test]
+    fn test_encode_ranged() {
+        const HEADER_LEN: usize = 3;
+        let mut dle_encoder = DleEncoder::default();
+        let source: [u8; 7] = [STX_CHAR, DLE_CHAR, ETX_CHAR, 0x11, STX_CHAR, DLE_CHAR, 0x22];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder
+                .encode_ranged(&source, HEADER_LEN..source.len(), &mut encoded)
+                .unwrap();
+            let start_len = dle_encoder.start_marker().len();
+            // The header is transmitted verbatim
+            assert_eq!(
+                &encoded[start_len..start_len + HEADER_LEN],
+                &source[..HEADER_LEN]
+            );
+            // The body is a regular encoded stream once the header is skipped
+            let mut body_frame: [u8; 32] = [0; 32];
+            let body_frame_len = encoded_len - HEADER_LEN;
+            body_frame[..start_len].copy_from_slice(dle_encoder.start_marker());
+            body_frame[start_len..body_frame_len]
+                .copy_from_slice(&encoded[start_len + HEADER_LEN..encoded_len]);
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(&body_frame[..body_frame_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &source[HEADER_LEN..]);
+
+            // Escaping the full range is the same as a regular encoding
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&source, &mut expected).unwrap();
+            assert_eq!(
+                dle_encoder.encode_ranged(&source, 0..100, &mut encoded),
+                Ok(expected_len)
+            );
+            assert_frame_eq(&encoded[..expected_len], &expected[..expected_len]);
+            assert_eq!(
+                dle_encoder.encode_ranged(&source, 0..100, &mut encoded[..expected_len - 1]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+        dle_encoder.add_stx_etx = false;
+        let mut encoded: [u8; 32] = [0; 32];
+        assert_eq!(
+            dle_encoder.encode_ranged(&source, 10..20, &mut encoded),
+            Err(DleError::AmbiguousPayload)
+        );
+        dle_encoder.escape_stx_etx = true;
+        assert_eq!(
+            dle_encoder.encode_ranged(&source, 10..20, &mut encoded),
+            Ok(source.len())
+        );
+        assert_frame_eq(&encoded[..source.len()], &source);
+    }
+
+    #[test]
+    fn test_encode_one() {
+        let configs = [
+            DleEncoder::default(),
+            DleEncoder::non_escaped(),
+            DleEncoder::raw_escaped_no_framing(),
+            DleEncoder::text_safe(),
+            DleEncoder {
+                add_stx_etx: false,
+                escape_stx_etx: false,
+                ..Default::default()
+            },
+            DleEncoder {
+                dle_escape_style: DleStyle::Offset,
+                escape_extra: ByteSet::new().with(0x00).with(0xff),
+                resync_interval: Some(1),
+                ..Default::default()
+            },
+            DleEncoder {
+                pad_to: Some((6, 0x55)),
+                ..DleEncoder::non_escaped()
+            },
+            DleEncoder {
+                pad_to: Some((3, 0x55)),
+                ..Default::default()
+            },
+        ];
+        for dle_encoder in configs.iter() {
+            for byte in 0..=u8::MAX {
+                let mut expected: [u8; 8] = [0; 8];
+                let mut encoded: [u8; 8] = [0; 8];
+                assert_eq!(
+                    dle_encoder.encode_one(byte, &mut encoded),
+                    dle_encoder.encode(&[byte], &mut expected)
+                );
+                assert_eq!(encoded, expected);
+                // Destination streams which are too short
+                for dest_len in 0..4 {
+                    assert_eq!(
+                        dle_encoder.encode_one(byte, &mut encoded[..dest_len]),
+                        dle_encoder.encode(&[byte], &mut expected[..dest_len])
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_predicate() {
+        let dle_encoder = DleEncoder::default();
+        let is_control = |byte: u8| byte < 0x20;
+        let payload: [u8; 6] = [0x00, 0x1f, 0x20, DLE_CHAR, STX_CHAR, 0x55];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder
+            .encode_with_predicate(&payload, is_control, &mut encoded)
+            .unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[
+                STX_CHAR, DLE_CHAR, 0x40, DLE_CHAR, 0x5f, 0x20, DLE_CHAR, DLE_CHAR, DLE_CHAR, 0x42,
+                0x55, ETX_CHAR
+            ]
+        );
+        assert!(encoded[1..encoded_len - 1]
+            .iter()
+            .all(|&byte| !is_control(byte) || byte == DLE_CHAR));
+
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode_with_predicate(
+                &encoded[..encoded_len],
+                is_control,
+                &mut decoded,
+                &mut read_len,
+            )
+            .unwrap();
+        assert_eq!(read_len, encoded_len);
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+
+        // A predicate escaping fewer bytes rejects the escape sequences
+        let error = dle_encoder
+            .decode_with_predicate(
+                &encoded[..encoded_len],
+                |_| false,
+                &mut decoded,
+                &mut read_len,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error,
+            DleError::decoding(DecodeErrorKind::InvalidDleSequence { found: 0x40 }, 2)
+        );
+
+        // The frame is always encoded in the escaped mode, STX, ETX and DLE are escaped
+        // without a predicate
+        let non_escaped = DleEncoder::non_escaped();
+        let encoded_len = non_escaped
+            .encode_with_predicate(&[ETX_CHAR], |_| false, &mut encoded)
+            .unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[STX_CHAR, DLE_CHAR, 0x43, ETX_CHAR]
+        );
+    }
+
+    #[test]
+    fn test_encode_scatter() {
+        let payload: [u8; 8] = [
+            0x01, STX_CHAR, DLE_CHAR, 0x55, ETX_CHAR, 0x66, DLE_CHAR, 0x77,
+        ];
+        for dle_encoder in [
+            DleEncoder::escaped(),
+            DleEncoder::non_escaped(),
+            DleEncoder {
+                resync_interval: Some(3),
+                ..DleEncoder::escaped()
+            },
+        ] {
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&payload, &mut expected).unwrap();
+            // Every split position, including inside escape sequences and empty regions
+            for split in 0..=expected_len {
+                let mut dest_0: [u8; 32] = [0; 32];
+                let mut dest_1: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder
+                    .encode_scatter(&payload, &mut dest_0[..split], &mut dest_1)
+                    .unwrap();
+                assert_eq!(encoded_len, expected_len);
+                let mut concatenated: [u8; 64] = [0; 64];
+                concatenated[..split].copy_from_slice(&dest_0[..split]);
+                concatenated[split..encoded_len].copy_from_slice(&dest_1[..encoded_len - split]);
+                assert_frame_eq(&concatenated[..encoded_len], &expected[..expected_len]);
+
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(&concatenated[..encoded_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], &payload);
+
+                // One byte missing in total
+                if split < expected_len {
+                    let dest_1_len = expected_len - split - 1;
+                    assert_eq!(
+                        dle_encoder.encode_scatter(
+                            &payload,
+                            &mut dest_0[..split],
+                            &mut dest_1[..dest_1_len]
+                        ),
+                        Err(DleError::StreamTooShort)
+                    );
+                }
+            }
+        }
+    }
 
-    const TEST_ARRAY_1_ENCODED_ESCPAED: [u8; 6] = [STX_CHAR, 0, DLE_CHAR, DLE_CHAR, 5, ETX_CHAR];
-    const TEST_ARRAY_1_ENCODED_NON_ESCPAED: [u8; 8] = [
-        DLE_CHAR, STX_CHAR, 0, DLE_CHAR, DLE_CHAR, 5, DLE_CHAR, ETX_CHAR,
-    ];
+    #[test]
+    fn test_decode_concat() {
+        let mut dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 3] = [&TEST_ARRAY_1, &TEST_ARRAY_3, &TEST_ARRAY_4];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 64] = [0; 64];
+            let mut encoded_len = 0;
+            let mut expected: [u8; 16] = [0; 16];
+            let mut expected_len = 0;
+            for payload in payloads.iter() {
+                encoded_len += dle_encoder
+                    .encode(payload, &mut encoded[encoded_len..])
+                    .unwrap();
+                expected[expected_len..expected_len + payload.len()].copy_from_slice(payload);
+                expected_len += payload.len();
+            }
+            let mut decoded: [u8; 16] = [0; 16];
+            assert_eq!(
+                dle_encoder.decode_concat(&encoded[..encoded_len], &mut decoded),
+                Ok(expected_len)
+            );
+            assert_frame_eq(&decoded[..expected_len], &expected[..expected_len]);
+            assert_eq!(
+                dle_encoder
+                    .decode_concat(&encoded[..encoded_len], &mut decoded[..expected_len - 1]),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(dle_encoder.decode_concat(&[], &mut decoded), Ok(0));
 
-    const TEST_ARRAY_2_ENCODED_ESCPAED: &[u8] =
-        &[STX_CHAR, 0, DLE_CHAR, STX_CHAR + 0x40, 5, ETX_CHAR];
-    const TEST_ARRAY_2_ENCODED_NON_ESCPAED: &[u8] =
-        &[DLE_CHAR, STX_CHAR, 0, STX_CHAR, 5, DLE_CHAR, ETX_CHAR];
+            // Error offsets are relative to the start of the source stream
+            encoded[encoded_len] = 0x55;
+            let error = dle_encoder
+                .decode_concat(&encoded[..encoded_len + 1], &mut decoded)
+                .unwrap_err();
+            assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingStart));
+            assert_eq!(error.offset(), Some(encoded_len));
+        }
+    }
 
-    const TEST_ARRAY_3_ENCODED_ESCPAED: &[u8] =
-        &[STX_CHAR, 0, CR_CHAR, DLE_CHAR, ETX_CHAR + 0x40, ETX_CHAR];
-    const TEST_ARRAY_3_ENCODED_NON_ESCPAED: &[u8] =
-        &[DLE_CHAR, STX_CHAR, 0, CR_CHAR, ETX_CHAR, DLE_CHAR, ETX_CHAR];
+    #[test]
+    fn test_for_each_frame() {
+        let mut dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &[], &TEST_ARRAY_3, &TEST_ARRAY_4];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 64] = [0; 64];
+            let mut encoded_len = 0;
+            for payload in payloads.iter() {
+                encoded_len += dle_encoder
+                    .encode(payload, &mut encoded[encoded_len..])
+                    .unwrap();
+            }
+            let mut decoded: [u8; 8] = [0; 8];
+            let mut frame_idx = 0;
+            let frame_count = dle_encoder
+                .for_each_frame(&encoded[..encoded_len], &mut decoded, |payload| {
+                    assert_frame_eq(payload, payloads[frame_idx]);
+                    frame_idx += 1;
+                })
+                .unwrap();
+            assert_eq!(frame_count, payloads.len());
+            assert_eq!(frame_idx, payloads.len());
+            assert_eq!(dle_encoder.for_each_frame(&[], &mut decoded, |_| ()), Ok(0));
 
-    const TEST_ARRAY_4_ENCODED_ESCPAED: &[u8] = &[
-        STX_CHAR,
-        DLE_CHAR,
-        DLE_CHAR,
-        DLE_CHAR,
-        ETX_CHAR + 0x40,
-        DLE_CHAR,
-        STX_CHAR + 0x40,
-        ETX_CHAR,
-    ];
-    const TEST_ARRAY_4_ENCODED_NON_ESCPAED: [u8; 8] = [
-        DLE_CHAR, STX_CHAR, DLE_CHAR, DLE_CHAR, ETX_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR,
-    ];
+            // The frames before an error are still passed to the closure
+            encoded[encoded_len] = 0x55;
+            let mut frame_count = 0;
+            let error = dle_encoder
+                .for_each_frame(&encoded[..encoded_len + 1], &mut decoded, |_| {
+                    frame_count += 1
+                })
+                .unwrap_err();
+            assert_eq!(frame_count, payloads.len());
+            assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingStart));
+            assert_eq!(error.offset(), Some(encoded_len));
+            assert_eq!(
+                dle_encoder.for_each_frame(&encoded[..encoded_len], &mut decoded[..1], |_| ()),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
 
     #[test]
-    fn test_encoder() {
+    fn test_validate_batch() {
         let mut dle_encoder = DleEncoder::default();
-        let mut buffer: [u8; 32] = [0; 32];
-        let test_encode_closure = |dle_encoder: &DleEncoder,
-                                   buf_to_encode: &[u8],
-                                   expected_buf: &[u8],
-                                   buffer: &mut [u8]| {
-            let encode_res = dle_encoder.encode(buf_to_encode, buffer);
-            assert!(encode_res.is_ok());
-            for (idx, byte) in expected_buf.iter().enumerate() {
-                assert_eq!(buffer[idx], *byte);
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &[], &TEST_ARRAY_3, &TEST_ARRAY_4];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 64] = [0; 64];
+            let mut frame_ends = [0; 4];
+            let mut encoded_len = 0;
+            for (payload, frame_end) in payloads.iter().zip(frame_ends.iter_mut()) {
+                encoded_len += dle_encoder
+                    .encode(payload, &mut encoded[encoded_len..])
+                    .unwrap();
+                *frame_end = encoded_len;
             }
-            assert_eq!(encode_res.unwrap(), expected_buf.len());
+            // Every batch of whole frames is valid
+            for (frame_count, &frame_end) in frame_ends.iter().enumerate() {
+                assert_eq!(
+                    dle_encoder.validate_batch(&encoded[..frame_end]),
+                    Ok(frame_count + 1)
+                );
+            }
+            assert_eq!(dle_encoder.validate_batch(&[]), Ok(0));
+
+            // Trailing junk and a trailing partial frame
+            encoded[encoded_len] = 0x55;
+            assert_eq!(
+                dle_encoder.validate_batch(&encoded[..encoded_len + 1]),
+                Err(DleError::decoding(
+                    DecodeErrorKind::MissingStart,
+                    encoded_len
+                ))
+            );
+            let partial_len = dle_encoder
+                .encode(&TEST_ARRAY_1, &mut encoded[encoded_len..])
+                .unwrap();
+            let error = dle_encoder
+                .validate_batch(&encoded[..encoded_len + partial_len - 1])
+                .unwrap_err();
+            assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingEnd));
+
+            // A malformed frame inside the batch
+            encoded[frame_ends[0] + dle_encoder.start_marker().len()] = DLE_CHAR;
+            encoded[frame_ends[0] + dle_encoder.start_marker().len() + 1] = 0x99;
+            let error = dle_encoder
+                .validate_batch(&encoded[..encoded_len])
+                .unwrap_err();
+            assert_eq!(
+                error.decode_kind(),
+                Some(DecodeErrorKind::InvalidDleSequence { found: 0x99 })
+            );
+            assert!(error.offset().unwrap() > frame_ends[0]);
+        }
+
+        // Padding belongs to the frames, idle sequences belong to the following frame
+        let dle_encoder = DleEncoder {
+            pad_to: Some((6, 0x55)),
+            ..Default::default()
+        };
+        let mut encoded: [u8; 12] = [0; 12];
+        dle_encoder.encode(&[0x01], &mut encoded[..6]).unwrap();
+        dle_encoder.encode(&[DLE_CHAR], &mut encoded[6..]).unwrap();
+        assert_eq!(dle_encoder.validate_batch(&encoded), Ok(2));
+        let dle_encoder = DleEncoder {
+            idle_on_empty: Some(&[DLE_CHAR, SYN_CHAR]),
+            ..Default::default()
         };
+        let encoded = [
+            DLE_CHAR, SYN_CHAR, STX_CHAR, ETX_CHAR, STX_CHAR, 0x55, ETX_CHAR,
+        ];
+        assert_eq!(dle_encoder.validate_batch(&encoded), Ok(2));
+    }
 
-        let test_faulty_encoding = |dle_encoder: &DleEncoder,
-                                    buf_to_encode: &[u8],
-                                    expected_buf: &[u8],
-                                    buffer: &mut [u8]| {
-            for faulty_dest_size in 0..expected_buf.len() {
-                let encode_res =
-                    dle_encoder.encode(buf_to_encode, &mut buffer[0..faulty_dest_size]);
-                assert!(encode_res.is_err());
-                assert_eq!(encode_res.unwrap_err(), DleError::StreamTooShort);
+    #[test]
+    fn test_text_safe() {
+        let dle_encoder = DleEncoder::text_safe();
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        let payload: [u8; 7] = [b'o', b'k', CR_CHAR, LF_CHAR, LF_CHAR, DLE_CHAR, CR_CHAR];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        assert!(!encoded[..encoded_len]
+            .iter()
+            .any(|&byte| byte == CR_CHAR || byte == LF_CHAR));
+
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(read_len, encoded_len);
+    }
+
+    #[test]
+    fn test_control_char_accessors() {
+        for dle_encoder in [
+            DleEncoder::default(),
+            DleEncoder::non_escaped(),
+            DleEncoder::text_safe(),
+        ] {
+            assert_eq!(dle_encoder.stx(), STX_CHAR);
+            assert_eq!(dle_encoder.etx(), ETX_CHAR);
+            assert_eq!(dle_encoder.dle(), DLE_CHAR);
+            assert_eq!(dle_encoder.cr(), CR_CHAR);
+        }
+
+        // The accessors match the characters of encoded frames
+        let dle_encoder = DleEncoder::non_escaped();
+        let mut encoded: [u8; 16] = [0; 16];
+        let encoded_len = dle_encoder.encode(&[0x55], &mut encoded).unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[
+                dle_encoder.dle(),
+                dle_encoder.stx(),
+                0x55,
+                dle_encoder.dle(),
+                dle_encoder.etx()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dle_mode() {
+        let modes = [
+            (DleMode::EscapedFramed, (true, false, true)),
+            (DleMode::EscapedFramedCr, (true, true, true)),
+            (DleMode::EscapedBare, (true, false, false)),
+            (DleMode::EscapedBareCr, (true, true, false)),
+            (DleMode::NonEscapedFramed, (false, false, true)),
+        ];
+        for (mode, fields) in modes {
+            let dle_encoder = DleEncoder::from(mode);
+            assert_eq!(
+                (
+                    dle_encoder.escape_stx_etx,
+                    dle_encoder.escape_cr,
+                    dle_encoder.add_stx_etx
+                ),
+                fields
+            );
+            assert_eq!(dle_encoder.mode(), Some(mode));
+            assert!(dle_encoder.escape_extra.is_empty());
+            assert_eq!(dle_encoder.pad_to, None);
+        }
+        assert_eq!(DleEncoder::default().mode(), Some(DleMode::EscapedFramed));
+        assert_eq!(
+            DleEncoder::raw_escaped_no_framing().mode(),
+            Some(DleMode::EscapedBare)
+        );
+
+        // The other settings do not change the mode
+        let dle_encoder = DleEncoder {
+            resync_interval: Some(4),
+            ..DleEncoder::non_escaped()
+        };
+        assert_eq!(dle_encoder.mode(), Some(DleMode::NonEscapedFramed));
+
+        // Combinations with ignored settings or without a way to frame the stream
+        for (escape_cr, add_stx_etx) in [(true, true), (true, false), (false, false)] {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx: false,
+                escape_cr,
+                add_stx_etx,
+                ..Default::default()
+            };
+            assert_eq!(dle_encoder.mode(), None);
+        }
+    }
+
+    #[test]
+    fn test_presets() {
+        let escaped = DleEncoder::escaped();
+        assert!(escaped.escape_stx_etx);
+        assert!(escaped.add_stx_etx);
+        let non_escaped = DleEncoder::non_escaped();
+        assert!(!non_escaped.escape_stx_etx);
+        assert!(non_escaped.add_stx_etx);
+        let raw = DleEncoder::raw_escaped_no_framing();
+        assert!(raw.escape_stx_etx);
+        assert!(!raw.add_stx_etx);
+        for preset in [escaped, non_escaped, raw] {
+            assert!(!preset.escape_cr);
+            assert!(preset.escape_extra.is_empty());
+            assert_eq!(preset.escape_scheme, EscapeScheme::Additive(0x40));
+            assert!(!preset.keep_terminator);
+            assert!(preset.strict_escapes);
+            assert_eq!(preset.alt_terminator, None);
+            assert_eq!(preset.resync_interval, None);
+            assert_eq!(preset.trailing_policy, TrailingPolicy::Ignore);
+            assert_eq!(preset.pad_to, None);
+        }
+
+        for preset in [escaped, non_escaped] {
+            assert_eq!(preset.assert_lossless(), Ok(()));
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = preset.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = preset
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+            assert_eq!(read_len, encoded_len);
+        }
+
+        // Without framing, the receiver adds the framing characters of the escaped mode
+        let mut encoded: [u8; 32] = [0; 32];
+        encoded[0] = STX_CHAR;
+        let encoded_len = raw.encode(&TEST_ARRAY_4, &mut encoded[1..]).unwrap() + 2;
+        encoded[encoded_len - 1] = ETX_CHAR;
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let decoded_len = escaped
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_to_string() {
+        let text = "Grüße, DLE \u{2}";
+        for dle_encoder in [DleEncoder::escaped(), DleEncoder::non_escaped()] {
+            let mut encoded: [u8; 64] = [0; 64];
+            let encoded_len = dle_encoder.encode(text.as_bytes(), &mut encoded).unwrap();
+            assert_eq!(
+                dle_encoder.decode_to_string(&encoded[..encoded_len]),
+                Ok(String::from(text))
+            );
+
+            // Truncated multi-byte sequence
+            let encoded_len = dle_encoder
+                .encode(&text.as_bytes()[..3], &mut encoded)
+                .unwrap();
+            assert_eq!(
+                dle_encoder.decode_to_string(&encoded[..encoded_len]),
+                Err(DleError::InvalidUtf8)
+            );
+        }
+
+        // Decoding errors take precedence
+        let dle_encoder = DleEncoder::default();
+        assert_eq!(
+            dle_encoder.decode_to_string(&[STX_CHAR, 0xff]),
+            Err(DleError::decoding(DecodeErrorKind::MissingEnd, 2))
+        );
+    }
+
+    #[test]
+    fn test_looks_encoded() {
+        let payloads: [&[u8]; 4] = [&TEST_ARRAY_1, &TEST_ARRAY_2, &TEST_ARRAY_4, &[]];
+        for dle_encoder in [DleEncoder::escaped(), DleEncoder::non_escaped()] {
+            for payload in payloads.iter() {
+                let mut encoded: [u8; 32] = [0; 32];
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                assert!(dle_encoder.looks_encoded(&encoded[..encoded_len]));
+                // Raw payloads, truncated frames and frames followed by other data
+                assert!(!dle_encoder.looks_encoded(payload));
+                assert!(!dle_encoder.looks_encoded(&encoded[..encoded_len - 1]));
+                assert!(!dle_encoder.looks_encoded(&encoded[..encoded_len + 1]));
+            }
+        }
+
+        let dle_encoder = DleEncoder::default();
+        // Invalid escape sequence
+        assert!(!dle_encoder.looks_encoded(&[STX_CHAR, DLE_CHAR, 0x01, ETX_CHAR]));
+        // Two frames
+        assert!(!dle_encoder.looks_encoded(&[STX_CHAR, ETX_CHAR, STX_CHAR, ETX_CHAR]));
+        // Frames of the other mode
+        assert!(!dle_encoder.looks_encoded(&[DLE_CHAR, STX_CHAR, 0x01, DLE_CHAR, ETX_CHAR]));
+        assert!(!DleEncoder::non_escaped().looks_encoded(&[STX_CHAR, 0x01, ETX_CHAR]));
+        // Without framing characters, nothing is detected
+        let mut encoded: [u8; 8] = [0; 8];
+        let raw = DleEncoder::raw_escaped_no_framing();
+        let encoded_len = raw.encode(&TEST_ARRAY_2, &mut encoded).unwrap();
+        assert!(!raw.looks_encoded(&encoded[..encoded_len]));
+    }
+
+    #[test]
+    fn test_encode_fmt() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&TEST_ARRAY_4, &mut expected).unwrap();
+
+            dle_encoder.text_format = TextFormat::Char;
+            let mut text = String::new();
+            assert_eq!(
+                dle_encoder.encode_fmt(&TEST_ARRAY_4, &mut text),
+                Ok(expected_len)
+            );
+            let chars: Vec<u8> = text.chars().map(|c| c as u8).collect();
+            assert_frame_eq(&chars, &expected[..expected_len]);
+
+            dle_encoder.text_format = TextFormat::Hex;
+            let mut text = String::new();
+            assert_eq!(
+                dle_encoder.encode_fmt(&TEST_ARRAY_4, &mut text),
+                Ok(expected_len)
+            );
+            let hex: String = expected[..expected_len]
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            assert_eq!(text, hex);
+        }
+
+        // The hexadecimal format uses the same casing as encode_hex
+        let mut hex: [u8; 64] = [0; 64];
+        let hex_len = dle_encoder.encode_hex(&TEST_ARRAY_4, &mut hex).unwrap();
+        let mut text = String::new();
+        dle_encoder.encode_fmt(&TEST_ARRAY_4, &mut text).unwrap();
+        assert_eq!(text.as_bytes(), &hex[..hex_len]);
+
+        // Idle sequences, padding and resynchronization are written like by encode
+        let configs = [
+            DleEncoder {
+                idle_on_empty: Some(&[SYN_CHAR, SYN_CHAR]),
+                ..Default::default()
+            },
+            DleEncoder {
+                pad_to: Some((24, 0x55)),
+                ..Default::default()
+            },
+            DleEncoder {
+                resync_interval: Some(2),
+                ..Default::default()
+            },
+        ];
+        for mut config in configs {
+            config.text_format = TextFormat::Char;
+            for payload in [&[][..], &TEST_ARRAY_4[..]] {
+                let mut expected: [u8; 32] = [0; 32];
+                let expected_len = config.encode(payload, &mut expected).unwrap();
+                let mut text = String::new();
+                assert_eq!(config.encode_fmt(payload, &mut text), Ok(expected_len));
+                let chars: Vec<u8> = text.chars().map(|c| c as u8).collect();
+                assert_frame_eq(&chars, &expected[..expected_len]);
             }
+        }
+
+        // Frames exceeding the padded length are rejected without writing anything
+        let padded = DleEncoder {
+            pad_to: Some((4, 0)),
+            ..Default::default()
         };
+        let mut text = String::new();
+        assert_eq!(
+            padded.encode_fmt(&TEST_ARRAY_4, &mut text),
+            Err(DleError::FrameTooLarge)
+        );
+        assert!(text.is_empty());
 
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_0,
-            TEST_ARRAY_0_ENCODED_ESCPAED,
-            &mut buffer,
+        // Ambiguous payloads are rejected like by encode
+        let unframed = DleEncoder {
+            escape_stx_etx: false,
+            add_stx_etx: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            unframed.encode_fmt(&[0x01, ETX_CHAR], &mut String::new()),
+            Err(DleError::AmbiguousPayload)
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_1,
-            &TEST_ARRAY_1_ENCODED_ESCPAED,
-            &mut buffer,
+
+        // Bytes above 0x7f are written as a single character each, which takes two bytes
+        // of UTF-8, while the returned length counts frame bytes
+        dle_encoder.text_format = TextFormat::Char;
+        dle_encoder.add_stx_etx = false;
+        let mut text = String::new();
+        assert_eq!(dle_encoder.encode_fmt(&[0xe4], &mut text), Ok(1));
+        assert_eq!(text, "\u{e4}");
+        assert_eq!(text.len(), 2);
+
+        struct FailingWriter;
+        impl core::fmt::Write for FailingWriter {
+            fn write_str(&mut self, _: &str) -> core::fmt::Result {
+                Err(core::fmt::Error)
+            }
+        }
+        assert_eq!(
+            dle_encoder.encode_fmt(&[1], &mut FailingWriter),
+            Err(DleError::WriteFailed)
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_2,
-            TEST_ARRAY_2_ENCODED_ESCPAED,
-            &mut buffer,
+    }
+
+    #[test]
+    fn test_framed_no_escape() {
+        let payload = *b"STATUS 1\r\n";
+        for dle_encoder in [DleEncoder::default(), DleEncoder::non_escaped()] {
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder
+                .encode_framed_no_escape(&payload, &mut encoded)
+                .unwrap();
+            assert_eq!(encoded_len, payload.len() + 2);
+            assert_eq!(encoded[0], STX_CHAR);
+            assert_eq!(&encoded[1..encoded_len - 1], &payload);
+            assert_eq!(encoded[encoded_len - 1], ETX_CHAR);
+            // The escaped mode writes the same frame for safe payloads
+            let mut expected: [u8; 16] = [0; 16];
+            let expected_len = DleEncoder::escaped()
+                .encode(&payload, &mut expected)
+                .unwrap();
+            assert_eq!(&encoded[..encoded_len], &expected[..expected_len]);
+
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode_framed_no_escape(&encoded[..encoded_len + 1], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_eq!(read_len, encoded_len);
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+
+            // Unsafe payloads are rejected
+            for byte in [STX_CHAR, ETX_CHAR, DLE_CHAR] {
+                assert_eq!(
+                    dle_encoder.encode_framed_no_escape(&[0x55, byte], &mut encoded),
+                    Err(DleError::AmbiguousPayload)
+                );
+            }
+            assert_eq!(
+                dle_encoder.encode_framed_no_escape(&payload, &mut encoded[..payload.len() + 1]),
+                Err(DleError::StreamTooShort)
+            );
+        }
+
+        let dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 4] = [0; 4];
+        let mut read_len = 0;
+        let errors = [
+            (&[0x55, ETX_CHAR][..], DecodeErrorKind::MissingStart, 0),
+            (&[STX_CHAR, 0x55][..], DecodeErrorKind::MissingEnd, 2),
+            (
+                &[STX_CHAR, DLE_CHAR, 0x42, ETX_CHAR][..],
+                DecodeErrorKind::RawControlChar,
+                1,
+            ),
+            (
+                &[STX_CHAR, 0x55, STX_CHAR, ETX_CHAR][..],
+                DecodeErrorKind::RawControlChar,
+                2,
+            ),
+        ];
+        for (encoded, kind, offset) in errors.iter() {
+            assert_eq!(
+                dle_encoder.decode_framed_no_escape(encoded, &mut decoded, &mut read_len),
+                Err(DleError::decoding(*kind, *offset))
+            );
+        }
+        assert_eq!(
+            dle_encoder.decode_framed_no_escape(
+                &[STX_CHAR, 0x55, 0x55, 0x55, 0x55, 0x55, ETX_CHAR],
+                &mut decoded,
+                &mut read_len
+            ),
+            Err(DleError::StreamTooShort)
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_3,
-            TEST_ARRAY_3_ENCODED_ESCPAED,
-            &mut buffer,
+    }
+
+    #[test]
+    fn test_hex_layer() {
+        let mut dle_encoder = DleEncoder::default();
+        let payload: [u8; 6] = [STX_CHAR, 0x55, DLE_CHAR, ETX_CHAR, 0xab, 0xff];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut expected: [u8; 32] = [0; 32];
+            let expected_len = dle_encoder.encode(&payload, &mut expected).unwrap();
+            let mut encoded: [u8; 64] = [0; 64];
+            let encoded_len = dle_encoder.encode_hex(&payload, &mut encoded).unwrap();
+            let hex: String = expected[..expected_len]
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            assert_eq!(&encoded[..encoded_len], hex.as_bytes());
+
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode_hex(&encoded[..encoded_len + 2], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_eq!(read_len, encoded_len);
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+
+            // Lowercase digits are accepted as well
+            let lowercase = hex.to_lowercase();
+            let decoded_len = dle_encoder
+                .decode_hex(lowercase.as_bytes(), &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+
+            // A truncated pair of digits
+            let error = dle_encoder
+                .decode_hex(&encoded[..encoded_len - 1], &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(
+                error,
+                DleError::decoding(DecodeErrorKind::MissingEnd, encoded_len - 1)
+            );
+
+            // A destination which is too short does not consume any characters, like decode
+            read_len = usize::MAX;
+            assert_eq!(
+                dle_encoder.decode_hex(&encoded[..encoded_len], &mut decoded[..1], &mut read_len),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(read_len, 0);
+
+            // The destination must hold the hexadecimal form
+            let error = dle_encoder
+                .encode_hex(&payload, &mut encoded[..encoded_len - 1])
+                .unwrap_err();
+            assert_eq!(error, DleError::StreamTooShort);
+        }
+
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        let error = dle_encoder
+            .decode_hex(b"10025G1003", &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            DleError::decoding(DecodeErrorKind::InvalidHexDigit, 5)
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_4,
-            TEST_ARRAY_4_ENCODED_ESCPAED,
-            &mut buffer,
+        assert_eq!(read_len, 5);
+
+        // Offsets of decoding errors refer to the hexadecimal characters
+        dle_encoder.escape_stx_etx = true;
+        let error = dle_encoder
+            .decode_hex(b"02109903", &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            DleError::decoding(DecodeErrorKind::InvalidDleSequence { found: 0x99 }, 4)
         );
+    }
 
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_0,
-            TEST_ARRAY_0_ENCODED_ESCPAED,
-            &mut buffer,
+    #[test]
+    fn test_validate_config() {
+        let mut dle_encoder = DleEncoder::default();
+        assert_eq!(dle_encoder.validate_config(), Ok(()));
+        // Framing settings are not checked, in contrast to assert_lossless
+        dle_encoder.add_stx_etx = false;
+        dle_encoder.keep_terminator = true;
+        assert_eq!(dle_encoder.validate_config(), Ok(()));
+
+        // STX + 0x01 is ETX
+        dle_encoder.escape_scheme = EscapeScheme::Additive(0x01);
+        assert_eq!(
+            dle_encoder.validate_config(),
+            Err(ConfigError::ControlCharEscape { byte: STX_CHAR })
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_1,
-            &TEST_ARRAY_1_ENCODED_ESCPAED,
-            &mut buffer,
+        dle_encoder.escape_scheme = EscapeScheme::Additive(0x80);
+        assert_eq!(dle_encoder.validate_config(), Ok(()));
+        // 0x90 + 0x80 is DLE
+        dle_encoder.escape_extra = ByteSet::from_bytes(&[0x90]);
+        assert_eq!(
+            dle_encoder.validate_config(),
+            Err(ConfigError::ControlCharEscape { byte: 0x90 })
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_2,
-            TEST_ARRAY_2_ENCODED_ESCPAED,
-            &mut buffer,
+    }
+
+    #[test]
+    fn test_config_fingerprint() {
+        static TABLE: [u8; 256] = [0x55; 256];
+        let base = DleEncoder::default();
+        assert_eq!(
+            base.config_fingerprint(),
+            DleEncoder::escaped().config_fingerprint()
+        );
+        // The fingerprint must not change between platforms or releases
+        assert_eq!(base.config_fingerprint(), 0x6532_b190);
+
+        let variants = [
+            DleEncoder::non_escaped(),
+            DleEncoder::raw_escaped_no_framing(),
+            DleEncoder::text_safe(),
+            DleEncoder {
+                escape_cr: true,
+                ..base
+            },
+            DleEncoder {
+                keep_terminator: true,
+                ..base
+            },
+            DleEncoder {
+                strict_escapes: false,
+                ..base
+            },
+            DleEncoder {
+                strict_non_escaped: true,
+                ..base
+            },
+            DleEncoder {
+                escape_extra: ByteSet::from_bytes(&[0xff]),
+                ..base
+            },
+            DleEncoder {
+                escape_scheme: EscapeScheme::Additive(0x41),
+                ..base
+            },
+            DleEncoder {
+                escape_scheme: EscapeScheme::Xor(0x40),
+                ..base
+            },
+            DleEncoder {
+                escape_scheme: EscapeScheme::Table(&TABLE),
+                ..base
+            },
+            DleEncoder {
+                alt_terminator: Some(LF_CHAR),
+                ..base
+            },
+            DleEncoder {
+                text_format: TextFormat::Hex,
+                ..base
+            },
+            DleEncoder {
+                resync_interval: Some(64),
+                ..base
+            },
+            DleEncoder {
+                trailing_policy: TrailingPolicy::Error,
+                ..base
+            },
+            DleEncoder {
+                pad_to: Some((64, 0)),
+                ..base
+            },
+            DleEncoder {
+                pad_to: Some((64, 0x55)),
+                ..base
+            },
+            DleEncoder {
+                idle_on_empty: Some(&[]),
+                ..base
+            },
+            DleEncoder {
+                idle_on_empty: Some(&[0x55]),
+                ..base
+            },
+            DleEncoder {
+                lenient_stx: true,
+                ..base
+            },
+            DleEncoder {
+                lenient_dle: true,
+                ..base
+            },
+            DleEncoder {
+                etx_terminates_only_after_dle: true,
+                ..base
+            },
+        ];
+        for (idx, variant) in variants.iter().enumerate() {
+            let copy = *variant;
+            assert_eq!(variant.config_fingerprint(), copy.config_fingerprint());
+            assert_ne!(variant.config_fingerprint(), base.config_fingerprint());
+            for other in variants[idx + 1..].iter() {
+                assert_ne!(variant.config_fingerprint(), other.config_fingerprint());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_diff_against() {
+        let dle_encoder = DleEncoder::default();
+        let payload = [0x01, CR_CHAR, 0x55];
+        let diff = dle_encoder
+            .diff_against(&DleEncoder::escaped(), &payload)
+            .unwrap();
+        assert!(!diff.differs());
+        assert_eq!(diff.first_difference, None);
+        assert_frame_eq(&diff.encoded, &diff.other_encoded);
+
+        // The escaped CR is the first difference
+        let diff = dle_encoder
+            .diff_against(&DleEncoder::text_safe(), &payload)
+            .unwrap();
+        assert!(diff.differs());
+        assert_eq!(diff.first_difference, Some(2));
+        assert_eq!(diff.other_encoded[2], DLE_CHAR);
+
+        // The padded encoded form starts with the unpadded one
+        let padded = DleEncoder {
+            pad_to: Some((8, 0)),
+            ..dle_encoder
+        };
+        let diff = dle_encoder.diff_against(&padded, &payload).unwrap();
+        assert_eq!(diff.first_difference, Some(5));
+        assert_eq!(diff.encoded.len(), 5);
+        assert_eq!(
+            dle_encoder.diff_against(&padded, &[0x55; 8]),
+            Err(DleError::FrameTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_trailing_policy() {
+        for escape_stx_etx in [true, false] {
+            let mut dle_encoder = DleEncoder {
+                escape_stx_etx,
+                ..Default::default()
+            };
+            let mut frame: [u8; 16] = [0; 16];
+            let frame_len = dle_encoder.encode(&TEST_ARRAY_4, &mut frame).unwrap();
+            let start_marker = dle_encoder.start_marker();
+            let trailers: [&[u8]; 5] = [
+                &[],
+                &[0x55, 0x66],
+                start_marker,
+                &start_marker[..1],
+                &frame[..frame_len],
+            ];
+            for policy in [
+                TrailingPolicy::Ignore,
+                TrailingPolicy::Error,
+                TrailingPolicy::RequireEmpty,
+            ] {
+                dle_encoder.trailing_policy = policy;
+                for trailer in trailers.iter() {
+                    let mut source: [u8; 32] = [0; 32];
+                    source[..frame_len].copy_from_slice(&frame[..frame_len]);
+                    source[frame_len..frame_len + trailer.len()].copy_from_slice(trailer);
+                    let source = &source[..frame_len + trailer.len()];
+                    let allowed = match policy {
+                        TrailingPolicy::Ignore => true,
+                        TrailingPolicy::Error => trailer != &[0x55, 0x66],
+                        TrailingPolicy::RequireEmpty => trailer.is_empty(),
+                    };
+
+                    let mut decoded: [u8; 16] = [0; 16];
+                    let mut read_len = 0;
+                    let result = dle_encoder.decode(source, &mut decoded, &mut read_len);
+                    if allowed {
+                        assert_eq!(result, Ok(TEST_ARRAY_4.len()));
+                    } else {
+                        assert_eq!(
+                            result,
+                            Err(DleError::decoding(
+                                DecodeErrorKind::TrailingBytes,
+                                frame_len
+                            ))
+                        );
+                    }
+                    // The frame is decoded and consumed in any case
+                    assert_frame_eq(&decoded[..TEST_ARRAY_4.len()], &TEST_ARRAY_4);
+                    assert_eq!(read_len, frame_len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pad_to() {
+        for (escape_stx_etx, fill) in [(true, 0x00), (true, ETX_CHAR), (false, ETX_CHAR)] {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx,
+                pad_to: Some((16, fill)),
+                trailing_policy: TrailingPolicy::RequireEmpty,
+                ..Default::default()
+            };
+            let payloads: [&[u8]; 3] = [&[], &TEST_ARRAY_4, &[fill; 4]];
+            let mut packets: [u8; 48] = [0; 48];
+            for (idx, payload) in payloads.iter().enumerate() {
+                let encoded_len = dle_encoder
+                    .encode(payload, &mut packets[idx * 16..])
+                    .unwrap();
+                assert_eq!(encoded_len, 16);
+                assert_eq!(packets[idx * 16 + 15], fill);
+            }
+
+            // Consecutive packets are decoded one after another
+            let mut source = &packets[..];
+            for payload in payloads.iter() {
+                let mut decoded: [u8; 16] = [0; 16];
+                let mut read_len = 0;
+                let packet_len = source.len().min(16);
+                let decoded_len = dle_encoder
+                    .decode(&source[..packet_len], &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], payload);
+                assert_eq!(read_len, 16);
+                source = &source[read_len..];
+            }
+
+            // Fill bytes are only skipped up to the padded length
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            let mut source: [u8; 17] = [fill; 17];
+            source[..16].copy_from_slice(&packets[..16]);
+            assert_eq!(
+                dle_encoder.decode(&source, &mut decoded, &mut read_len),
+                Err(DleError::decoding(DecodeErrorKind::TrailingBytes, 16))
+            );
+
+            // Frames exceeding the padded length and short destinations are rejected
+            let mut encoded: [u8; 32] = [0; 32];
+            assert_eq!(
+                dle_encoder.encode(&[0x55; 16], &mut encoded),
+                Err(DleError::FrameTooLarge)
+            );
+            assert_eq!(
+                dle_encoder.encode(&[0x55], &mut encoded[..15]),
+                Err(DleError::StreamTooShort)
+            );
+
+            // All encoders of complete frames write the same padded frame
+            let mut expected: [u8; 16] = [0; 16];
+            dle_encoder.encode(&TEST_ARRAY_4, &mut expected).unwrap();
+            encoded = [0; 32];
+            let (first, second) = encoded.split_at_mut(5);
+            assert_eq!(
+                dle_encoder.encode_scatter(&TEST_ARRAY_4, first, second),
+                Ok(16)
+            );
+            assert_eq!(&encoded[..16], &expected);
+            assert_eq!(
+                dle_encoder.encode_ranged(&TEST_ARRAY_4, 0..TEST_ARRAY_4.len(), &mut encoded),
+                Ok(16)
+            );
+            assert_eq!(&encoded[..16], &expected);
+            assert_eq!(
+                dle_encoder.encode_fragment(&TEST_ARRAY_4, FragmentRole::Whole, &mut encoded),
+                Ok(16)
+            );
+            assert_eq!(&encoded[..16], &expected);
+            let progress =
+                dle_encoder.encode_chunk(&TEST_ARRAY_4, &mut encoded, FragmentRole::Whole);
+            assert_eq!((progress.written, progress.complete), (16, true));
+            assert_eq!(&encoded[..16], &expected);
+
+            // Padded whole frames are not written partially into short destinations
+            let progress =
+                dle_encoder.encode_chunk(&TEST_ARRAY_4, &mut encoded[..15], FragmentRole::Whole);
+            assert_eq!((progress.written, progress.src_consumed), (0, 0));
+            assert!(!progress.complete);
+        }
+    }
+
+    #[test]
+    fn test_idle_on_empty() {
+        const IDLE: &[u8] = &[0xff, 0x00];
+        for escape_stx_etx in [true, false] {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx,
+                idle_on_empty: Some(IDLE),
+                ..Default::default()
+            };
+            let mut stream: [u8; 32] = [0; 32];
+            let mut stream_len = 0;
+            for _ in 0..2 {
+                let idle_len = dle_encoder.encode(&[], &mut stream[stream_len..]).unwrap();
+                assert_eq!(&stream[stream_len..stream_len + idle_len], IDLE);
+                stream_len += idle_len;
+            }
+            let frame_start = stream_len;
+            stream_len += dle_encoder
+                .encode(&TEST_ARRAY_4, &mut stream[stream_len..])
+                .unwrap();
+            #[cfg(feature = "alloc")]
+            assert_eq!(dle_encoder.encode_cow(&[]).unwrap().as_ref(), IDLE);
+            assert_eq!(
+                dle_encoder.encode(&[], &mut [0; 1]),
+                Err(DleError::StreamTooShort)
+            );
+
+            // Idle sequences before a frame are skipped and included in the read length
+            let stream = &stream[..stream_len];
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(stream, &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+            assert_eq!(read_len, stream_len);
+            assert_eq!(dle_encoder.decoded_len(stream), Ok(TEST_ARRAY_4.len()));
+            assert!(dle_encoder.looks_encoded(stream));
+            let (_, raw) = dle_encoder.decode_with_raw(stream, &mut decoded).unwrap();
+            assert_eq!(raw, &stream[frame_start..]);
+            assert_eq!(
+                dle_encoder.decode(stream, &mut decoded[..1], &mut read_len),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(read_len, 0);
+
+            // Error offsets are relative to the start of the source stream
+            assert_eq!(
+                dle_encoder.decode(&stream[..frame_start], &mut decoded, &mut read_len),
+                Err(DleError::decoding(
+                    DecodeErrorKind::MissingStart,
+                    frame_start
+                ))
+            );
+            assert_eq!(read_len, frame_start);
+            assert_eq!(
+                dle_encoder.decode(&stream[..stream_len - 1], &mut decoded, &mut read_len),
+                Err(DleError::decoding(
+                    DecodeErrorKind::MissingEnd,
+                    stream_len - 1
+                ))
+            );
+            assert_eq!(read_len, stream_len - 1);
+
+            // All encoders of complete frames write the idle sequence for empty payloads
+            let mut encoded: [u8; 8] = [0; 8];
+            let (first, second) = encoded.split_at_mut(1);
+            assert_eq!(
+                dle_encoder.encode_scatter(&[], first, second),
+                Ok(IDLE.len())
+            );
+            assert_eq!(&encoded[..IDLE.len()], IDLE);
+            encoded = [0; 8];
+            assert_eq!(
+                dle_encoder.encode_ranged(&[], 0..0, &mut encoded),
+                Ok(IDLE.len())
+            );
+            assert_eq!(&encoded[..IDLE.len()], IDLE);
+            encoded = [0; 8];
+            assert_eq!(
+                dle_encoder.encode_fragment(&[], FragmentRole::Whole, &mut encoded),
+                Ok(IDLE.len())
+            );
+            assert_eq!(&encoded[..IDLE.len()], IDLE);
+            encoded = [0; 8];
+            let progress = dle_encoder.encode_chunk(&[], &mut encoded, FragmentRole::Whole);
+            assert_eq!((progress.written, progress.complete), (IDLE.len(), true));
+            assert_eq!(&encoded[..IDLE.len()], IDLE);
+
+            // Idle sequences between and after frames are skipped when decoding batches
+            let mut batch: [u8; 32] = [0; 32];
+            let mut batch_len = 0;
+            for payload in [&[0x01][..], &[], &TEST_ARRAY_4, &[]] {
+                batch_len += dle_encoder
+                    .encode(payload, &mut batch[batch_len..])
+                    .unwrap();
+            }
+            let batch = &batch[..batch_len];
+            assert_eq!(dle_encoder.validate_batch(batch), Ok(2));
+            let mut frame_count = 0;
+            assert_eq!(
+                dle_encoder.for_each_frame(batch, &mut decoded, |_| frame_count += 1),
+                Ok(2)
+            );
+            assert_eq!(frame_count, 2);
+            let decoded_len = dle_encoder.decode_concat(batch, &mut decoded).unwrap();
+            assert_eq!(
+                &decoded[..decoded_len],
+                &[0x01, DLE_CHAR, ETX_CHAR, STX_CHAR]
+            );
+            let error = Err(DleError::decoding(DecodeErrorKind::MissingStart, batch_len));
+            let mut batch_with_garbage = batch.to_vec();
+            batch_with_garbage.extend_from_slice(&[0xff, 0x55]);
+            assert_eq!(dle_encoder.validate_batch(&batch_with_garbage), error);
+
+            // Idle sequences may precede the next frame with the trailing policy Error
+            let strict_encoder = DleEncoder {
+                trailing_policy: TrailingPolicy::Error,
+                ..dle_encoder
+            };
+            assert!(strict_encoder
+                .decode(batch, &mut decoded, &mut read_len)
+                .is_ok());
+            assert_eq!(strict_encoder.decode_concat(batch, &mut decoded), Ok(4));
+        }
+
+        // Idle sequences can not contain control characters which start frames or escapes
+        for escape_stx_etx in [true, false] {
+            for (idle, byte) in [(&[0xff, STX_CHAR][..], STX_CHAR), (&[DLE_CHAR], DLE_CHAR)] {
+                let dle_encoder = DleEncoder {
+                    escape_stx_etx,
+                    idle_on_empty: Some(idle),
+                    ..Default::default()
+                };
+                let error = ConfigError::IdleConflict { byte };
+                assert_eq!(dle_encoder.validate_config(), Err(error));
+                assert_eq!(dle_encoder.assert_lossless(), Err(error));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lenient_stx() {
+        for escape_stx_etx in [true, false] {
+            let mut dle_encoder = DleEncoder {
+                escape_stx_etx,
+                lenient_stx: true,
+                ..Default::default()
+            };
+            let stx_idx = usize::from(!escape_stx_etx);
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&TEST_ARRAY_4, &mut encoded).unwrap();
+            let mut decoded: [u8; 16] = [0; 16];
+            let mut read_len = 0;
+
+            // All single bit flips except the one resulting in ETX are recovered
+            for bit in 1..8 {
+                let mut corrupted = encoded;
+                corrupted[stx_idx] ^= 1 << bit;
+                let corrupted = &corrupted[..encoded_len];
+                let decoded_len = dle_encoder
+                    .decode(corrupted, &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_frame_eq(&decoded[..decoded_len], &TEST_ARRAY_4);
+                assert_eq!(read_len, encoded_len);
+                assert_eq!(dle_encoder.decoded_len(corrupted), Ok(TEST_ARRAY_4.len()));
+                let mut bytes = corrupted.iter().copied();
+                assert_eq!(
+                    dle_encoder.decode_pull(|| bytes.next(), &mut decoded),
+                    Ok(TEST_ARRAY_4.len())
+                );
+            }
+
+            // Two bit flips and terminators are rejected
+            let missing_start = Err(DleError::decoding(DecodeErrorKind::MissingStart, stx_idx));
+            for corrupted_stx in [STX_CHAR ^ 0x05, STX_CHAR ^ 0x30, ETX_CHAR] {
+                let mut corrupted = encoded;
+                corrupted[stx_idx] = corrupted_stx;
+                let corrupted = &corrupted[..encoded_len];
+                assert_eq!(
+                    dle_encoder.decode(corrupted, &mut decoded, &mut read_len),
+                    missing_start
+                );
+                assert_eq!(dle_encoder.decoded_len(corrupted), missing_start);
+            }
+
+            // Without the option, only an exact STX starts a frame
+            dle_encoder.lenient_stx = false;
+            let mut corrupted = encoded;
+            corrupted[stx_idx] ^= 0x40;
+            assert_eq!(
+                dle_encoder.decode(&corrupted[..encoded_len], &mut decoded, &mut read_len),
+                missing_start
+            );
+        }
+    }
+
+    #[test]
+    fn test_lenient_dle() {
+        let mut dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            ..Default::default()
+        };
+        let encoded = [
+            DLE_CHAR, STX_CHAR, 0x01, DLE_CHAR, 0x55, DLE_CHAR, DLE_CHAR, DLE_CHAR, ETX_CHAR,
+        ];
+        let mut decoded: [u8; 8] = [0; 8];
+        let mut read_len = 0;
+        let invalid_escape = Err(DleError::decoding(
+            DecodeErrorKind::InvalidDleSequence { found: 0x55 },
+            4,
+        ));
+        assert_eq!(
+            dle_encoder.decode(&encoded, &mut decoded, &mut read_len),
+            invalid_escape
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_3,
-            TEST_ARRAY_3_ENCODED_ESCPAED,
-            &mut buffer,
+        assert_eq!(dle_encoder.decoded_len(&encoded), invalid_escape);
+
+        // The unknown escape sequence is decoded as the character following the DLE
+        dle_encoder.lenient_dle = true;
+        let expected = [0x01, 0x55, DLE_CHAR];
+        let decoded_len = dle_encoder
+            .decode(&encoded, &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &expected);
+        assert_eq!(read_len, encoded.len());
+        assert_eq!(dle_encoder.decoded_len(&encoded), Ok(expected.len()));
+        let mut bytes = encoded.iter().copied();
+        let decoded_len = dle_encoder
+            .decode_pull(|| bytes.next(), &mut decoded)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &expected);
+        let mut byte_decoder = ByteAtATimeDecoder::<8>::new(dle_encoder);
+        for &byte in &encoded[..encoded.len() - 1] {
+            assert_eq!(byte_decoder.push_byte(byte), DecodePush::More);
+        }
+        assert_eq!(
+            byte_decoder.push_byte(ETX_CHAR),
+            DecodePush::Frame(expected.len())
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_4,
-            TEST_ARRAY_4_ENCODED_ESCPAED,
-            &mut buffer,
+        assert_frame_eq(byte_decoder.frame(), &expected);
+
+        // The escaped mode still rejects unknown escape sequences
+        dle_encoder.escape_stx_etx = true;
+        assert_eq!(
+            dle_encoder.decode(
+                &[STX_CHAR, 0x01, DLE_CHAR, 0x55, ETX_CHAR],
+                &mut decoded,
+                &mut read_len
+            ),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x55 },
+                3
+            ))
         );
+    }
 
-        dle_encoder.escape_stx_etx = false;
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_0,
-            TEST_ARRAY_0_ENCODED_NON_ESCPAED,
-            &mut buffer,
+    #[test]
+    fn test_etx_terminates_only_after_dle() {
+        let dle_encoder = DleEncoder {
+            etx_terminates_only_after_dle: true,
+            ..Default::default()
+        };
+        assert_ne!(
+            dle_encoder.config_fingerprint(),
+            DleEncoder::default().config_fingerprint()
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_1,
-            &TEST_ARRAY_1_ENCODED_NON_ESCPAED,
-            &mut buffer,
+        let payload = [0x01, ETX_CHAR, ETX_CHAR, DLE_CHAR, STX_CHAR, 0x55, ETX_CHAR];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        assert_frame_eq(
+            &encoded[..encoded_len],
+            &[
+                STX_CHAR, 0x01, ETX_CHAR, ETX_CHAR, DLE_CHAR, DLE_CHAR, DLE_CHAR, 0x42, 0x55,
+                ETX_CHAR, DLE_CHAR, ETX_CHAR,
+            ],
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_2,
-            TEST_ARRAY_2_ENCODED_NON_ESCPAED,
-            &mut buffer,
+        let mut single: [u8; 4] = [0; 4];
+        let single_len = dle_encoder.encode_one(ETX_CHAR, &mut single).unwrap();
+        assert_eq!(
+            &single[..single_len],
+            &[STX_CHAR, ETX_CHAR, DLE_CHAR, ETX_CHAR]
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_3,
-            TEST_ARRAY_3_ENCODED_NON_ESCPAED,
-            &mut buffer,
+
+        encoded[encoded_len] = 0x55;
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len + 1], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(read_len, encoded_len);
+        // The destination is full when the terminator sequence is reached
+        let decoded_len = dle_encoder
+            .decode(
+                &encoded[..encoded_len],
+                &mut decoded[..payload.len()],
+                &mut read_len,
+            )
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(
+            dle_encoder.decode(
+                &encoded[..encoded_len],
+                &mut decoded[..payload.len() - 1],
+                &mut read_len
+            ),
+            Err(DleError::StreamTooShort)
         );
-        test_encode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_4,
-            &TEST_ARRAY_4_ENCODED_NON_ESCPAED,
-            &mut buffer,
+
+        // The terminator is kept and escaped ETX characters are still decoded
+        let keep_terminator = DleEncoder {
+            keep_terminator: true,
+            ..dle_encoder
+        };
+        let decoded_len = keep_terminator
+            .decode(
+                &[STX_CHAR, DLE_CHAR, 0x43, ETX_CHAR, DLE_CHAR, ETX_CHAR],
+                &mut decoded,
+                &mut read_len,
+            )
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[ETX_CHAR, ETX_CHAR, ETX_CHAR]);
+        assert_eq!(read_len, 6);
+
+        // A bare terminator at the end does not complete the frame
+        let error = dle_encoder
+            .decode(&encoded[..encoded_len - 2], &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingEnd));
+        let error = dle_encoder
+            .decode(&encoded[..encoded_len - 1], &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(error.decode_kind(), Some(DecodeErrorKind::MissingEnd));
+
+        // Decoders without the setting end the frame at the first bare ETX
+        let decoded_len = DleEncoder::default()
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[0x01]);
+
+        // All other decoders only end the frame at the DLE ETX sequence as well
+        let frame = &encoded[..encoded_len];
+        let mut bytes = frame.iter().copied();
+        let decoded_len = dle_encoder
+            .decode_pull(|| bytes.next(), &mut decoded)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(dle_encoder.decoded_len(frame), Ok(payload.len()));
+        assert!(dle_encoder.looks_encoded(frame));
+        assert_eq!(dle_encoder.validate_batch(frame), Ok(1));
+        assert_eq!(
+            dle_encoder.strip_framing(frame),
+            Ok(&frame[1..encoded_len - 2])
         );
+        assert_eq!(
+            dle_encoder.split_first_frame(&encoded[..encoded_len + 1]),
+            Ok((frame, &encoded[encoded_len..encoded_len + 1]))
+        );
+        let decoded_len = dle_encoder
+            .decode_escaped_body(&frame[1..], &mut decoded)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        let mut byte_decoder = ByteAtATimeDecoder::<16>::new(dle_encoder);
+        let pushed = frame
+            .iter()
+            .map(|&byte| byte_decoder.push_byte(byte))
+            .find(|push| *push != DecodePush::More);
+        assert_eq!(pushed, Some(DecodePush::Frame(payload.len())));
+        assert_frame_eq(byte_decoder.frame(), &payload);
+        let mut ring: [u8; 32] = [0; 32];
+        let mut ring_decoder = RingDecoder::new(dle_encoder, &mut ring);
+        ring_decoder.push(frame);
+        let decoded_len = ring_decoder
+            .poll_frame(&mut decoded)
+            .unwrap()
+            .unwrap()
+            .len();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        #[cfg(feature = "alloc")]
+        {
+            assert!(dle_encoder.is_reversible(&payload));
+            let chunks: Vec<u8> = dle_encoder
+                .decode_chunks(frame)
+                .unwrap()
+                .flatten()
+                .copied()
+                .collect();
+            assert_frame_eq(&chunks, &payload);
+            let typed = dle_encoder.decode_frame_typed(frame).unwrap();
+            assert_frame_eq(&typed.payload, &payload);
+            assert_eq!(typed.source_range, 0..encoded_len);
+            assert_eq!(dle_encoder.frame_ranges(frame), vec![0..encoded_len]);
+            let mut stream_decoder = StreamDecoder::new(dle_encoder);
+            stream_decoder.push(frame);
+            assert_frame_eq(&stream_decoder.next_frame().unwrap().unwrap(), &payload);
+            let text = [STX_CHAR, b'o', ETX_CHAR, b'k', DLE_CHAR, ETX_CHAR];
+            assert_eq!(dle_encoder.decode_to_string(&text).unwrap(), "o\u{3}k");
+        }
+        #[cfg(feature = "std")]
+        {
+            let mut reader = DleReader::new(dle_encoder, frame);
+            assert_frame_eq(reader.next_frame().unwrap().unwrap(), &payload);
+        }
+    }
 
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_0,
-            TEST_ARRAY_0_ENCODED_ESCPAED,
-            &mut buffer,
+    #[test]
+    fn test_dle_escape_style() {
+        let dle_encoder = DleEncoder {
+            dle_escape_style: DleStyle::Offset,
+            ..Default::default()
+        };
+        assert_eq!(dle_encoder.validate_config(), Ok(()));
+        let payload = [DLE_CHAR, 0x55, DLE_CHAR, DLE_CHAR, STX_CHAR, 0x50];
+        let mut encoded: [u8; 32] = [0; 32];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        assert_frame_eq(
+            &encoded[..encoded_len],
+            &[
+                STX_CHAR, DLE_CHAR, 0x50, 0x55, DLE_CHAR, 0x50, DLE_CHAR, 0x50, DLE_CHAR, 0x42,
+                0x50, ETX_CHAR,
+            ],
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_1,
-            &TEST_ARRAY_1_ENCODED_ESCPAED,
-            &mut buffer,
+        let mut decoded: [u8; 16] = [0; 16];
+        let mut read_len = 0;
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
+        assert_eq!(read_len, encoded_len);
+
+        // Doubled DLE characters are still accepted
+        let decoded_len = dle_encoder
+            .decode(
+                &[STX_CHAR, DLE_CHAR, DLE_CHAR, DLE_CHAR, 0x50, ETX_CHAR],
+                &mut decoded,
+                &mut read_len,
+            )
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[DLE_CHAR, DLE_CHAR]);
+
+        // The escaped form of DLE is rejected with the default style
+        let error = DleEncoder::default()
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap_err();
+        assert_eq!(
+            error.decode_kind(),
+            Some(DecodeErrorKind::InvalidDleSequence { found: 0x50 })
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_2,
-            TEST_ARRAY_2_ENCODED_ESCPAED,
-            &mut buffer,
+
+        // The non-escaped mode always doubles DLE characters
+        let non_escaped = DleEncoder {
+            escape_stx_etx: false,
+            ..dle_encoder
+        };
+        let encoded_len = non_escaped.encode(&[DLE_CHAR], &mut encoded).unwrap();
+        assert_frame_eq(
+            &encoded[..encoded_len],
+            &[DLE_CHAR, STX_CHAR, DLE_CHAR, DLE_CHAR, DLE_CHAR, ETX_CHAR],
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_3,
-            TEST_ARRAY_3_ENCODED_ESCPAED,
-            &mut buffer,
+
+        let xor_zero = DleEncoder {
+            escape_scheme: EscapeScheme::Xor(0),
+            ..dle_encoder
+        };
+        assert_eq!(
+            xor_zero.validate_config(),
+            Err(ConfigError::ControlCharEscape { byte: STX_CHAR })
         );
-        test_faulty_encoding(
-            &dle_encoder,
-            &TEST_ARRAY_4,
-            TEST_ARRAY_4_ENCODED_ESCPAED,
-            &mut buffer,
+        assert_ne!(
+            dle_encoder.config_fingerprint(),
+            DleEncoder::default().config_fingerprint()
         );
     }
 
     #[test]
-    fn test_decoder() {
-        let mut dle_encoder = DleEncoder::default();
-        let mut buffer: [u8; 32] = [0; 32];
-        let test_decode_closure = |dle_encoder: &DleEncoder,
-                                   encoded_test_vec: &[u8],
-                                   expected_buf: &[u8],
-                                   buffer: &mut [u8]| {
+    fn test_trailing_cr() {
+        let payload: [u8; 3] = [0x01, 0x02, CR_CHAR];
+        let cases: [(bool, bool, &[u8]); 4] = [
+            (
+                true,
+                true,
+                &[STX_CHAR, 0x01, DLE_CHAR, 0x42, DLE_CHAR, 0x4d, ETX_CHAR],
+            ),
+            (
+                true,
+                false,
+                &[STX_CHAR, 0x01, DLE_CHAR, 0x42, CR_CHAR, ETX_CHAR],
+            ),
+            (
+                false,
+                true,
+                &[DLE_CHAR, STX_CHAR, 0x01, 0x02, CR_CHAR, DLE_CHAR, ETX_CHAR],
+            ),
+            (
+                false,
+                false,
+                &[DLE_CHAR, STX_CHAR, 0x01, 0x02, CR_CHAR, DLE_CHAR, ETX_CHAR],
+            ),
+        ];
+        for (escape_stx_etx, escape_cr, expected) in cases.iter() {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx: *escape_stx_etx,
+                escape_cr: *escape_cr,
+                ..Default::default()
+            };
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            assert_frame_eq(&encoded[..encoded_len], expected);
+
+            let mut decoded: [u8; 16] = [0; 16];
             let mut read_len = 0;
-            let decode_res = dle_encoder.decode(encoded_test_vec, buffer, &mut read_len);
-            assert!(decode_res.is_ok());
-            for (idx, byte) in expected_buf.iter().enumerate() {
-                assert_eq!(buffer[idx], *byte);
+            let decoded_len = dle_encoder
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+            assert_eq!(read_len, encoded_len);
+
+            // The byte at a time encoder and the pull decoder agree
+            let mut byte_encoder = ByteAtATimeEncoder::new(dle_encoder);
+            let mut idx = 0;
+            while let Some(byte) = byte_encoder.next_byte(&payload) {
+                assert_eq!(byte, encoded[idx]);
+                idx += 1;
             }
-            assert_eq!(read_len, encoded_test_vec.len());
-            assert_eq!(decode_res.unwrap(), expected_buf.len());
-        };
+            assert_eq!(idx, encoded_len);
+            let mut source = encoded[..encoded_len].iter().copied();
+            assert_eq!(
+                dle_encoder.decode_pull(|| source.next(), &mut decoded),
+                Ok(payload.len())
+            );
+        }
+    }
 
-        let test_faulty_decoding =
-            |dle_encoder: &DleEncoder, faulty_encoded_buf: &[u8], buffer: &mut [u8]| {
-                let mut read_len = 0;
-                let decode_res = dle_encoder.decode(&faulty_encoded_buf, buffer, &mut read_len);
-                assert!(decode_res.is_err());
-                assert_eq!(decode_res.unwrap_err(), DleError::DecodingError);
+    #[test]
+    fn test_boundary_arithmetic() {
+        let payload = [0x11, DLE_CHAR, STX_CHAR, ETX_CHAR, CR_CHAR, 0x22];
+        for escape_stx_etx in [true, false] {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx,
+                resync_interval: Some(2),
+                ..Default::default()
             };
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            // Every truncation of the frame and every destination length hits the boundaries
+            // of the index arithmetic. Errors must be returned instead of panicking
+            for source_len in 0..encoded_len {
+                let source = &encoded[..source_len];
+                for dest_len in 0..=payload.len() {
+                    let mut decoded = [0; 8];
+                    let decoded = &mut decoded[..dest_len];
+                    let mut read_len = 0;
+                    let error = dle_encoder
+                        .decode(source, decoded, &mut read_len)
+                        .unwrap_err();
+                    assert!(read_len <= source_len);
+                    assert!(error.offset().unwrap_or(0) <= source_len);
+                    if escape_stx_etx {
+                        for bulk_copy in [true, false] {
+                            assert!(dle_encoder
+                                .decode_escaped_generic(source, decoded, &mut read_len, bulk_copy)
+                                .is_err());
+                            assert!(read_len <= source_len);
+                        }
+                    }
+                    let mut bytes = source.iter().copied();
+                    assert!(dle_encoder.decode_pull(|| bytes.next(), decoded).is_err());
+                    assert!(dle_encoder
+                        .decode_escaped_body(source.get(1..).unwrap_or(&[]), decoded)
+                        .is_err());
+                    assert!(dle_encoder.decode_typed(source, decoded).is_err());
+                }
+                assert!(dle_encoder.decoded_len(source).is_err());
+                // An empty source stream contains no frames
+                assert_eq!(
+                    dle_encoder
+                        .for_each_frame(source, &mut [0; 8], |_| ())
+                        .is_err(),
+                    source_len > 0
+                );
+            }
+            let mut decoded: [u8; 8] = [0; 8];
+            let mut read_len = 0;
+            assert_eq!(
+                dle_encoder.decode(&encoded[..encoded_len], &mut decoded, &mut read_len),
+                Ok(payload.len())
+            );
+            assert_eq!(read_len, encoded_len);
+        }
 
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_0_ENCODED_ESCPAED,
-            &TEST_ARRAY_0,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_1_ENCODED_ESCPAED,
-            &TEST_ARRAY_1,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_2_ENCODED_ESCPAED,
-            &TEST_ARRAY_2,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_3_ENCODED_ESCPAED,
-            &TEST_ARRAY_3,
-            &mut buffer,
+        // Error offsets of headerless bodies never underflow
+        let dle_encoder = DleEncoder::default();
+        let mut decoded: [u8; 8] = [0; 8];
+        assert_eq!(
+            dle_encoder.decode_escaped_body(&[], &mut decoded),
+            Err(DleError::decoding(DecodeErrorKind::MissingEnd, 0))
         );
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_4_ENCODED_ESCPAED,
-            &TEST_ARRAY_4,
-            &mut buffer,
+        assert_eq!(
+            dle_encoder.decode_escaped_body(&[STX_CHAR], &mut decoded),
+            Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, 0))
         );
+    }
 
-        dle_encoder.escape_stx_etx = false;
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_0_ENCODED_NON_ESCPAED,
-            &TEST_ARRAY_0,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_1_ENCODED_NON_ESCPAED,
-            &TEST_ARRAY_1,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_2_ENCODED_NON_ESCPAED,
-            &TEST_ARRAY_2,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            TEST_ARRAY_3_ENCODED_NON_ESCPAED,
-            &TEST_ARRAY_3,
-            &mut buffer,
-        );
-        test_decode_closure(
-            &dle_encoder,
-            &TEST_ARRAY_4_ENCODED_NON_ESCPAED,
-            &TEST_ARRAY_4,
-            &mut buffer,
+    #[test]
+    fn test_early_failure_read_len() {
+        let mut decoded: [u8; 8] = [0; 8];
+        let cases: [(bool, &[u8], DleError, usize); 11] = [
+            (
+                true,
+                &[],
+                DleError::decoding(DecodeErrorKind::MissingStart, 0),
+                0,
+            ),
+            (
+                true,
+                &[0x55, STX_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingStart, 0),
+                0,
+            ),
+            (
+                true,
+                &[STX_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 1),
+                1,
+            ),
+            (
+                true,
+                &[STX_CHAR, DLE_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 2),
+                2,
+            ),
+            (
+                true,
+                &[STX_CHAR, DLE_CHAR, 0x42],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 3),
+                3,
+            ),
+            (
+                false,
+                &[],
+                DleError::decoding(DecodeErrorKind::MissingStart, 0),
+                0,
+            ),
+            (
+                false,
+                &[STX_CHAR, DLE_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingStart, 0),
+                0,
+            ),
+            (
+                false,
+                &[DLE_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 1),
+                1,
+            ),
+            (
+                false,
+                &[DLE_CHAR, 0x55],
+                DleError::decoding(DecodeErrorKind::MissingStart, 1),
+                1,
+            ),
+            (
+                false,
+                &[DLE_CHAR, STX_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 2),
+                2,
+            ),
+            (
+                false,
+                &[DLE_CHAR, STX_CHAR, DLE_CHAR],
+                DleError::decoding(DecodeErrorKind::MissingEnd, 3),
+                3,
+            ),
+        ];
+        for (escape_stx_etx, source, error, expected_read_len) in cases {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx,
+                ..Default::default()
+            };
+            let mut read_len = usize::MAX;
+            let decode_error = dle_encoder
+                .decode(source, &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(decode_error, error);
+            assert_eq!(read_len, expected_read_len);
+            assert_eq!(dle_encoder.decoded_len(source).unwrap_err(), decode_error);
+        }
+    }
+
+    #[test]
+    fn test_non_escaped_raw_stx() {
+        let dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            ..Default::default()
+        };
+        let payloads: [&[u8]; 5] = [
+            &[STX_CHAR],
+            &[STX_CHAR, STX_CHAR, 0x11],
+            &[0x11, STX_CHAR, ETX_CHAR, STX_CHAR],
+            &[DLE_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR],
+            &[ETX_CHAR, DLE_CHAR, DLE_CHAR, STX_CHAR],
+        ];
+        for payload in payloads.iter() {
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+            let mut decoded: [u8; 32] = [0; 32];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], payload);
+            assert_eq!(read_len, encoded_len);
+        }
+    }
+
+    #[test]
+    fn test_strict_non_escaped() {
+        let mut dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            strict_non_escaped: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            dle_encoder.assert_lossless(),
+            Err(ConfigError::StrictNonEscaped)
         );
+        let mut encoded: [u8; 32] = [0; 32];
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
 
-        let mut test_array_1_encoded_faulty = TEST_ARRAY_1_ENCODED_NON_ESCPAED.clone();
-        let mut prev_val = test_array_1_encoded_faulty[0];
-        test_array_1_encoded_faulty[0] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+        // Doubled DLE characters are still accepted
+        let payload = [0x11, DLE_CHAR, 0x22];
+        let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &payload);
 
-        test_array_1_encoded_faulty[0] = prev_val;
-        prev_val = test_array_1_encoded_faulty[1];
-        test_array_1_encoded_faulty[1] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+        for payload in [[0x11, STX_CHAR, 0x22], [0x11, ETX_CHAR, 0x22]] {
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let encoded = &encoded[..encoded_len];
+            let error = || DleError::decoding(DecodeErrorKind::RawControlChar, 3);
+            assert_eq!(
+                dle_encoder.decode(encoded, &mut decoded, &mut read_len),
+                Err(error())
+            );
+            assert_eq!(read_len, 3);
+            assert_eq!(dle_encoder.decoded_len(encoded), Err(error()));
+            let mut bytes = encoded.iter().copied();
+            assert_eq!(
+                dle_encoder.decode_pull(|| bytes.next(), &mut decoded),
+                Err(error())
+            );
+            let mut byte_decoder = ByteAtATimeDecoder::<32>::new(dle_encoder);
+            for &byte in &encoded[..3] {
+                assert_eq!(byte_decoder.push_byte(byte), DecodePush::More);
+            }
+            assert_eq!(
+                byte_decoder.push_byte(encoded[3]),
+                DecodePush::Error(error())
+            );
+        }
 
-        test_array_1_encoded_faulty[1] = prev_val;
-        prev_val = test_array_1_encoded_faulty[6];
-        test_array_1_encoded_faulty[6] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+        // Raw STX and ETX characters are only data in the escaped mode
+        dle_encoder.escape_stx_etx = true;
+        assert_eq!(dle_encoder.assert_lossless(), Ok(()));
+        let encoded_len = dle_encoder
+            .encode(&[STX_CHAR, ETX_CHAR], &mut encoded)
+            .unwrap();
+        let decoded_len = dle_encoder
+            .decode(&encoded[..encoded_len], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[STX_CHAR, ETX_CHAR]);
+    }
 
-        test_array_1_encoded_faulty[6] = prev_val;
-        test_array_1_encoded_faulty[7] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+    #[test]
+    fn test_non_escaped_unexpected_start() {
+        let dle_encoder = DleEncoder {
+            escape_stx_etx: false,
+            ..Default::default()
+        };
+        let mut decoded: [u8; 32] = [0; 32];
+        let mut read_len = 0;
 
-        let mut test_array_4_encoded_faulty = TEST_ARRAY_4_ENCODED_NON_ESCPAED.clone();
-        test_array_4_encoded_faulty[3] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_4_encoded_faulty, &mut buffer);
+        // The end of the first frame was lost, but the second frame is complete
+        let stream = [
+            DLE_CHAR, STX_CHAR, 0x11, STX_CHAR, DLE_CHAR, STX_CHAR, 0x22, STX_CHAR, DLE_CHAR,
+            ETX_CHAR,
+        ];
+        assert_eq!(
+            dle_encoder.decode(&stream, &mut decoded, &mut read_len),
+            Err(DleError::decoding(DecodeErrorKind::UnexpectedStart, 4))
+        );
+        assert_eq!(read_len, 4);
+        let decoded_len = dle_encoder
+            .decode(&stream[read_len..], &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[0x22, STX_CHAR]);
 
-        dle_encoder.escape_stx_etx = true;
-        let mut test_array_1_encoded_faulty = TEST_ARRAY_1_ENCODED_ESCPAED.clone();
-        prev_val = test_array_1_encoded_faulty[3];
-        test_array_1_encoded_faulty[3] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+        // A corrupted escape sequence is reported distinctly
+        let stream = [
+            DLE_CHAR, STX_CHAR, 0x11, DLE_CHAR, 0x12, 0x22, DLE_CHAR, ETX_CHAR,
+        ];
+        assert_eq!(
+            dle_encoder.decode(&stream, &mut decoded, &mut read_len),
+            Err(DleError::decoding(
+                DecodeErrorKind::InvalidDleSequence { found: 0x12 },
+                4
+            ))
+        );
 
-        test_array_1_encoded_faulty[3] = prev_val;
-        prev_val = test_array_1_encoded_faulty[0];
-        test_array_1_encoded_faulty[0] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+        // A doubled DLE followed by STX is data and not a start marker
+        let stream = [
+            DLE_CHAR, STX_CHAR, DLE_CHAR, DLE_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR,
+        ];
+        let decoded_len = dle_encoder
+            .decode(&stream, &mut decoded, &mut read_len)
+            .unwrap();
+        assert_frame_eq(&decoded[..decoded_len], &[DLE_CHAR, STX_CHAR]);
+    }
 
-        test_array_1_encoded_faulty[0] = prev_val;
-        prev_val = test_array_1_encoded_faulty[5];
-        test_array_1_encoded_faulty[5] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_resync_interval() {
+        // Contains all special characters, including SYN as regular payload byte
+        let payload: Vec<u8> = (0..200).map(|idx| (idx * 7 % 0x20) as u8).collect();
+        for escape_stx_etx in [true, false] {
+            let dle_encoder = DleEncoder {
+                escape_stx_etx,
+                resync_interval: Some(16),
+                ..Default::default()
+            };
+            assert_eq!(dle_encoder.validate_config(), Ok(()));
+            let mut encoded = vec![0; dle_encoder.max_encoded_len(payload.len())];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let encoded = &encoded[..encoded_len];
+            let markers = encoded
+                .windows(2)
+                .filter(|window| window == &[DLE_CHAR, SYN_CHAR])
+                .count();
+            assert_eq!(markers, 12);
 
-        test_array_1_encoded_faulty[5] = prev_val;
-        test_array_1_encoded_faulty[2] = 0;
-        test_faulty_decoding(&dle_encoder, &test_array_1_encoded_faulty, &mut buffer);
+            let mut decoded = vec![0; payload.len()];
+            let mut read_len = 0;
+            let decoded_len = dle_encoder
+                .decode(encoded, &mut decoded, &mut read_len)
+                .unwrap();
+            assert_frame_eq(&decoded[..decoded_len], &payload);
+            assert_eq!(read_len, encoded_len);
+            assert_eq!(dle_encoder.decoded_len(encoded), Ok(payload.len()));
+            let mut source = encoded.iter().copied();
+            assert_eq!(
+                dle_encoder.decode_pull(|| source.next(), &mut decoded),
+                Ok(payload.len())
+            );
+            let mut byte_decoder = ByteAtATimeDecoder::<256>::new(dle_encoder);
+            let pushes: Vec<DecodePush> = encoded
+                .iter()
+                .map(|&byte| byte_decoder.push_byte(byte))
+                .collect();
+            assert_eq!(pushes.last(), Some(&DecodePush::Frame(payload.len())));
+            assert_frame_eq(byte_decoder.frame(), &payload);
 
-        let mut decoding_buffer: [u8; 16] = [0; 16];
-        let encoded_array: [u8; 4] = [0x02, 0x10, 0x02 + 0x40, 0x03];
-        let mut read_len = 0;
-        let decode_result = dle_encoder.decode(&encoded_array, &mut decoding_buffer, &mut read_len);
-        assert!(decode_result.is_ok());
-        let decoded_len = decode_result.unwrap();
-        assert_eq!(decoded_len, 1);
+            // No marker is written after the last payload byte
+            let mut exact = [0; 64];
+            let exact_len = dle_encoder.encode(&[0x01; 32], &mut exact).unwrap();
+            assert_eq!(exact_len, 32 + 2 + dle_encoder.start_marker().len() * 2);
+
+            // Without the setting, the markers are rejected
+            let strict_encoder = DleEncoder {
+                resync_interval: None,
+                ..dle_encoder
+            };
+            let error = strict_encoder
+                .decode(encoded, &mut decoded, &mut read_len)
+                .unwrap_err();
+            assert_eq!(
+                error.decode_kind(),
+                Some(DecodeErrorKind::InvalidDleSequence { found: 0x16 })
+            );
+            let first_marker = encoded
+                .windows(2)
+                .position(|window| window == [DLE_CHAR, SYN_CHAR])
+                .unwrap();
+            assert_eq!(error.offset(), Some(first_marker + 1));
+        }
+
+        // The escaped form of a byte must not be SYN if markers are enabled
+        let dle_encoder = DleEncoder {
+            escape_extra: ByteSet::from_bytes(&[SYN_CHAR + 0xc0]),
+            resync_interval: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(
+            dle_encoder.validate_config(),
+            Err(ConfigError::ControlCharEscape {
+                byte: SYN_CHAR + 0xc0
+            })
+        );
     }
 }
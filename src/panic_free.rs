@@ -0,0 +1,257 @@
+//! Adversarial inputs for all public encoding and decoding methods.
+//!
+//! The methods must return errors instead of panicking for malformed frames, truncated
+//! escape sequences and destination streams which are too short. Each input is passed to
+//! every method with a range of encoder configurations and destination lengths. The results
+//! are not checked, only the absence of panics.
+use crate::{
+    ByteSet, DecodeErrorKind, DleEncoder, DleError, DleFrameWriter, DleStyle, FieldFramer,
+    FragmentRole, TrailingPolicy, CR_CHAR, DLE_CHAR, ETX_CHAR, STX_CHAR, SYN_CHAR, US_CHAR,
+};
+use core::fmt::{self, Write};
+use core::ops::Range;
+
+const DEST_LENS: [usize; 5] = [0, 1, 2, 5, 64];
+
+/// Writer which discards everything written to it
+struct Discard;
+
+impl fmt::Write for Discard {
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+fn configs() -> impl Iterator<Item = DleEncoder> {
+    let presets = [
+        DleEncoder::default(),
+        DleEncoder::escaped(),
+        DleEncoder::non_escaped(),
+        DleEncoder::raw_escaped_no_framing(),
+        DleEncoder::text_safe(),
+    ];
+    IntoIterator::into_iter(presets).flat_map(|preset| {
+        let variants: [fn(&mut DleEncoder); 13] = [
+            |_| (),
+            |config| config.add_stx_etx = false,
+            |config| config.keep_terminator = true,
+            |config| config.strict_escapes = false,
+            |config| config.strict_non_escaped = true,
+            |config| config.alt_terminator = Some(CR_CHAR),
+            |config| config.resync_interval = Some(1),
+            |config| config.resync_interval = Some(0),
+            |config| config.trailing_policy = TrailingPolicy::RequireEmpty,
+            |config| config.pad_to = Some((3, 0)),
+            |config| config.idle_on_empty = Some(&[DLE_CHAR, SYN_CHAR]),
+            |config| config.etx_terminates_only_after_dle = true,
+            |config| {
+                config.lenient_stx = true;
+                config.lenient_dle = true;
+                config.dle_escape_style = DleStyle::Offset;
+                config.escape_extra = ByteSet::new().with(0x00).with(0xff);
+            },
+        ];
+        IntoIterator::into_iter(variants).map(move |variant| {
+            let mut config = preset;
+            variant(&mut config);
+            config
+        })
+    })
+}
+
+fn inputs() -> impl Iterator<Item = &'static [u8]> {
+    let inputs: [&'static [u8]; 22] = [
+        &[],
+        &[STX_CHAR],
+        &[ETX_CHAR],
+        &[DLE_CHAR],
+        &[CR_CHAR],
+        &[0x55],
+        &[DLE_CHAR; 2],
+        &[DLE_CHAR; 3],
+        &[DLE_CHAR; 64],
+        &[STX_CHAR, DLE_CHAR],
+        &[STX_CHAR, ETX_CHAR],
+        &[STX_CHAR, STX_CHAR, ETX_CHAR],
+        &[STX_CHAR, 0x55, DLE_CHAR],
+        &[STX_CHAR, DLE_CHAR, ETX_CHAR],
+        &[STX_CHAR, DLE_CHAR, 0xff, ETX_CHAR],
+        &[DLE_CHAR, STX_CHAR],
+        &[DLE_CHAR, STX_CHAR, DLE_CHAR],
+        &[DLE_CHAR, STX_CHAR, DLE_CHAR, ETX_CHAR],
+        &[DLE_CHAR, STX_CHAR, 0x55, DLE_CHAR, 0x55, DLE_CHAR, ETX_CHAR],
+        &[DLE_CHAR, STX_CHAR, DLE_CHAR, SYN_CHAR, DLE_CHAR],
+        &[STX_CHAR, DLE_CHAR, US_CHAR, DLE_CHAR],
+        &[0xff; 8],
+    ];
+    IntoIterator::into_iter(inputs)
+}
+
+fn check_encode(config: &DleEncoder, input: &[u8]) {
+    for dest_len in DEST_LENS {
+        let mut dest_stream = [0; 64];
+        let dest_stream = &mut dest_stream[..dest_len];
+        let _ = config.encode(input, dest_stream);
+        let _ = config.encode_escaped(input, dest_stream);
+        for &byte in input.iter().take(1) {
+            let _ = config.encode_one(byte, dest_stream);
+        }
+        let _ = config.encode_non_escaped(input, dest_stream);
+        for role in [
+            FragmentRole::First,
+            FragmentRole::Middle,
+            FragmentRole::Last,
+            FragmentRole::Whole,
+        ] {
+            let _ = config.encode_fragment(input, role, dest_stream);
+            let _ = config.encode_chunk(input, dest_stream, role);
+        }
+        let _ = config.encode_typed(0x55, input, dest_stream);
+        let _ = config.encode_sparse(&[(0x0210, DLE_CHAR)], dest_stream);
+        let _ = config.encode_framed_len(input, dest_stream);
+        let _ = config.encode_with_crc(input, dest_stream);
+        let _ = config.encode_crc_single_pass(input, dest_stream);
+        let _ = config.encode_hex(input, dest_stream);
+        let _ = config.encode_framed_no_escape(input, dest_stream);
+        if let Ok(field_framer) = FieldFramer::new(*config, US_CHAR) {
+            let _ = field_framer.encode_fields(&[input, input], dest_stream);
+        }
+        let _ = config.encode_with_predicate(input, |byte| byte < 0x20, dest_stream);
+        if let Ok(text) = core::str::from_utf8(input) {
+            let mut frame_writer = DleFrameWriter::new(*config, dest_stream);
+            let _ = write!(frame_writer, "{}{}", text, text);
+            let _ = frame_writer.into_frame();
+        }
+        for reserve_tail in [0, 1, usize::MAX] {
+            let _ = config.encode_reserve(input, reserve_tail, dest_stream);
+        }
+        let reversed = Range { start: 2, end: 1 };
+        for escape_range in [0..0, 1..2, reversed, 0..usize::MAX, usize::MAX..usize::MAX] {
+            let _ = config.encode_ranged(input, escape_range, dest_stream);
+        }
+        let (dest_stream_0, dest_stream_1) = dest_stream.split_at_mut(dest_len / 2);
+        let _ = config.encode_scatter(input, dest_stream_0, dest_stream_1);
+    }
+    let _ = config.encode_fmt(input, &mut Discard);
+    #[cfg(feature = "alloc")]
+    {
+        let _ = config.encode_cow(input);
+        let _ = config.diff_against(&DleEncoder::default(), input);
+    }
+    #[cfg(feature = "heapless")]
+    {
+        let _ = config.encode_heapless::<0>(input);
+        let _ = config.encode_heapless::<4>(input);
+    }
+    #[cfg(feature = "fec")]
+    for scheme in [crate::FecScheme::Xor, crate::FecScheme::Hamming] {
+        let mut dest_stream = [0; 64];
+        for dest_len in DEST_LENS {
+            let _ = config.encode_fec(scheme, input, &mut dest_stream[..dest_len]);
+        }
+    }
+}
+
+fn check_decode(config: &DleEncoder, input: &[u8]) {
+    for dest_len in DEST_LENS {
+        let mut dest_stream = [0; 64];
+        let dest_stream = &mut dest_stream[..dest_len];
+        let mut read_len = 0;
+        let _ = config.decode(input, dest_stream, &mut read_len);
+        let _ = config.decode_escaped(input, dest_stream, &mut read_len);
+        let _ = config.decode_non_escaped(input, dest_stream, &mut read_len);
+        let _ = config.decode_escaped_body(input, dest_stream);
+        let _ = config.decode_and_remainder(input, dest_stream);
+        let _ = config.decode_with_raw(input, dest_stream);
+        let (header, payload) = dest_stream.split_at_mut(dest_len / 2);
+        let _ = config.decode_split(input, header, payload, &mut read_len);
+        let _ = config.decode_with_stats(input, dest_stream, &mut read_len);
+        let _ = config.decode_framed_len(input, dest_stream, &mut read_len);
+        let _ = config.decode_with_crc(input, dest_stream, &mut read_len);
+        let _ = config.decode_hex(input, dest_stream, &mut read_len);
+        let _ = config.decode_framed_no_escape(input, dest_stream, &mut read_len);
+        if let Ok(mut progress) = config.decode_partial(input, dest_stream) {
+            progress.complete = false;
+            for resume_idx in [0, 1, usize::MAX] {
+                progress.read_len = resume_idx;
+                let _ = config.resume_partial(input, &progress, dest_stream);
+            }
+        }
+        let _ = config.decode_with_predicate(input, |byte| byte < 0x20, dest_stream, &mut read_len);
+        let _ = config.decode_probe_offset(input, &[0x01, 0x20, 0x40], dest_stream);
+        let _ = config.decode_typed(input, dest_stream);
+        let _ = config
+            .decode_sparse(input, dest_stream)
+            .map(Iterator::count);
+        let _ = config.decode_concat(input, dest_stream);
+        let _ = config.for_each_frame(input, dest_stream, |_| ());
+        let mut bytes = input.iter().copied();
+        let _ = config.decode_pull(|| bytes.next(), dest_stream);
+        #[cfg(feature = "fec")]
+        for scheme in [crate::FecScheme::Xor, crate::FecScheme::Hamming] {
+            let _ = config.decode_fec(scheme, input, dest_stream);
+        }
+    }
+    if let Ok(chunks) = config.decode_chunks(input) {
+        chunks.for_each(drop);
+    }
+    let _ = config.decode_array::<0>(input);
+    let _ = config.decode_array::<4>(input);
+    let _ = config.decode_bounded::<0>(input);
+    let _ = config.decode_bounded::<4>(input);
+    let _ = config.same_payload(input, &DleEncoder::non_escaped(), input);
+    for &found in input {
+        let error = DleError::decoding(DecodeErrorKind::InvalidDleSequence { found }, 0);
+        let _ = config.explain_invalid_escape(&error);
+    }
+    let _ = config.strip_framing(input);
+    let _ = config.validate_batch(input);
+    let _ = config.decoded_len(input);
+    let _ = config.decoded_len_bounded(input, 0);
+    let _ = config.looks_encoded(input);
+    let _ = config.min_decode_dest_len(input);
+    #[cfg(feature = "alloc")]
+    {
+        let _ = config.reframe(input);
+        let _ = config.is_reversible(input);
+        if let Ok(field_framer) = FieldFramer::new(*config, US_CHAR) {
+            let _ = field_framer.decode_fields(input);
+        }
+        let _ = config.decode_frame_typed(input);
+        let _ = config.decode_to_string(input);
+        let _ = config.frame_ranges(input);
+        let _ = config.scan_frames(input);
+    }
+    #[cfg(feature = "heapless")]
+    {
+        let mut read_len = 0;
+        let _ = config.decode_heapless::<0>(input, &mut read_len);
+        let _ = config.decode_heapless::<4>(input, &mut read_len);
+    }
+}
+
+#[test]
+fn test_adversarial_inputs() {
+    for config in configs() {
+        for input in inputs() {
+            check_encode(&config, input);
+            check_decode(&config, input);
+        }
+    }
+}
+
+#[test]
+fn test_truncated_frames() {
+    // Every prefix of a valid frame is a frame which was cut off at an arbitrary position
+    let payload = [DLE_CHAR, STX_CHAR, 0x55, ETX_CHAR, CR_CHAR, DLE_CHAR];
+    for config in configs() {
+        let mut encoded = [0; 64];
+        let encoded_len = match config.encode(&payload, &mut encoded) {
+            Ok(encoded_len) => encoded_len,
+            Err(_) => continue,
+        };
+        for prefix_len in 0..=encoded_len {
+            check_decode(&config, &encoded[..prefix_len]);
+        }
+    }
+}
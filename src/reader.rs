@@ -0,0 +1,189 @@
+//! Reading of frames from a [std::io::Read] implementation.
+
+use crate::{DleEncoder, DleError};
+use alloc::vec::Vec;
+use std::io::{ErrorKind, Read};
+
+/// Number of bytes requested from the reader at once
+const READ_CHUNK_LEN: usize = 256;
+
+/// Reader which reads DLE encoded frames from a [Read] implementation, for example a serial
+/// port or a file. The received bytes and the decoded payload are kept in internal buffers
+/// which are reused for all frames, so reading frames does not allocate once the buffers
+/// have grown to the largest frame. Frames can be split across multiple reads. Bytes outside
+/// of frames are discarded.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, DleReader};
+/// use std::io::Cursor;
+///
+/// let encoded_frames: [u8; 8] = [0x02, 0x01, 0x03, 0x02, 0x10, 0x42, 0x05, 0x03];
+/// let mut dle_reader = DleReader::new(DleEncoder::default(), Cursor::new(encoded_frames));
+/// assert_eq!(dle_reader.next_frame(), Ok(Some(&[0x01][..])));
+/// assert_eq!(dle_reader.next_frame(), Ok(Some(&[0x02, 0x05][..])));
+/// assert_eq!(dle_reader.next_frame(), Ok(None));
+/// ```
+pub struct DleReader<R> {
+    /// The encoder configuration used to find and decode frames
+    pub encoder: DleEncoder,
+    reader: R,
+    /// Received bytes which were not processed yet
+    buffer: Vec<u8>,
+    /// Decoded payload of the last frame
+    payload: Vec<u8>,
+}
+
+impl<R: Read> DleReader<R> {
+    /// Create a new frame reader using the given encoder configuration and reader
+    pub fn new(encoder: DleEncoder, reader: R) -> DleReader<R> {
+        DleReader {
+            encoder,
+            reader,
+            buffer: Vec::new(),
+            payload: Vec::new(),
+        }
+    }
+
+    /// Read and decode the next frame. Returns the decoded payload, which is valid until
+    /// the next call, or [None] if the reader reached the end of its stream. Erroneous frames
+    /// are returned as errors and skipped, so this method can be called again to read the
+    /// following frames. If the stream ends inside a frame, [DleError::SourceIncomplete] is
+    /// returned. Failing reads are returned as [DleError::ReadFailed], interrupted reads are
+    /// retried.
+    pub fn next_frame(&mut self) -> Result<Option<&[u8]>, DleError> {
+        loop {
+            if let Some(range) = self.encoder.find_frame(&self.buffer) {
+                self.payload.resize(range.len(), 0);
+                let mut read_len = 0;
+                let frame = self.buffer.get(range.clone()).unwrap_or_default();
+                let result = self.encoder.decode(frame, &mut self.payload, &mut read_len);
+                self.buffer.drain(..range.end);
+                let decoded_len = result?;
+                return Ok(self.payload.get(..decoded_len));
+            }
+            let buffered_len = self.buffer.len();
+            self.buffer.resize(buffered_len + READ_CHUNK_LEN, 0);
+            match self.reader.read(&mut self.buffer[buffered_len..]) {
+                Ok(0) => {
+                    self.buffer.truncate(buffered_len);
+                    let incomplete = self.encoder.find_frame_start(&self.buffer).is_some();
+                    self.buffer.clear();
+                    return if incomplete {
+                        Err(DleError::SourceIncomplete)
+                    } else {
+                        Ok(None)
+                    };
+                }
+                Ok(read_len) => self.buffer.truncate(buffered_len + read_len),
+                Err(error) => {
+                    self.buffer.truncate(buffered_len);
+                    if error.kind() != ErrorKind::Interrupted {
+                        return Err(DleError::ReadFailed);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Discard all buffered bytes, including a partially received frame, so the next frame
+    /// is read from the current position of the reader. This is useful if the reader was
+    /// reconnected
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.payload.clear();
+    }
+
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DecodeErrorKind, DLE_CHAR, ETX_CHAR, STX_CHAR};
+    use std::io::{self, Cursor};
+
+    /// Reader which returns at most the given number of bytes per read and fails every
+    /// other read as interrupted
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk_len: usize,
+        interrupt: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::Error::from(ErrorKind::Interrupted));
+            }
+            let read_len = self.chunk_len.min(buf.len()).min(self.data.len());
+            buf[..read_len].copy_from_slice(&self.data[..read_len]);
+            self.data = &self.data[read_len..];
+            Ok(read_len)
+        }
+    }
+
+    #[test]
+    fn test_dle_reader() {
+        let mut dle_encoder = DleEncoder::default();
+        let payloads: [&[u8]; 2] = [&[DLE_CHAR, STX_CHAR, ETX_CHAR], &[0x55; 300]];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut stream = vec![0x77];
+            let mut encoded: [u8; 512] = [0; 512];
+            for payload in payloads.iter() {
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                stream.extend_from_slice(&encoded[..encoded_len]);
+            }
+
+            let mut dle_reader = DleReader::new(dle_encoder, Cursor::new(&stream));
+            assert_eq!(dle_reader.next_frame(), Ok(Some(payloads[0])));
+            assert_eq!(dle_reader.next_frame(), Ok(Some(payloads[1])));
+            assert_eq!(dle_reader.next_frame(), Ok(None));
+
+            // Frames split across reads
+            for chunk_len in 1..4 {
+                let trickle = Trickle {
+                    data: &stream,
+                    chunk_len,
+                    interrupt: false,
+                };
+                let mut dle_reader = DleReader::new(dle_encoder, trickle);
+                assert_eq!(dle_reader.next_frame(), Ok(Some(payloads[0])));
+                assert_eq!(dle_reader.next_frame(), Ok(Some(payloads[1])));
+                assert_eq!(dle_reader.next_frame(), Ok(None));
+            }
+
+            // An erroneous frame is skipped and the stream ends inside a frame
+            let mut stream = Vec::new();
+            stream.extend_from_slice(dle_encoder.start_marker());
+            stream.extend_from_slice(&[DLE_CHAR, 0x99]);
+            stream.extend_from_slice(dle_encoder.end_marker());
+            let encoded_len = dle_encoder.encode(&[0x55], &mut encoded).unwrap();
+            stream.extend_from_slice(&encoded[..encoded_len - 1]);
+            let mut dle_reader = DleReader::new(dle_encoder, Cursor::new(&stream));
+            let error = dle_reader.next_frame().unwrap_err();
+            assert_eq!(
+                error.decode_kind(),
+                Some(DecodeErrorKind::InvalidDleSequence { found: 0x99 })
+            );
+            assert_eq!(dle_reader.next_frame(), Err(DleError::SourceIncomplete));
+            assert_eq!(dle_reader.next_frame(), Ok(None));
+
+            // A buffered partial frame is discarded by a reset
+            let mut stream = Vec::new();
+            let encoded_len = dle_encoder.encode(&[0x55], &mut encoded).unwrap();
+            stream.extend_from_slice(&encoded[..encoded_len]);
+            stream.extend_from_slice(&encoded[..encoded_len - 1]);
+            let mut dle_reader = DleReader::new(dle_encoder, Cursor::new(&stream));
+            assert_eq!(dle_reader.next_frame(), Ok(Some(&[0x55][..])));
+            dle_reader.reset();
+            assert_eq!(dle_reader.next_frame(), Ok(None));
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Decoding of frames from a continuous byte stream without allocations.
+
+use crate::{DleEncoder, DleError, DLE_CHAR, STX_CHAR};
+
+/// Decoder which accumulates received bytes in a ring buffer provided by the caller and
+/// extracts the DLE encoded frames contained in them. This is the counterpart of the
+/// `StreamDecoder` for `no_std` environments without a global allocator, so all memory can
+/// be allocated statically. Bytes outside of frames are discarded. A frame can not be
+/// longer than the ring buffer, including the framing characters.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, RingDecoder};
+///
+/// let mut ring: [u8; 8] = [0; 8];
+/// let mut ring_decoder = RingDecoder::new(DleEncoder::default(), &mut ring);
+/// let mut output: [u8; 8] = [0; 8];
+/// assert_eq!(ring_decoder.push(&[0x02, 0x01]), 2);
+/// assert!(ring_decoder.poll_frame(&mut output).is_none());
+/// assert_eq!(ring_decoder.push(&[0x10, 0x42, 0x03]), 3);
+/// assert_eq!(ring_decoder.poll_frame(&mut output), Some(Ok(&[0x01, 0x02][..])));
+/// ```
+pub struct RingDecoder<'a> {
+    /// The encoder configuration used to find and decode frames
+    pub encoder: DleEncoder,
+    ring: &'a mut [u8],
+    /// Index of the oldest buffered byte inside the ring
+    head: usize,
+    /// Number of buffered bytes
+    len: usize,
+}
+
+impl<'a> RingDecoder<'a> {
+    /// Create a new ring decoder using the given encoder configuration and ring buffer
+    pub fn new(encoder: DleEncoder, ring: &'a mut [u8]) -> RingDecoder<'a> {
+        RingDecoder {
+            encoder,
+            ring,
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Append received bytes to the ring buffer. Returns the number of stored bytes, which
+    /// is less than the length of the given data if the ring buffer is full. The remaining
+    /// bytes should be pushed again after polling frames.
+    pub fn push(&mut self, data: &[u8]) -> usize {
+        let stored = data.len().min(self.ring.len() - self.len);
+        for &byte in &data[..stored] {
+            let idx = (self.head + self.len) % self.ring.len();
+            self.ring[idx] = byte;
+            self.len += 1;
+        }
+        stored
+    }
+
+    /// Number of accumulated bytes which were not processed yet
+    pub fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    /// Reset the decoder so it can be reused for a new connection. All accumulated bytes
+    /// are discarded, including a partially received frame
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Extract the next complete frame from the accumulated bytes and decode it into the
+    /// given output region. Returns the decoded payload as a subslice of the output region,
+    /// or [None] if no complete frame is available yet. Erroneous frames are returned as
+    /// errors and removed from the ring buffer, so this method can be called in a loop until
+    /// it returns [None]. If the ring buffer is full without containing a complete frame,
+    /// [DleError::FrameTooLarge] is returned and the decoder resynchronizes on the next
+    /// start marker. If the output region is too short, [DleError::StreamTooShort] is
+    /// returned and the frame is kept, so it can be polled again with a larger output region.
+    pub fn poll_frame<'b>(&mut self, output: &'b mut [u8]) -> Option<Result<&'b [u8], DleError>> {
+        self.discard_until_frame_start(0);
+        if self.len == 0 {
+            return None;
+        }
+        let mut pulled = 0;
+        let result = {
+            let (ring, head, len) = (&self.ring, self.head, self.len);
+            self.encoder.decode_pull(
+                || {
+                    let byte = (pulled < len).then(|| ring[(head + pulled) % ring.len()]);
+                    pulled += 1;
+                    byte
+                },
+                output,
+            )
+        };
+        match result {
+            Ok(decoded_len) => {
+                self.consume(pulled);
+                Some(Ok(&output[..decoded_len]))
+            }
+            Err(DleError::SourceIncomplete) => {
+                if self.len < self.ring.len() {
+                    return None;
+                }
+                // Skip the start marker of the oversized frame and resynchronize on the next one
+                self.discard_until_frame_start(1);
+                Some(Err(DleError::FrameTooLarge))
+            }
+            Err(DleError::StreamTooShort) => Some(Err(DleError::StreamTooShort)),
+            Err(error) => {
+                // Keep the offending byte, it could be the start of the next frame
+                self.consume(error.offset().unwrap_or(0).max(1));
+                Some(Err(error))
+            }
+        }
+    }
+
+    /// Returns the buffered byte with the given index, starting at the oldest byte
+    fn get(&self, idx: usize) -> u8 {
+        self.ring[(self.head + idx) % self.ring.len()]
+    }
+
+    /// Remove the given number of the oldest bytes
+    fn consume(&mut self, count: usize) {
+        let count = count.min(self.len);
+        if count > 0 {
+            self.head = (self.head + count) % self.ring.len();
+            self.len -= count;
+        }
+    }
+
+    /// Discard all bytes before the first frame start marker found after the given offset
+    fn discard_until_frame_start(&mut self, offset: usize) {
+        let mut idx = offset.min(self.len);
+        while idx < self.len {
+            let byte = self.get(idx);
+            if self.encoder.escape_stx_etx {
+                if byte == STX_CHAR {
+                    break;
+                }
+            } else if byte == DLE_CHAR && (idx + 1 == self.len || self.get(idx + 1) == STX_CHAR) {
+                // A trailing DLE could be the first half of a DLE/STX start marker
+                break;
+            }
+            idx += 1;
+        }
+        self.consume(idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_frame_eq;
+    use crate::{DecodeErrorKind, ETX_CHAR};
+
+    #[test]
+    fn test_ring_decoder_fragments() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let payloads: [&[u8]; 4] = [
+                &[1, 2, 3],
+                &[DLE_CHAR, STX_CHAR, ETX_CHAR],
+                &[],
+                &[DLE_CHAR, DLE_CHAR, 0x55, DLE_CHAR],
+            ];
+            let mut stream = [0; 64];
+            let mut stream_len = 0;
+            for payload in payloads.iter() {
+                stream[stream_len] = 0x77;
+                stream_len += 1;
+                stream_len += dle_encoder
+                    .encode(payload, &mut stream[stream_len..])
+                    .unwrap();
+            }
+
+            // Different ring sizes and chunk lengths move the wrap-around through all
+            // positions of the frames, including between a DLE and the following byte
+            for ring_len in 13..17 {
+                for chunk_len in 1..5 {
+                    let mut ring = [0; 16];
+                    let mut ring_decoder = RingDecoder::new(dle_encoder, &mut ring[..ring_len]);
+                    let mut output: [u8; 16] = [0; 16];
+                    let mut frame_idx = 0;
+                    let mut remaining = &stream[..stream_len];
+                    while !remaining.is_empty() {
+                        let chunk = &remaining[..chunk_len.min(remaining.len())];
+                        let stored = ring_decoder.push(chunk);
+                        remaining = &remaining[stored..];
+                        while let Some(frame) = ring_decoder.poll_frame(&mut output) {
+                            assert_frame_eq(frame.unwrap(), payloads[frame_idx]);
+                            frame_idx += 1;
+                        }
+                    }
+                    assert_eq!(frame_idx, payloads.len());
+                    assert!(ring_decoder.buffered_len() <= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ring_decoder_errors() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut ring = [0; 8];
+            let mut ring_decoder = RingDecoder::new(dle_encoder, &mut ring);
+            let mut output: [u8; 8] = [0; 8];
+
+            // A frame which does not fit into the ring buffer
+            let mut encoded: [u8; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&[0x55; 10], &mut encoded).unwrap();
+            assert_eq!(ring_decoder.push(&encoded[..encoded_len]), 8);
+            assert_eq!(
+                ring_decoder.poll_frame(&mut output),
+                Some(Err(DleError::FrameTooLarge))
+            );
+            assert_eq!(ring_decoder.poll_frame(&mut output), None);
+            assert_eq!(ring_decoder.buffered_len(), 0);
+
+            // The output region is too short, the frame can be polled again
+            let encoded_len = dle_encoder.encode(&[1, 2, 3], &mut encoded).unwrap();
+            ring_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(
+                ring_decoder.poll_frame(&mut output[..2]),
+                Some(Err(DleError::StreamTooShort))
+            );
+            assert_eq!(
+                ring_decoder.poll_frame(&mut output),
+                Some(Ok(&[1, 2, 3][..]))
+            );
+
+            // A lost end marker, the following frame is still decoded
+            let encoded_len = dle_encoder.encode(&[4], &mut encoded).unwrap();
+            let start_len = encoded_len - 1 - usize::from(!escape_stx_etx);
+            ring_decoder.push(&encoded[..start_len]);
+            ring_decoder.push(&encoded[..encoded_len]);
+            let error = ring_decoder.poll_frame(&mut output).unwrap().unwrap_err();
+            assert_eq!(error.decode_kind(), Some(DecodeErrorKind::UnexpectedStart));
+            assert_eq!(ring_decoder.poll_frame(&mut output), Some(Ok(&[4][..])));
+            assert_eq!(ring_decoder.poll_frame(&mut output), None);
+
+            // A partial frame is discarded by a reset, the next frame starts from scratch
+            ring_decoder.push(&encoded[..start_len]);
+            assert_eq!(ring_decoder.poll_frame(&mut output), None);
+            ring_decoder.reset();
+            assert_eq!(ring_decoder.buffered_len(), 0);
+            let encoded_len = dle_encoder.encode(&[5, 6], &mut encoded).unwrap();
+            ring_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(ring_decoder.poll_frame(&mut output), Some(Ok(&[5, 6][..])));
+            assert_eq!(ring_decoder.poll_frame(&mut output), None);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Frames with a sequence number for detecting dropped frames.
+
+use crate::{DleEncoder, DleError, FragmentRole};
+
+/// Encoder which prepends an incrementing sequence number to the payload of each frame.
+/// The sequence number is the first byte of the frame payload and is escaped like the rest
+/// of the payload. It wraps around after 255, so receivers can detect dropped frames on
+/// one-way links by checking for gaps in the received sequence numbers.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, SeqEncoder};
+///
+/// let mut seq_encoder = SeqEncoder::new(DleEncoder::default());
+/// let mut encoding_buffer: [u8; 16] = [0; 16];
+/// let mut decoding_buffer: [u8; 16] = [0; 16];
+/// let encoded_len = seq_encoder
+///     .encode_seq(&[0x01], &mut encoding_buffer)
+///     .unwrap();
+/// assert_eq!(&encoding_buffer[..encoded_len], &[0x02, 0x00, 0x01, 0x03]);
+/// let encoded_len = seq_encoder
+///     .encode_seq(&[0x01], &mut encoding_buffer)
+///     .unwrap();
+/// let (seq, payload) = seq_encoder
+///     .decode_seq(&encoding_buffer[..encoded_len], &mut decoding_buffer)
+///     .unwrap();
+/// assert_eq!(seq, 1);
+/// assert_eq!(payload, &[0x01]);
+/// ```
+#[derive(Copy, Clone)]
+pub struct SeqEncoder {
+    /// The encoder configuration used to encode and decode frames
+    pub encoder: DleEncoder,
+    /// Sequence number of the next encoded frame
+    next_seq: u8,
+}
+
+impl SeqEncoder {
+    /// Create a new sequence encoder using the given encoder configuration. The first frame
+    /// has the sequence number 0
+    pub fn new(encoder: DleEncoder) -> SeqEncoder {
+        SeqEncoder {
+            encoder,
+            next_seq: 0,
+        }
+    }
+
+    /// Sequence number of the next encoded frame
+    pub fn next_seq(&self) -> u8 {
+        self.next_seq
+    }
+
+    /// Reset the sequence number to 0, for example when a new connection is established
+    pub fn reset(&mut self) {
+        self.next_seq = 0;
+    }
+
+    /// This method encodes the given payload with the next sequence number prepended, so the
+    /// frame payload is `[seq, payload...]`. The sequence number is only incremented if the
+    /// frame was encoded successfully.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to encode
+    /// * `dest_stream` - Encoded frame will be written here
+    pub fn encode_seq(
+        &mut self,
+        payload: &[u8],
+        dest_stream: &mut [u8],
+    ) -> Result<usize, DleError> {
+        let (seq_role, payload_role) = if self.encoder.add_stx_etx {
+            (FragmentRole::First, FragmentRole::Last)
+        } else {
+            (FragmentRole::Middle, FragmentRole::Middle)
+        };
+        let seq_len = self
+            .encoder
+            .encode_fragment(&[self.next_seq], seq_role, dest_stream)?;
+        let payload_len = self.encoder.encode_fragment(
+            payload,
+            payload_role,
+            dest_stream
+                .get_mut(seq_len..)
+                .ok_or(DleError::StreamTooShort)?,
+        )?;
+        let encoded_len = self.encoder.pad(dest_stream, seq_len + payload_len)?;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        Ok(encoded_len)
+    }
+
+    /// This method decodes the first frame of a given byte stream which was encoded with
+    /// [SeqEncoder::encode_seq]. Returns the sequence number and the payload, which is a
+    /// subslice of the destination stream. [DleError::InvalidHeader] is returned if the
+    /// decoded frame is empty.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded frame including the sequence number will be written here
+    pub fn decode_seq<'a>(
+        &self,
+        source_stream: &[u8],
+        dest_stream: &'a mut [u8],
+    ) -> Result<(u8, &'a [u8]), DleError> {
+        let mut read_len = 0;
+        let decoded_len = self
+            .encoder
+            .decode(source_stream, dest_stream, &mut read_len)?;
+        match dest_stream.get(..decoded_len).unwrap_or_default() {
+            [seq, payload @ ..] => Ok((*seq, payload)),
+            [] => Err(DleError::InvalidHeader),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+    #[test]
+    fn test_seq_encoder() {
+        let mut dle_encoder = DleEncoder::default();
+        let payload = [DLE_CHAR, STX_CHAR, 0x55, ETX_CHAR];
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut seq_encoder = SeqEncoder::new(dle_encoder);
+            let mut encoded: [u8; 16] = [0; 16];
+            let mut decoded: [u8; 16] = [0; 16];
+            // The sequence number wraps around and is escaped where required
+            for expected_seq in (0..=u8::MAX).chain(0..4) {
+                assert_eq!(seq_encoder.next_seq(), expected_seq);
+                let encoded_len = seq_encoder.encode_seq(&payload, &mut encoded).unwrap();
+                let (seq, decoded_payload) = seq_encoder
+                    .decode_seq(&encoded[..encoded_len], &mut decoded)
+                    .unwrap();
+                assert_eq!(seq, expected_seq);
+                assert_eq!(decoded_payload, payload);
+            }
+
+            // A failed encoding does not consume a sequence number
+            assert_eq!(
+                seq_encoder.encode_seq(&payload, &mut encoded[..4]),
+                Err(DleError::StreamTooShort)
+            );
+            assert_eq!(seq_encoder.next_seq(), 4);
+
+            let encoded_len = dle_encoder.encode(&[], &mut encoded).unwrap();
+            assert_eq!(
+                seq_encoder.decode_seq(&encoded[..encoded_len], &mut decoded),
+                Err(DleError::InvalidHeader)
+            );
+
+            seq_encoder.reset();
+            assert_eq!(seq_encoder.next_seq(), 0);
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! Decoding of frames from a continuous byte stream.
+
+use crate::{DleEncoder, DleError};
+use alloc::{vec, vec::Vec};
+
+/// Decoder which accumulates received bytes and extracts the DLE encoded frames contained
+/// in them. This is useful if the data arrives in arbitrary chunks, for example from a serial
+/// port. Bytes outside of frames are discarded.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, StreamDecoder};
+///
+/// let mut stream_decoder = StreamDecoder::new(DleEncoder::default());
+/// stream_decoder.push(&[0x02, 0x01]);
+/// assert!(stream_decoder.next_frame().is_none());
+/// stream_decoder.push(&[0x10, 0x42, 0x03]);
+/// assert_eq!(stream_decoder.next_frame(), Some(Ok(vec![0x01, 0x02])));
+/// ```
+#[derive(Clone)]
+pub struct StreamDecoder {
+    /// The encoder configuration used to find and decode frames
+    pub encoder: DleEncoder,
+    /// Maximum number of encoded bytes of a single frame, including the framing characters.
+    /// If an incomplete frame grows beyond this limit, [StreamDecoder::next_frame] returns a
+    /// [DleError::FrameTooLarge] error and the accumulated bytes are discarded up to the next
+    /// start marker. This prevents unbounded buffering on untrusted links
+    pub max_frame_len: Option<usize>,
+    buffer: Vec<u8>,
+    resync_count: usize,
+    dropped_bytes: usize,
+    frames_decoded: usize,
+    /// Whether bytes were discarded since the last frame start was found
+    out_of_sync: bool,
+}
+
+impl StreamDecoder {
+    /// Create a new stream decoder using the given encoder configuration
+    pub fn new(encoder: DleEncoder) -> StreamDecoder {
+        StreamDecoder {
+            encoder,
+            max_frame_len: None,
+            buffer: Vec::new(),
+            resync_count: 0,
+            dropped_bytes: 0,
+            frames_decoded: 0,
+            out_of_sync: false,
+        }
+    }
+
+    /// Append received bytes to the internal buffer
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Number of accumulated bytes which were not processed yet
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Reset the decoder so it can be reused for a new connection. All accumulated bytes
+    /// are discarded, including a partially received frame, and the counters are cleared.
+    /// The allocated buffer is kept
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.resync_count = 0;
+        self.dropped_bytes = 0;
+        self.frames_decoded = 0;
+        self.out_of_sync = false;
+    }
+
+    /// Number of times the decoder resynchronized on a frame start after discarding bytes,
+    /// for example garbage between frames or the start of a frame whose end marker was lost
+    pub fn resync_count(&self) -> usize {
+        self.resync_count
+    }
+
+    /// Number of received bytes which were discarded without being decoded into a frame.
+    /// This includes bytes outside of frames, unterminated frames and the bytes of erroneous
+    /// frames
+    pub fn dropped_bytes(&self) -> usize {
+        self.dropped_bytes
+    }
+
+    /// Number of frames which were decoded successfully
+    pub fn frames_decoded(&self) -> usize {
+        self.frames_decoded
+    }
+
+    /// Extract and decode the next complete frame from the accumulated bytes. Returns
+    /// [None] if no complete frame is available yet. Erroneous frames are returned as
+    /// errors and removed from the buffer, so this method can be called in a loop until
+    /// it returns [None].
+    pub fn next_frame(&mut self) -> Option<Result<Vec<u8>, DleError>> {
+        self.discard_until_frame_start(0);
+        if let Some(range) = self.encoder.find_frame(&self.buffer) {
+            if range.start > 0 {
+                // A new frame started before the end of the previous one
+                self.dropped_bytes += range.start;
+                self.resync_count += 1;
+            }
+            if self.exceeds_max_frame_len(range.len()) {
+                self.dropped_bytes += range.len();
+                self.buffer.drain(..range.end);
+                return Some(Err(DleError::FrameTooLarge));
+            }
+            let mut payload = vec![0; range.len()];
+            let mut read_len = 0;
+            let result = self
+                .encoder
+                .decode(&self.buffer[range.clone()], &mut payload, &mut read_len)
+                .map(|decoded_len| {
+                    payload.truncate(decoded_len);
+                    payload
+                });
+            if result.is_ok() {
+                self.frames_decoded += 1;
+            } else {
+                self.dropped_bytes += range.len();
+            }
+            self.buffer.drain(..range.end);
+            return Some(result);
+        }
+        if self.exceeds_max_frame_len(self.buffer.len()) {
+            // Skip the start marker of the oversized frame and resynchronize on the next one
+            self.discard_until_frame_start(1);
+            return Some(Err(DleError::FrameTooLarge));
+        }
+        None
+    }
+
+    fn exceeds_max_frame_len(&self, frame_len: usize) -> bool {
+        matches!(self.max_frame_len, Some(max_frame_len) if frame_len > max_frame_len)
+    }
+
+    /// Discard all bytes before the first frame start marker found after the given offset
+    fn discard_until_frame_start(&mut self, offset: usize) {
+        let offset = offset.min(self.buffer.len());
+        let start = self.encoder.find_frame_start(&self.buffer[offset..]);
+        let discard = match start {
+            Some(start) => offset + start,
+            None => {
+                // Keep a trailing byte, it could be the first half of a DLE/STX start marker
+                let keep = usize::from(!self.encoder.escape_stx_etx);
+                self.buffer.len().saturating_sub(keep).max(offset)
+            }
+        };
+        self.buffer.drain(..discard);
+        self.dropped_bytes += discard;
+        self.out_of_sync |= discard > 0;
+        if start.is_some() && self.out_of_sync {
+            self.resync_count += 1;
+            self.out_of_sync = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+    #[test]
+    fn test_stream_decoder_chunks() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut stream = vec![0x55, 0x66];
+            let mut encoded: [u8; 32] = [0; 32];
+            let payloads: [&[u8]; 3] = [&[1, 2, 3], &[DLE_CHAR, STX_CHAR, ETX_CHAR], &[]];
+            for payload in payloads.iter() {
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                stream.extend_from_slice(&encoded[..encoded_len]);
+                stream.push(0x77);
+            }
+
+            let mut stream_decoder = StreamDecoder::new(dle_encoder);
+            let mut frames = Vec::new();
+            for chunk in stream.chunks(3) {
+                stream_decoder.push(chunk);
+                while let Some(frame) = stream_decoder.next_frame() {
+                    frames.push(frame.unwrap());
+                }
+            }
+            assert_eq!(frames, payloads);
+            assert!(stream_decoder.buffered_len() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_stream_decoder_max_frame_len() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut stream_decoder = StreamDecoder::new(dle_encoder);
+            stream_decoder.max_frame_len = Some(16);
+
+            // A frame which is never terminated
+            let mut encoded: [u8; 64] = [0; 64];
+            let mut encoded_len = dle_encoder.encode(&[0x55; 32], &mut encoded).unwrap();
+            encoded_len -= if escape_stx_etx { 1 } else { 2 };
+            let mut errors = 0;
+            for chunk in encoded[..encoded_len].chunks(4) {
+                stream_decoder.push(chunk);
+                while let Some(frame) = stream_decoder.next_frame() {
+                    assert_eq!(frame, Err(DleError::FrameTooLarge));
+                    errors += 1;
+                }
+                assert!(stream_decoder.buffered_len() <= 16 + 4);
+            }
+            assert_eq!(errors, 1);
+
+            // The decoder resynchronizes on the next valid frame
+            let encoded_len = dle_encoder.encode(&[1, 2, 3], &mut encoded).unwrap();
+            stream_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(stream_decoder.next_frame(), Some(Ok(vec![1, 2, 3])));
+
+            // Complete frames exceeding the limit are rejected as well
+            let encoded_len = dle_encoder.encode(&[0x55; 20], &mut encoded).unwrap();
+            stream_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(
+                stream_decoder.next_frame(),
+                Some(Err(DleError::FrameTooLarge))
+            );
+            assert_eq!(stream_decoder.next_frame(), None);
+        }
+    }
+
+    #[test]
+    fn test_stream_decoder_counters() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let end_marker_len = if escape_stx_etx { 1 } else { 2 };
+            let mut encoded: [u8; 32] = [0; 32];
+            let mut encode = |payload: &[u8]| {
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                encoded[..encoded_len].to_vec()
+            };
+            let garbage = [0x55, 0x66, 0x77];
+            let mut stream = garbage.to_vec();
+            stream.extend(encode(&[1, 2, 3]));
+            // A frame whose end marker was lost
+            let mut unterminated = encode(&[0x55; 4]);
+            unterminated.truncate(unterminated.len() - end_marker_len);
+            stream.extend_from_slice(&unterminated);
+            stream.extend(encode(&[DLE_CHAR, STX_CHAR]));
+            stream.extend(encode(&[4, 5]));
+            stream.extend_from_slice(&garbage);
+
+            for chunk_len in [1, 5, stream.len()] {
+                let mut stream_decoder = StreamDecoder::new(dle_encoder);
+                let mut frames = Vec::new();
+                for chunk in stream.chunks(chunk_len) {
+                    stream_decoder.push(chunk);
+                    while let Some(frame) = stream_decoder.next_frame() {
+                        frames.push(frame.unwrap());
+                    }
+                }
+                assert_eq!(frames, [&[1, 2, 3][..], &[DLE_CHAR, STX_CHAR], &[4, 5]]);
+                assert_eq!(stream_decoder.frames_decoded(), 3);
+                // Resynchronized after the leading garbage and the unterminated frame
+                assert_eq!(stream_decoder.resync_count(), 2);
+                assert_eq!(
+                    stream_decoder.dropped_bytes() + stream_decoder.buffered_len(),
+                    2 * garbage.len() + unterminated.len()
+                );
+            }
+
+            // Erroneous frames are dropped without a resynchronization
+            let mut stream_decoder = StreamDecoder::new(dle_encoder);
+            let mut invalid = encode(&[0x55]);
+            invalid.insert(invalid.len() - end_marker_len, DLE_CHAR);
+            invalid.insert(invalid.len() - end_marker_len, 0x99);
+            stream_decoder.push(&invalid);
+            assert!(stream_decoder.next_frame().unwrap().is_err());
+            assert_eq!(stream_decoder.next_frame(), None);
+            assert_eq!(stream_decoder.frames_decoded(), 0);
+            assert_eq!(stream_decoder.resync_count(), 0);
+            assert_eq!(stream_decoder.dropped_bytes(), invalid.len());
+        }
+    }
+
+    #[test]
+    fn test_stream_decoder_reset() {
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&[1, 2, 3], &mut encoded).unwrap();
+            let mut stream_decoder = StreamDecoder::new(dle_encoder);
+            stream_decoder.push(&[0x55]);
+            stream_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(stream_decoder.next_frame(), Some(Ok(vec![1, 2, 3])));
+            // A partial frame ending with a DLE character
+            stream_decoder.push(&encoded[..encoded_len - 1]);
+            stream_decoder.push(&[DLE_CHAR]);
+            assert_eq!(stream_decoder.next_frame(), None);
+
+            stream_decoder.reset();
+            assert_eq!(stream_decoder.buffered_len(), 0);
+            assert_eq!(stream_decoder.frames_decoded(), 0);
+            assert_eq!(stream_decoder.resync_count(), 0);
+            assert_eq!(stream_decoder.dropped_bytes(), 0);
+            let encoded_len = dle_encoder.encode(&[4, 5], &mut encoded).unwrap();
+            stream_decoder.push(&encoded[..encoded_len]);
+            assert_eq!(stream_decoder.next_frame(), Some(Ok(vec![4, 5])));
+            assert_eq!(stream_decoder.next_frame(), None);
+            assert_eq!(stream_decoder.resync_count(), 0);
+        }
+    }
+}
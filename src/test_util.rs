@@ -0,0 +1,257 @@
+//! Helpers to deterministically corrupt and compare encoded frames.
+//!
+//! These helpers are intended to test the error handling of receivers. Each corruption helper
+//! produces a specific malformation which the decoder reports with a distinct
+//! [DecodeErrorKind](crate::DecodeErrorKind). The frames must have been encoded with STX and
+//! ETX characters. The mode of a frame is detected from its first byte: frames starting with
+//! STX are treated as escaped, frames starting with DLE as non-escaped.
+
+use crate::{DLE_CHAR, ETX_CHAR, STX_CHAR};
+use core::fmt;
+
+/// Byte which replaces other bytes in corrupted frames. It is not a control character and
+/// not a valid escaped character for the default encoder configuration
+pub const CORRUPTION_BYTE: u8 = 0x99;
+
+/// Replace the character following the DLE of the escape sequence with the given index with
+/// [CORRUPTION_BYTE]. The decoder reports this as an invalid escape. Returns the offset of the
+/// corrupted byte, or [None] if the frame contains fewer escape sequences.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::test_util::{corrupt_escape, CORRUPTION_BYTE};
+/// use dle_encoder::{DecodeErrorKind, DleEncoder};
+///
+/// let dle_encoder = DleEncoder::default();
+/// let mut frame: [u8; 6] = [0x02, 0x01, 0x10, 0x42, 0x05, 0x03];
+/// assert_eq!(corrupt_escape(&mut frame, 0), Some(3));
+///
+/// let mut decoding_buffer: [u8; 16] = [0; 16];
+/// let mut read_len = 0;
+/// let error = dle_encoder
+///     .decode(&frame, &mut decoding_buffer, &mut read_len)
+///     .unwrap_err();
+/// assert_eq!(
+///     error.decode_kind(),
+///     Some(DecodeErrorKind::InvalidDleSequence {
+///         found: CORRUPTION_BYTE
+///     })
+/// );
+/// ```
+pub fn corrupt_escape(frame: &mut [u8], which: usize) -> Option<usize> {
+    let (start, end) = body_range(frame);
+    let mut found = 0;
+    let mut idx = start;
+    while idx + 1 < end {
+        if frame[idx] == DLE_CHAR {
+            if found == which {
+                frame[idx + 1] = CORRUPTION_BYTE;
+                return Some(idx + 1);
+            }
+            found += 1;
+            idx += 1;
+        }
+        idx += 1;
+    }
+    None
+}
+
+/// Overwrite the end marker of the frame with [CORRUPTION_BYTE], so the decoder reports
+/// a missing end marker. In the non-escaped mode, both the DLE and the ETX character of the
+/// end marker are overwritten. Returns whether an end marker was found.
+pub fn drop_etx(frame: &mut [u8]) -> bool {
+    let (_, end) = body_range(frame);
+    if end == frame.len() {
+        return false;
+    }
+    frame[end..].fill(CORRUPTION_BYTE);
+    true
+}
+
+/// Overwrite the start marker of the frame with [CORRUPTION_BYTE], so the decoder reports
+/// a missing start marker. Returns whether a start marker was found.
+pub fn drop_stx(frame: &mut [u8]) -> bool {
+    let (start, _) = body_range(frame);
+    frame[..start].fill(CORRUPTION_BYTE);
+    start > 0
+}
+
+/// Assert that two encoded or decoded byte streams are equal. On mismatch, the panic message
+/// contains both streams in hexadecimal with the first differing index highlighted, which is
+/// easier to read than the output of [assert_eq] for long streams.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::test_util::assert_frame_eq;
+/// use dle_encoder::DleEncoder;
+///
+/// let dle_encoder = DleEncoder::default();
+/// let mut encoding_buffer: [u8; 16] = [0; 16];
+/// let encoded_len = dle_encoder.encode(&[0x02], &mut encoding_buffer).unwrap();
+/// assert_frame_eq(&encoding_buffer[..encoded_len], &[0x02, 0x10, 0x42, 0x03]);
+/// ```
+#[track_caller]
+pub fn assert_frame_eq(actual: &[u8], expected: &[u8]) {
+    if actual != expected {
+        panic!("{}", FrameDiff { actual, expected });
+    }
+}
+
+/// Formats the difference of two byte streams for [assert_frame_eq]
+struct FrameDiff<'a> {
+    actual: &'a [u8],
+    expected: &'a [u8],
+}
+
+impl FrameDiff<'_> {
+    fn first_difference(&self) -> usize {
+        self.actual
+            .iter()
+            .zip(self.expected.iter())
+            .position(|(actual, expected)| actual != expected)
+            .unwrap_or_else(|| self.actual.len().min(self.expected.len()))
+    }
+}
+
+impl fmt::Display for FrameDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let idx = self.first_difference();
+        let describe = |stream: &[u8]| match stream.get(idx) {
+            Some(byte) => (*byte, true),
+            None => (0, false),
+        };
+        write!(f, "frames differ at index {}", idx)?;
+        match (describe(self.expected), describe(self.actual)) {
+            ((expected, true), (actual, true)) => {
+                write!(f, " (expected {:#04x}, actual {:#04x})", expected, actual)?
+            }
+            ((expected, true), _) => write!(f, " (expected {:#04x}, actual ended)", expected)?,
+            (_, (actual, true)) => write!(f, " (expected end, actual {:#04x})", actual)?,
+            _ => (),
+        }
+        writeln!(f)?;
+        for (name, stream) in [("expected", self.expected), ("actual", self.actual)] {
+            write!(f, "{:<9} [", name)?;
+            for (byte_idx, byte) in stream.iter().enumerate() {
+                if byte_idx > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:02x}", byte)?;
+            }
+            writeln!(f, "] ({} bytes)", stream.len())?;
+        }
+        // Three characters per byte, after the name column and the opening bracket
+        write!(f, "{:width$}^^", "", width = 10 + 3 * idx)
+    }
+}
+
+/// Determine the range of the frame body, excluding the start and end markers
+fn body_range(frame: &[u8]) -> (usize, usize) {
+    if frame.starts_with(&[STX_CHAR]) {
+        let end = if frame.len() > 1 && frame.ends_with(&[ETX_CHAR]) {
+            frame.len() - 1
+        } else {
+            frame.len()
+        };
+        (1, end)
+    } else if frame.starts_with(&[DLE_CHAR, STX_CHAR]) {
+        let end = if frame.len() > 3 && frame.ends_with(&[DLE_CHAR, ETX_CHAR]) {
+            frame.len() - 2
+        } else {
+            frame.len()
+        };
+        (2, end)
+    } else {
+        (0, frame.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DecodeErrorKind, DleEncoder};
+
+    fn decode_kind(dle_encoder: &DleEncoder, frame: &[u8]) -> Option<DecodeErrorKind> {
+        let mut buffer: [u8; 32] = [0; 32];
+        let mut read_len = 0;
+        dle_encoder
+            .decode(frame, &mut buffer, &mut read_len)
+            .unwrap_err()
+            .decode_kind()
+    }
+
+    #[test]
+    fn test_frame_diff() {
+        let diff = FrameDiff {
+            actual: &[0x02, 0x10, 0x43, 0x03],
+            expected: &[0x02, 0x10, 0x42, 0x03],
+        };
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "frames differ at index 2 (expected 0x42, actual 0x43)\n",
+                "expected  [02 10 42 03] (4 bytes)\n",
+                "actual    [02 10 43 03] (4 bytes)\n",
+                "                ^^"
+            )
+        );
+        let diff = FrameDiff {
+            actual: &[0x02],
+            expected: &[0x02, 0x03],
+        };
+        assert!(diff
+            .to_string()
+            .starts_with("frames differ at index 1 (expected 0x03, actual ended)"));
+        assert_frame_eq(&[0x02, 0x03], &[0x02, 0x03]);
+    }
+
+    #[test]
+    fn test_corruptions() {
+        let payload: [u8; 5] = [DLE_CHAR, 1, STX_CHAR, ETX_CHAR, DLE_CHAR];
+        let mut dle_encoder = DleEncoder::default();
+        for escape_stx_etx in [true, false] {
+            dle_encoder.escape_stx_etx = escape_stx_etx;
+            let mut encoded: [u8; 32] = [0; 32];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            let frame = &encoded[..encoded_len];
+            let escapes = if escape_stx_etx { 4 } else { 2 };
+
+            for which in 0..escapes {
+                let mut corrupted = frame.to_vec();
+                let offset = corrupt_escape(&mut corrupted, which).unwrap();
+                assert_eq!(
+                    decode_kind(&dle_encoder, &corrupted),
+                    Some(DecodeErrorKind::InvalidDleSequence {
+                        found: CORRUPTION_BYTE
+                    })
+                );
+                let mut buffer: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let error = dle_encoder
+                    .decode(&corrupted, &mut buffer, &mut read_len)
+                    .unwrap_err();
+                assert_eq!(error.offset(), Some(offset));
+            }
+            let mut corrupted = frame.to_vec();
+            assert_eq!(corrupt_escape(&mut corrupted, escapes), None);
+            assert_eq!(corrupted, frame);
+
+            let mut corrupted = frame.to_vec();
+            assert!(drop_etx(&mut corrupted));
+            assert_eq!(
+                decode_kind(&dle_encoder, &corrupted),
+                Some(DecodeErrorKind::MissingEnd)
+            );
+
+            let mut corrupted = frame.to_vec();
+            assert!(drop_stx(&mut corrupted));
+            assert_eq!(
+                decode_kind(&dle_encoder, &corrupted),
+                Some(DecodeErrorKind::MissingStart)
+            );
+            assert!(!drop_stx(&mut corrupted));
+        }
+    }
+}
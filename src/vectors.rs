@@ -0,0 +1,190 @@
+//! Regression test vectors for the encoder, loaded from an embedded fixture.
+//!
+//! The fixture `testdata/encode_vectors.bin` is generated by
+//! `testdata/gen_encode_vectors.py` with an independent reference encoder. All integers
+//! are little endian. The format is:
+//!
+//! | Field     | Size | Description                               |
+//! |-----------|------|-------------------------------------------|
+//! | Magic     | 4    | `b"DLEV"`                                 |
+//! | Version   | 1    | Format version, currently 1               |
+//! | Count     | 2    | Number of vectors following the header    |
+//!
+//! Each vector is encoded as:
+//!
+//! | Field       | Size        | Description                             |
+//! |-------------|-------------|-----------------------------------------|
+//! | Flags       | 1           | Encoder configuration, see below        |
+//! | Payload len | 2           | Length of the payload                   |
+//! | Payload     | Payload len | Data passed to the encoder              |
+//! | Encoded len | 2           | Length of the expected encoded data     |
+//! | Encoded     | Encoded len | Expected output of the encoder          |
+//!
+//! The flags map to the [DleEncoder] fields `escape_stx_etx` (bit 0), `add_stx_etx`
+//! (bit 1) and `escape_cr` (bit 2). All other fields use their default values. Other bits
+//! must not be set.
+use crate::{DleEncoder, DleError, ETX_CHAR, STX_CHAR};
+
+const MAGIC: &[u8; 4] = b"DLEV";
+const VERSION: u8 = 1;
+
+const FLAG_ESCAPE_STX_ETX: u8 = 1 << 0;
+const FLAG_ADD_STX_ETX: u8 = 1 << 1;
+const FLAG_ESCAPE_CR: u8 = 1 << 2;
+const KNOWN_FLAGS: u8 = FLAG_ESCAPE_STX_ETX | FLAG_ADD_STX_ETX | FLAG_ESCAPE_CR;
+
+static FIXTURE: &[u8] = include_bytes!("../testdata/encode_vectors.bin");
+
+#[derive(Debug, PartialEq)]
+enum FixtureError {
+    Truncated { offset: usize },
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    UnknownFlags { offset: usize, flags: u8 },
+    TrailingData { offset: usize },
+}
+
+struct TestVector<'a> {
+    encoder: DleEncoder,
+    payload: &'a [u8],
+    encoded: &'a [u8],
+}
+
+struct FixtureReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> FixtureReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], FixtureError> {
+        let bytes =
+            self.data
+                .get(self.offset..self.offset + len)
+                .ok_or(FixtureError::Truncated {
+                    offset: self.offset,
+                })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, FixtureError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, FixtureError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_block(&mut self) -> Result<&'a [u8], FixtureError> {
+        let len = self.read_u16()?;
+        self.take(len as usize)
+    }
+
+    fn read_vector(&mut self) -> Result<TestVector<'a>, FixtureError> {
+        let flags_offset = self.offset;
+        let flags = self.read_u8()?;
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(FixtureError::UnknownFlags {
+                offset: flags_offset,
+                flags,
+            });
+        }
+        let encoder = DleEncoder {
+            escape_stx_etx: flags & FLAG_ESCAPE_STX_ETX != 0,
+            add_stx_etx: flags & FLAG_ADD_STX_ETX != 0,
+            escape_cr: flags & FLAG_ESCAPE_CR != 0,
+            ..Default::default()
+        };
+        let payload = self.read_block()?;
+        let encoded = self.read_block()?;
+        Ok(TestVector {
+            encoder,
+            payload,
+            encoded,
+        })
+    }
+}
+
+fn parse_fixture(data: &[u8]) -> Result<Vec<TestVector<'_>>, FixtureError> {
+    let mut reader = FixtureReader { data, offset: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(FixtureError::InvalidMagic);
+    }
+    let version = reader.read_u8()?;
+    if version != VERSION {
+        return Err(FixtureError::UnsupportedVersion(version));
+    }
+    let count = reader.read_u16()?;
+    let vectors = (0..count)
+        .map(|_| reader.read_vector())
+        .collect::<Result<Vec<_>, _>>()?;
+    if reader.offset != data.len() {
+        return Err(FixtureError::TrailingData {
+            offset: reader.offset,
+        });
+    }
+    Ok(vectors)
+}
+
+#[test]
+fn test_encode_vectors() {
+    let vectors = parse_fixture(FIXTURE).unwrap();
+    assert!(vectors.len() >= 20);
+    for (idx, vector) in vectors.iter().enumerate() {
+        let mut encoded = vec![0; vector.encoded.len()];
+        let encoder = vector.encoder;
+        let ambiguous = !encoder.escape_stx_etx
+            && !encoder.add_stx_etx
+            && vector
+                .payload
+                .iter()
+                .any(|&byte| byte == STX_CHAR || byte == ETX_CHAR);
+        let encode_result = if ambiguous {
+            assert_eq!(
+                encoder.encode(vector.payload, &mut encoded),
+                Err(DleError::AmbiguousPayload),
+                "vector {}",
+                idx
+            );
+            encoder.encode_non_escaped(vector.payload, &mut encoded)
+        } else {
+            encoder.encode(vector.payload, &mut encoded)
+        };
+        let encoded_len =
+            encode_result.unwrap_or_else(|e| panic!("encoding vector {} failed: {:?}", idx, e));
+        assert_eq!(&encoded[..encoded_len], vector.encoded, "vector {}", idx);
+    }
+}
+
+#[test]
+fn test_parse_fixture_errors() {
+    assert_eq!(
+        parse_fixture(b"DLE").err(),
+        Some(FixtureError::Truncated { offset: 0 })
+    );
+    assert_eq!(
+        parse_fixture(b"DLEX\x01\x00\x00").err(),
+        Some(FixtureError::InvalidMagic)
+    );
+    assert_eq!(
+        parse_fixture(b"DLEV\x02\x00\x00").err(),
+        Some(FixtureError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        parse_fixture(b"DLEV\x01\x01\x00\x08\x00\x00\x00\x00").err(),
+        Some(FixtureError::UnknownFlags {
+            offset: 7,
+            flags: 0x08
+        })
+    );
+    assert_eq!(
+        parse_fixture(b"DLEV\x01\x01\x00\x03\x02\x00\x11").err(),
+        Some(FixtureError::Truncated { offset: 10 })
+    );
+    assert_eq!(
+        parse_fixture(b"DLEV\x01\x00\x00\x00").err(),
+        Some(FixtureError::TrailingData { offset: 7 })
+    );
+    assert_eq!(parse_fixture(b"DLEV\x01\x00\x00").map(|v| v.len()), Ok(0));
+}
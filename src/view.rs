@@ -0,0 +1,105 @@
+//! Bounds checked access to the fields of a decoded payload.
+
+use core::convert::TryFrom;
+
+/// Read-only view of a decoded payload, which reads fixed-size fields at byte offsets
+/// without manual slicing and endian conversion. All accessors return [None] if the field
+/// does not fit into the payload at the given offset, so malformed payloads can not cause
+/// panics.
+///
+/// # Examples
+///
+/// ```
+/// use dle_encoder::{DleEncoder, PayloadView};
+///
+/// let dle_encoder = DleEncoder::default();
+/// let mut decoding_buffer: [u8; 16] = [0; 16];
+/// let mut read_len = 0;
+/// let decoded_len = dle_encoder
+///     .decode(&[0x02, 0x07, 0x12, 0x34, 0x03], &mut decoding_buffer, &mut read_len)
+///     .unwrap();
+/// let view = PayloadView::new(&decoding_buffer[..decoded_len]);
+/// assert_eq!(view.u8_at(0), Some(0x07));
+/// assert_eq!(view.u16_be_at(1), Some(0x1234));
+/// assert_eq!(view.u16_be_at(2), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PayloadView<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> PayloadView<'a> {
+    /// Create a view of the given decoded payload
+    pub fn new(payload: &'a [u8]) -> PayloadView<'a> {
+        PayloadView { payload }
+    }
+
+    /// The complete payload of the view
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the byte at the given offset
+    pub fn u8_at(&self, offset: usize) -> Option<u8> {
+        self.payload.get(offset).copied()
+    }
+
+    /// Returns the big endian 16 bit value starting at the given offset
+    pub fn u16_be_at(&self, offset: usize) -> Option<u16> {
+        self.array_at(offset).map(u16::from_be_bytes)
+    }
+
+    /// Returns the big endian 32 bit value starting at the given offset
+    pub fn u32_be_at(&self, offset: usize) -> Option<u32> {
+        self.array_at(offset).map(u32::from_be_bytes)
+    }
+
+    fn array_at<const N: usize>(&self, offset: usize) -> Option<[u8; N]> {
+        let end = offset.checked_add(N)?;
+        <[u8; N]>::try_from(self.payload.get(offset..end)?).ok()
+    }
+}
+
+impl<'a> From<&'a [u8]> for PayloadView<'a> {
+    fn from(payload: &'a [u8]) -> PayloadView<'a> {
+        PayloadView::new(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_view() {
+        let payload = [0x01, 0x02, 0x03, 0x04, 0x05];
+        let view = PayloadView::new(&payload);
+        assert_eq!(view.payload(), &payload);
+        assert_eq!(view.u8_at(0), Some(0x01));
+        assert_eq!(view.u8_at(4), Some(0x05));
+        assert_eq!(view.u16_be_at(0), Some(0x0102));
+        assert_eq!(view.u16_be_at(3), Some(0x0405));
+        assert_eq!(view.u32_be_at(0), Some(0x0102_0304));
+        assert_eq!(view.u32_be_at(1), Some(0x0203_0405));
+
+        // Fields exceeding the payload
+        assert_eq!(view.u8_at(5), None);
+        assert_eq!(view.u16_be_at(4), None);
+        assert_eq!(view.u32_be_at(2), None);
+        assert_eq!(view.u32_be_at(usize::MAX), None);
+        assert_eq!(view.u16_be_at(usize::MAX - 1), None);
+
+        let empty = PayloadView::from(&[][..]);
+        assert_eq!(empty.u8_at(0), None);
+        assert_eq!(empty.u16_be_at(0), None);
+        assert_eq!(empty.u32_be_at(0), None);
+
+        let dle_encoder = crate::DleEncoder::default();
+        let result = dle_encoder
+            .decode_bounded::<4>(&[0x02, 0x12, 0x34, 0x56, 0x78, 0x9a, 0x03])
+            .unwrap();
+        assert!(result.spilled);
+        assert_eq!(result.view().u32_be_at(0), Some(0x1234_5678));
+        assert_eq!(result.view().u8_at(4), None);
+    }
+}
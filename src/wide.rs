@@ -0,0 +1,546 @@
+//! DLE encoding for streams of 16-bit symbols.
+//!
+//! The [DleEncoderU16] works like the [DleEncoder](crate::DleEncoder), but operates on `u16`
+//! symbols. The control symbols and the escape offset are configurable, so they can be chosen
+//! outside of the `u8` range.
+
+use crate::{ConfigError, DecodeErrorKind, DleError, DLE_CHAR, ETX_CHAR, STX_CHAR};
+
+/// This struct is used to create a DLE encoder for 16-bit symbol streams. It can also be used
+/// to configure the encoder
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DleEncoderU16 {
+    /// Configure whether the encoder uses the escaped or non-escaped mode
+    pub escape_stx_etx: bool,
+    /// Configure the encoder to not add STX and ETX symbols at the start
+    /// and end when encoding
+    pub add_stx_etx: bool,
+    /// Start of text symbol
+    pub stx: u16,
+    /// End of text symbol
+    pub etx: u16,
+    /// Data link escape symbol
+    pub dle: u16,
+    /// STX and ETX symbols are encoded as DLE followed by the symbol + this offset in the
+    /// escaped mode. The escaped symbols must not collide with any of the control symbols
+    pub escape_offset: u16,
+}
+
+impl Default for DleEncoderU16 {
+    fn default() -> DleEncoderU16 {
+        DleEncoderU16 {
+            escape_stx_etx: true,
+            add_stx_etx: true,
+            stx: STX_CHAR as u16,
+            etx: ETX_CHAR as u16,
+            dle: DLE_CHAR as u16,
+            escape_offset: 0x40,
+        }
+    }
+}
+
+impl DleEncoderU16 {
+    /// This method checks whether the control symbols are distinct and whether the escaped
+    /// forms of STX and ETX collide with any control symbol, which would break decoding.
+    /// The escaped forms are only checked in the escaped mode. This should be called after
+    /// configuring custom control symbols.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::{ConfigError, DleEncoderU16};
+    ///
+    /// let mut dle_encoder = DleEncoderU16 {
+    ///     stx: 0x30,
+    ///     etx: 0x70,
+    ///     ..Default::default()
+    /// };
+    /// // STX + 0x40 is ETX
+    /// assert_eq!(
+    ///     dle_encoder.validate_config(),
+    ///     Err(ConfigError::ControlSymbolEscape { symbol: 0x30 })
+    /// );
+    /// dle_encoder.escape_offset = 0x80;
+    /// assert_eq!(dle_encoder.validate_config(), Ok(()));
+    /// ```
+    pub fn validate_config(&self) -> Result<(), ConfigError> {
+        let control_symbols = [self.stx, self.etx, self.dle];
+        for (idx, symbol) in control_symbols.iter().enumerate() {
+            if control_symbols[idx + 1..].contains(symbol) {
+                return Err(ConfigError::DuplicateControlSymbol { symbol: *symbol });
+            }
+        }
+        if self.escape_stx_etx {
+            for &symbol in &[self.stx, self.etx] {
+                if control_symbols.contains(&symbol.wrapping_add(self.escape_offset)) {
+                    return Err(ConfigError::ControlSymbolEscape { symbol });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// This method encodes a given symbol stream with DLE encoding.
+    /// It returns the number of encoded symbols or a DLE error code.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dle_encoder::DleEncoderU16;
+    ///
+    /// let dle_encoder = DleEncoderU16 {
+    ///     stx: 0x1002,
+    ///     etx: 0x1003,
+    ///     dle: 0x1010,
+    ///     escape_offset: 0x4000,
+    ///     ..Default::default()
+    /// };
+    /// let mut encoding_buffer: [u16; 16] = [0; 16];
+    /// let example_array: [u16; 3] = [0, 0x1002, 0x1010];
+    ///
+    /// let encoded_len = dle_encoder.encode(&example_array, &mut encoding_buffer).unwrap();
+    /// assert_eq!(
+    ///     &encoding_buffer[..encoded_len],
+    ///     &[0x1002, 0, 0x1010, 0x5002, 0x1010, 0x1010, 0x1003]
+    /// );
+    /// ```
+    #[must_use = "the encoded length is required to use the destination stream"]
+    pub fn encode(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+    ) -> Result<usize, DleError> {
+        if self.escape_stx_etx {
+            self.encode_escaped(source_stream, dest_stream)
+        } else {
+            self.encode_non_escaped(source_stream, dest_stream)
+        }
+    }
+
+    /// This method encodes a given symbol stream with DLE encoding.
+    /// It explicitely does so in the escaped mode, which is the default
+    /// mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    #[must_use = "the encoded length is required to use the destination stream"]
+    pub fn encode_escaped(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+    ) -> Result<usize, DleError> {
+        let mut writer = SymbolWriter::new(dest_stream);
+        if self.add_stx_etx {
+            writer.push(&[self.stx])?;
+        }
+        for &symbol in source_stream {
+            if symbol == self.dle {
+                writer.push(&[self.dle, self.dle])?;
+            } else if symbol == self.stx || symbol == self.etx {
+                writer.push(&[self.dle, symbol.wrapping_add(self.escape_offset)])?;
+            } else {
+                writer.push(&[symbol])?;
+            }
+        }
+        if self.add_stx_etx {
+            writer.push(&[self.etx])?;
+        }
+        Ok(writer.idx)
+    }
+
+    /// This method encodes a given symbol stream with DLE encoding.
+    /// It explicitely does so in the non-escaped mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to encode
+    /// * `dest_stream` - Encoded stream will be written here
+    #[must_use = "the encoded length is required to use the destination stream"]
+    pub fn encode_non_escaped(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+    ) -> Result<usize, DleError> {
+        let mut writer = SymbolWriter::new(dest_stream);
+        if self.add_stx_etx {
+            writer.push(&[self.dle, self.stx])?;
+        }
+        for &symbol in source_stream {
+            if symbol == self.dle {
+                writer.push(&[self.dle, self.dle])?;
+            } else {
+                writer.push(&[symbol])?;
+            }
+        }
+        if self.add_stx_etx {
+            writer.push(&[self.dle, self.etx])?;
+        }
+        Ok(writer.idx)
+    }
+
+    /// This method decodes a given symbol stream which was encoded with a DLE encoder.
+    /// It returns the length of the decoded buffer or an error code if
+    /// there is a decoder failure or the destination stream is too short.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read symbols in the source stream will be
+    ///   assigned to this variable
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        if self.escape_stx_etx {
+            self.decode_escaped(source_stream, dest_stream, read_len)
+        } else {
+            self.decode_non_escaped(source_stream, dest_stream, read_len)
+        }
+    }
+
+    /// This method decodes a given symbol stream which was encoded with a DLE encoder.
+    /// It explicitely does so in the escaped mode, which is the default mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read symbols in the source stream will be
+    ///   assigned to this variable
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_escaped(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        *read_len = 0;
+        if source_stream.first() != Some(&self.stx) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        let mut encoded_idx = 1;
+        let mut decoded_idx = 0;
+        while let Some(&symbol) = source_stream.get(encoded_idx) {
+            let decoded = if symbol == self.etx {
+                *read_len = encoded_idx + 1;
+                return Ok(decoded_idx);
+            } else if symbol == self.stx {
+                // Start of a new frame, which should be preserved
+                *read_len = encoded_idx;
+                return Err(DleError::decoding(
+                    DecodeErrorKind::UnexpectedStart,
+                    encoded_idx,
+                ));
+            } else if symbol == self.dle {
+                let next_symbol = match source_stream.get(encoded_idx + 1) {
+                    Some(&next_symbol) => next_symbol,
+                    None => break,
+                };
+                encoded_idx += 1;
+                if next_symbol == self.dle {
+                    self.dle
+                } else if next_symbol == self.stx.wrapping_add(self.escape_offset)
+                    || next_symbol == self.etx.wrapping_add(self.escape_offset)
+                {
+                    next_symbol.wrapping_sub(self.escape_offset)
+                } else {
+                    *read_len = encoded_idx + 1;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::InvalidDleSequence {
+                            found: low_byte(next_symbol),
+                        },
+                        encoded_idx,
+                    ));
+                }
+            } else {
+                symbol
+            };
+            match dest_stream.get_mut(decoded_idx) {
+                Some(dest) => *dest = decoded,
+                None => return Err(DleError::StreamTooShort),
+            }
+            decoded_idx += 1;
+            encoded_idx += 1;
+        }
+        *read_len = source_stream.len();
+        Err(DleError::decoding(
+            DecodeErrorKind::MissingEnd,
+            source_stream.len(),
+        ))
+    }
+
+    /// This method decodes a given symbol stream which was encoded with a DLE encoder.
+    /// It explicitely does so in the non-escaped mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_stream` - The stream to decode
+    /// * `dest_stream` - Decoded stream will be written here
+    /// * `read_len` - The number of read symbols in the source stream will be
+    ///   assigned to this variable
+    #[must_use = "the decoded length is required to use the destination stream"]
+    pub fn decode_non_escaped(
+        &self,
+        source_stream: &[u16],
+        dest_stream: &mut [u16],
+        read_len: &mut usize,
+    ) -> Result<usize, DleError> {
+        *read_len = 0;
+        if source_stream.first() != Some(&self.dle) {
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 0));
+        }
+        if source_stream.get(1) != Some(&self.stx) {
+            *read_len = 1;
+            return Err(DleError::decoding(DecodeErrorKind::MissingStart, 1));
+        }
+        let mut encoded_idx = 2;
+        let mut decoded_idx = 0;
+        while let Some(&symbol) = source_stream.get(encoded_idx) {
+            if symbol == self.dle {
+                let next_symbol = match source_stream.get(encoded_idx + 1) {
+                    Some(&next_symbol) => next_symbol,
+                    None => break,
+                };
+                if next_symbol == self.etx {
+                    *read_len = encoded_idx + 2;
+                    return Ok(decoded_idx);
+                } else if next_symbol == self.stx {
+                    // Set read_len so the DLE/STX combination is preserved, it could be the
+                    // start of another frame
+                    *read_len = encoded_idx;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::UnexpectedStart,
+                        encoded_idx,
+                    ));
+                } else if next_symbol != self.dle {
+                    *read_len = encoded_idx;
+                    return Err(DleError::decoding(
+                        DecodeErrorKind::InvalidDleSequence {
+                            found: low_byte(next_symbol),
+                        },
+                        encoded_idx + 1,
+                    ));
+                }
+                encoded_idx += 1;
+            }
+            match dest_stream.get_mut(decoded_idx) {
+                Some(dest) => *dest = symbol,
+                None => return Err(DleError::StreamTooShort),
+            }
+            decoded_idx += 1;
+            encoded_idx += 1;
+        }
+        *read_len = encoded_idx;
+        Err(DleError::decoding(
+            DecodeErrorKind::MissingEnd,
+            source_stream.len(),
+        ))
+    }
+}
+
+/// The low byte of a symbol, which is reported as the found byte of a
+/// [DecodeErrorKind::InvalidDleSequence]
+fn low_byte(symbol: u16) -> u8 {
+    let [low, _] = symbol.to_le_bytes();
+    low
+}
+
+/// Helper to write symbols into a destination stream with bounds checking
+struct SymbolWriter<'a> {
+    dest_stream: &'a mut [u16],
+    idx: usize,
+}
+
+impl<'a> SymbolWriter<'a> {
+    fn new(dest_stream: &'a mut [u16]) -> Self {
+        SymbolWriter {
+            dest_stream,
+            idx: 0,
+        }
+    }
+
+    fn push(&mut self, symbols: &[u16]) -> Result<(), DleError> {
+        let end = self.idx + symbols.len();
+        if end > self.dest_stream.len() {
+            return Err(DleError::StreamTooShort);
+        }
+        self.dest_stream[self.idx..end].copy_from_slice(symbols);
+        self.idx = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDE_STX: u16 = 0x0102;
+    const WIDE_ETX: u16 = 0x0103;
+    const WIDE_DLE: u16 = 0x0110;
+
+    fn wide_encoder(escape_stx_etx: bool) -> DleEncoderU16 {
+        DleEncoderU16 {
+            escape_stx_etx,
+            stx: WIDE_STX,
+            etx: WIDE_ETX,
+            dle: WIDE_DLE,
+            escape_offset: 0x4000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_wide_roundtrip() {
+        let payloads: [&[u16]; 5] = [
+            &[],
+            &[0, 0xffff, 0x1234],
+            &[WIDE_STX, WIDE_ETX, WIDE_DLE],
+            // The u8 control characters are regular data for this configuration
+            &[STX_CHAR as u16, ETX_CHAR as u16, DLE_CHAR as u16],
+            &[WIDE_DLE, WIDE_DLE, 5, WIDE_STX, WIDE_STX + 0x4000],
+        ];
+        let mut encoded: [u16; 32] = [0; 32];
+        let mut decoded: [u16; 32] = [0; 32];
+        for escape_stx_etx in [true, false] {
+            let dle_encoder = wide_encoder(escape_stx_etx);
+            for payload in payloads.iter() {
+                let encoded_len = dle_encoder.encode(payload, &mut encoded).unwrap();
+                let encoded = &encoded[..encoded_len];
+                if escape_stx_etx {
+                    // STX and ETX only appear as frame delimiters
+                    assert_eq!(encoded.iter().filter(|&&s| s == WIDE_STX).count(), 1);
+                    assert_eq!(encoded.iter().filter(|&&s| s == WIDE_ETX).count(), 1);
+                }
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(encoded, &mut decoded, &mut read_len)
+                    .unwrap();
+                assert_eq!(read_len, encoded_len);
+                assert_eq!(&decoded[..decoded_len], *payload);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_escaped_collisions() {
+        let dle_encoder = wide_encoder(true);
+        let mut encoded: [u16; 16] = [0; 16];
+        let encoded_len = dle_encoder
+            .encode(&[WIDE_STX, 7, WIDE_ETX, WIDE_DLE], &mut encoded)
+            .unwrap();
+        assert_eq!(
+            &encoded[..encoded_len],
+            &[
+                WIDE_STX,
+                WIDE_DLE,
+                WIDE_STX + 0x4000,
+                7,
+                WIDE_DLE,
+                WIDE_ETX + 0x4000,
+                WIDE_DLE,
+                WIDE_DLE,
+                WIDE_ETX
+            ]
+        );
+
+        let mut decoded: [u16; 16] = [0; 16];
+        let mut read_len = 0;
+        let faulty = [WIDE_STX, WIDE_DLE, 0x1234, WIDE_ETX];
+        assert_eq!(
+            dle_encoder.decode(&faulty, &mut decoded, &mut read_len),
+            Err(DleError::DecodingError {
+                kind: DecodeErrorKind::InvalidDleSequence { found: 0x34 },
+                offset: 2
+            })
+        );
+        assert_eq!(read_len, 3);
+    }
+
+    #[test]
+    fn test_wide_stream_too_short() {
+        let payload = [1, WIDE_STX, 2];
+        for escape_stx_etx in [true, false] {
+            let dle_encoder = wide_encoder(escape_stx_etx);
+            let mut encoded: [u16; 16] = [0; 16];
+            let encoded_len = dle_encoder.encode(&payload, &mut encoded).unwrap();
+            for faulty_dest_size in 0..encoded_len {
+                assert_eq!(
+                    dle_encoder.encode(&payload, &mut encoded[..faulty_dest_size]),
+                    Err(DleError::StreamTooShort)
+                );
+            }
+            let mut decoded: [u16; 2] = [0; 2];
+            let mut read_len = 0;
+            assert_eq!(
+                dle_encoder.decode(&encoded[..encoded_len], &mut decoded, &mut read_len),
+                Err(DleError::StreamTooShort)
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_validate_config() {
+        assert_eq!(DleEncoderU16::default().validate_config(), Ok(()));
+        assert_eq!(wide_encoder(true).validate_config(), Ok(()));
+        let safe = DleEncoderU16 {
+            stx: 0x30,
+            etx: 0x70,
+            escape_offset: 0x80,
+            ..Default::default()
+        };
+        assert_eq!(safe.validate_config(), Ok(()));
+
+        let colliding = DleEncoderU16 {
+            escape_offset: 0x40,
+            ..safe
+        };
+        assert_eq!(
+            colliding.validate_config(),
+            Err(ConfigError::ControlSymbolEscape { symbol: 0x30 })
+        );
+        // The escaped forms are irrelevant in the non-escaped mode
+        let non_escaped = DleEncoderU16 {
+            escape_stx_etx: false,
+            ..colliding
+        };
+        assert_eq!(non_escaped.validate_config(), Ok(()));
+        let escaped_to_dle = DleEncoderU16 {
+            etx: 0x20,
+            dle: 0x60,
+            escape_offset: 0x40,
+            ..safe
+        };
+        assert_eq!(
+            escaped_to_dle.validate_config(),
+            Err(ConfigError::ControlSymbolEscape { symbol: 0x20 })
+        );
+        let escaped_to_itself = DleEncoderU16 {
+            escape_offset: 0,
+            ..safe
+        };
+        assert_eq!(
+            escaped_to_itself.validate_config(),
+            Err(ConfigError::ControlSymbolEscape { symbol: 0x30 })
+        );
+        let duplicate = DleEncoderU16 {
+            dle: 0x30,
+            escape_stx_etx: false,
+            ..safe
+        };
+        assert_eq!(
+            duplicate.validate_config(),
+            Err(ConfigError::DuplicateControlSymbol { symbol: 0x30 })
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Building a frame from formatted text with the [core::fmt::Write] macros.
+
+use crate::{write_bytes, DleEncoder, DleError, DLE_CHAR, SYN_CHAR};
+use core::fmt;
+
+/// Writer which encodes formatted text into a single frame in a destination stream, so a
+/// frame can be built with the [write!] and [writeln!] macros. The bytes of the text are
+/// escaped as they are written. The frame is completed with [DleFrameWriter::into_frame],
+/// which returns the same frame as encoding the complete text with [DleEncoder::encode].
+/// If the destination stream is too short, the write fails with [fmt::Error] and the cause
+/// is returned by [DleFrameWriter::into_frame].
+///
+/// # Examples
+///
+/// ```
+/// use core::fmt::Write;
+/// use dle_encoder::{DleEncoder, DleFrameWriter};
+///
+/// let mut encoding_buffer: [u8; 16] = [0; 16];
+/// let mut frame_writer = DleFrameWriter::new(DleEncoder::default(), &mut encoding_buffer);
+/// write!(frame_writer, "ST {}", 2).unwrap();
+/// let frame = frame_writer.into_frame().unwrap();
+/// assert_eq!(frame, &[0x02, b'S', b'T', b' ', b'2', 0x03]);
+/// ```
+pub struct DleFrameWriter<'a> {
+    encoder: DleEncoder,
+    dest_stream: &'a mut [u8],
+    /// Number of written bytes of the frame
+    encoded_len: usize,
+    /// Number of text bytes written to the frame
+    source_len: usize,
+    /// First error of a write, which is returned when the frame is completed
+    error: Option<DleError>,
+}
+
+impl<'a> DleFrameWriter<'a> {
+    /// Create a new frame writer using the given encoder configuration, which writes the
+    /// frame to the given destination stream
+    pub fn new(encoder: DleEncoder, dest_stream: &'a mut [u8]) -> DleFrameWriter<'a> {
+        DleFrameWriter {
+            encoder,
+            dest_stream,
+            encoded_len: 0,
+            source_len: 0,
+            error: None,
+        }
+    }
+
+    /// Number of bytes of the frame which were written so far, excluding the end marker
+    /// and padding
+    pub fn encoded_len(&self) -> usize {
+        self.encoded_len
+    }
+
+    /// Complete the frame and return it. The end marker and padding are added as
+    /// configured. Returns the first error of the writes, for example
+    /// [DleError::StreamTooShort] if the text did not fit into the destination stream.
+    pub fn into_frame(self) -> Result<&'a [u8], DleError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let encoded_len = if self.source_len == 0 {
+            // Empty payloads are encoded as the idle sequence if one is configured
+            self.encoder.encode(&[], self.dest_stream)?
+        } else {
+            let encoded_len = if self.encoder.add_stx_etx {
+                write_bytes(
+                    self.dest_stream,
+                    self.encoded_len,
+                    self.encoder.end_marker(),
+                )?
+            } else {
+                self.encoded_len
+            };
+            self.encoder.pad(self.dest_stream, encoded_len)?
+        };
+        self.dest_stream
+            .get(..encoded_len)
+            .ok_or(DleError::StreamTooShort)
+    }
+
+    fn write_encoded(&mut self, bytes: &[u8]) -> Result<(), DleError> {
+        self.encoder.check_unambiguous(bytes)?;
+        for &byte in bytes {
+            if self.source_len == 0 && self.encoder.add_stx_etx {
+                self.encoded_len = write_bytes(self.dest_stream, 0, self.encoder.start_marker())?;
+            }
+            if self.encoder.resync_due(self.source_len) {
+                self.encoded_len =
+                    write_bytes(self.dest_stream, self.encoded_len, &[DLE_CHAR, SYN_CHAR])?;
+            }
+            let (encoded, encoded_len) = self.encoder.encode_byte(byte);
+            self.encoded_len = write_bytes(
+                self.dest_stream,
+                self.encoded_len,
+                encoded.get(..encoded_len).unwrap_or_default(),
+            )?;
+            self.source_len += 1;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Write for DleFrameWriter<'_> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        if self.error.is_some() {
+            return Err(fmt::Error);
+        }
+        self.write_encoded(text.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::STX_CHAR;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_dle_frame_writer() {
+        let configs = [
+            DleEncoder::default(),
+            DleEncoder::non_escaped(),
+            DleEncoder::raw_escaped_no_framing(),
+            DleEncoder {
+                resync_interval: Some(2),
+                pad_to: Some((24, 0x55)),
+                ..Default::default()
+            },
+        ];
+        let code = 0x10;
+        let mut expected: [u8; 32] = [0; 32];
+        for dle_encoder in configs.iter() {
+            let mut encoded: [u8; 32] = [0; 32];
+            let mut frame_writer = DleFrameWriter::new(*dle_encoder, &mut encoded);
+            write!(frame_writer, "STATUS {}", code).unwrap();
+            writeln!(frame_writer, "{}", char::from(DLE_CHAR)).unwrap();
+            let frame = frame_writer.into_frame().unwrap();
+            let expected_len = dle_encoder
+                .encode(b"STATUS 16\x10\n", &mut expected)
+                .unwrap();
+            assert_eq!(frame, &expected[..expected_len]);
+
+            if dle_encoder.add_stx_etx {
+                let mut decoded: [u8; 32] = [0; 32];
+                let mut read_len = 0;
+                let decoded_len = dle_encoder
+                    .decode(frame, &mut decoded, &mut read_len)
+                    .unwrap();
+                let text = core::str::from_utf8(&decoded[..decoded_len]).unwrap();
+                assert_eq!(text, "STATUS 16\x10\n");
+            }
+
+            // Empty frames
+            let mut encoded: [u8; 32] = [0; 32];
+            let frame = DleFrameWriter::new(*dle_encoder, &mut encoded)
+                .into_frame()
+                .unwrap();
+            let expected_len = dle_encoder.encode(&[], &mut expected).unwrap();
+            assert_eq!(frame, &expected[..expected_len]);
+        }
+    }
+
+    #[test]
+    fn test_dle_frame_writer_errors() {
+        let mut encoded: [u8; 4] = [0; 4];
+        let mut frame_writer = DleFrameWriter::new(DleEncoder::default(), &mut encoded);
+        write!(frame_writer, "ab").unwrap();
+        assert_eq!(frame_writer.encoded_len(), 3);
+        assert!(write!(frame_writer, "cd").is_err());
+        // Later writes fail as well, so no bytes are lost inside the frame
+        assert!(write!(frame_writer, "e").is_err());
+        assert_eq!(frame_writer.into_frame(), Err(DleError::StreamTooShort));
+
+        // The end marker does not fit
+        let mut frame_writer = DleFrameWriter::new(DleEncoder::default(), &mut encoded);
+        write!(frame_writer, "abc").unwrap();
+        assert_eq!(frame_writer.into_frame(), Err(DleError::StreamTooShort));
+
+        let dle_encoder = DleEncoder {
+            add_stx_etx: false,
+            ..DleEncoder::non_escaped()
+        };
+        let mut frame_writer = DleFrameWriter::new(dle_encoder, &mut encoded);
+        assert!(write!(frame_writer, "{}", char::from(STX_CHAR)).is_err());
+        assert_eq!(frame_writer.into_frame(), Err(DleError::AmbiguousPayload));
+    }
+}
@@ -0,0 +1,94 @@
+#!/usr/bin/env python3
+"""Generates the encoder regression vectors in encode_vectors.bin.
+
+The format is described in src/vectors.rs. The encoder below is an independent
+reference implementation of the DLE encoding so the vectors do not simply pin
+the output of the Rust encoder.
+
+Usage: python3 testdata/gen_encode_vectors.py
+"""
+import os
+import struct
+
+STX = 0x02
+ETX = 0x03
+DLE = 0x10
+CR = 0x0D
+
+ESCAPE_STX_ETX = 1 << 0
+ADD_STX_ETX = 1 << 1
+ESCAPE_CR = 1 << 2
+
+
+def encode(flags, payload):
+    out = bytearray()
+    if flags & ESCAPE_STX_ETX:
+        escaped = {STX, ETX}
+        if flags & ESCAPE_CR:
+            escaped.add(CR)
+        if flags & ADD_STX_ETX:
+            out.append(STX)
+        for byte in payload:
+            if byte == DLE:
+                out += bytes([DLE, DLE])
+            elif byte in escaped:
+                out += bytes([DLE, (byte + 0x40) & 0xFF])
+            else:
+                out.append(byte)
+        if flags & ADD_STX_ETX:
+            out.append(ETX)
+    else:
+        if flags & ADD_STX_ETX:
+            out += bytes([DLE, STX])
+        for byte in payload:
+            if byte == DLE:
+                out += bytes([DLE, DLE])
+            else:
+                out.append(byte)
+        if flags & ADD_STX_ETX:
+            out += bytes([DLE, ETX])
+    return bytes(out)
+
+
+PAYLOADS = [
+    b"",
+    bytes([0x00]),
+    bytes([STX]),
+    bytes([ETX]),
+    bytes([DLE]),
+    bytes([CR]),
+    bytes([DLE, DLE, DLE]),
+    bytes([STX, ETX, DLE, CR]),
+    bytes([0x42, 0x43, 0x4D, 0x50]),
+    bytes([0x11, DLE, STX, 0x22, ETX, 0x33, DLE]),
+    bytes([0xC2, 0xC3, 0xD0, 0xFF]),
+    bytes(range(256)),
+    bytes([DLE] * 300),
+]
+
+CONFIGS = [
+    ESCAPE_STX_ETX | ADD_STX_ETX,
+    ESCAPE_STX_ETX | ADD_STX_ETX | ESCAPE_CR,
+    ESCAPE_STX_ETX,
+    ADD_STX_ETX,
+    0,
+]
+
+
+def main():
+    vectors = []
+    for flags in CONFIGS:
+        for payload in PAYLOADS:
+            vectors.append((flags, payload, encode(flags, payload)))
+    out = bytearray(b"DLEV")
+    out += struct.pack("<BH", 1, len(vectors))
+    for flags, payload, encoded in vectors:
+        out += struct.pack("<BH", flags, len(payload)) + payload
+        out += struct.pack("<H", len(encoded)) + encoded
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "encode_vectors.bin")
+    with open(path, "wb") as f:
+        f.write(out)
+
+
+if __name__ == "__main__":
+    main()